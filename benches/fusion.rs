@@ -0,0 +1,60 @@
+// A long map/filter chain built with the fusing inherent methods, against the
+// same chain built through the Observable trait, which adds an observer per
+// operator. Run with cargo bench --bench fusion.
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rx::prelude::*;
+
+const ITEMS: u64 = 10_000_000;
+
+fn sink(total: Arc<Mutex<u64>>) -> impl Observer<Item=u64> + Send + Sync + 'static {
+    let mut sum = 0u64;
+    FnObserver::new(move |x: u64| sum = sum.wrapping_add(x))
+        .on_completed(move || *total.lock().unwrap() = sum)
+}
+
+fn fused() -> (Duration, u64) {
+    let total = Arc::new(Mutex::new(0));
+    let start = Instant::now();
+    range(0, ITEMS)
+        .map(|x| x.wrapping_mul(3))
+        .filter(|x| x % 7 != 0)
+        .map(|x| x ^ 0x55)
+        .map(|x| x.wrapping_add(11))
+        .filter(|x| x % 5 != 0)
+        .map(|x| x >> 1)
+        .map(|x| x.wrapping_mul(13))
+        .filter(|x| x & 1 == 0)
+        .subscribe(sink(total.clone()));
+    let elapsed = start.elapsed();
+    let total = *total.lock().unwrap();
+    (elapsed, total)
+}
+
+fn unfused() -> (Duration, u64) {
+    let total = Arc::new(Mutex::new(0));
+    let start = Instant::now();
+    let source = Observable::map(range(0, ITEMS), |x| x.wrapping_mul(3));
+    let source = Observable::filter(source, |x| x % 7 != 0);
+    let source = Observable::map(source, |x| x ^ 0x55);
+    let source = Observable::map(source, |x| x.wrapping_add(11));
+    let source = Observable::filter(source, |x| x % 5 != 0);
+    let source = Observable::map(source, |x| x >> 1);
+    let source = Observable::map(source, |x| x.wrapping_mul(13));
+    let source = Observable::filter(source, |x| x & 1 == 0);
+    source.subscribe(sink(total.clone()));
+    let elapsed = start.elapsed();
+    let total = *total.lock().unwrap();
+    (elapsed, total)
+}
+
+fn main() {
+    // Best of a few runs, after a warm up
+    fused();
+    unfused();
+    let (fused, a) = (0..5).map(|_| fused()).min().unwrap();
+    let (unfused, b) = (0..5).map(|_| unfused()).min().unwrap();
+    assert_eq!(a, b);
+    println!("{} items, 8 stages: fused {:?}, unfused {:?}", ITEMS, fused, unfused);
+}
@@ -0,0 +1,101 @@
+// Merging inner observables that emit from several threads at once, against
+// the same sources forwarded through a single Arc<Mutex<observer>>, which is
+// how merge used to serialize them. Run with cargo bench --bench merge.
+#![allow(clippy::redundant_field_names)]
+
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rx::prelude::*;
+
+const ITEMS: usize = 200_000;
+
+// Spins a little per item, standing in for work a producer does between
+// emissions
+fn produce(i: usize) -> usize {
+    let mut x = i;
+    for _ in 0..50 {
+        x = x.wrapping_mul(31).wrapping_add(7);
+    }
+    x
+}
+
+struct Counter {
+    count: usize,
+    done: mpsc::Sender<usize>
+}
+
+impl Observer for Counter {
+    type Item = usize;
+
+    fn next(&mut self, _: usize) -> IterationResult {
+        self.count += 1;
+        Continue
+    }
+
+    fn completed(&mut self) {
+        let _ = self.done.send(self.count);
+    }
+
+    fn error(&mut self, _: Error) {}
+}
+
+// Forwards to an observer shared behind a lock
+struct Locked(Arc<Mutex<Counter>>, Arc<Mutex<usize>>);
+
+impl Observer for Locked {
+    type Item = usize;
+
+    fn next(&mut self, val: usize) -> IterationResult {
+        self.0.lock().unwrap().next(val)
+    }
+
+    fn completed(&mut self) {
+        let mut remaining = self.1.lock().unwrap();
+        *remaining -= 1;
+        if *remaining == 0 {
+            self.0.lock().unwrap().completed();
+        }
+    }
+
+    fn error(&mut self, _: Error) {}
+}
+
+fn run_merge(threads: usize) -> Duration {
+    let (done, finished) = mpsc::channel();
+    let pool = NewThreadScheduler::new();
+    let start = Instant::now();
+    range(0, threads)
+        .flat_map(move |_| range(0, ITEMS / threads).map(produce).subscribe_on(pool.clone()))
+        .subscribe(Counter {count: 0, done: done});
+    assert_eq!(finished.recv().unwrap(), ITEMS / threads * threads);
+    start.elapsed()
+}
+
+fn run_locked(threads: usize) -> Duration {
+    let (done, finished) = mpsc::channel();
+    let observer = Arc::new(Mutex::new(Counter {count: 0, done: done}));
+    let remaining = Arc::new(Mutex::new(threads));
+    let pool = NewThreadScheduler::new();
+    let start = Instant::now();
+    for _ in 0..threads {
+        range(0, ITEMS / threads).map(produce).subscribe_on(pool.clone())
+            .subscribe(Locked(observer.clone(), remaining.clone()));
+    }
+    assert_eq!(finished.recv().unwrap(), ITEMS / threads * threads);
+    start.elapsed()
+}
+
+fn main() {
+    let cores = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    println!("{} items, {} cores", ITEMS, cores);
+    for threads in [1, 2, 4, 8] {
+        // Best of a few runs, after a warm up
+        run_merge(threads);
+        run_locked(threads);
+        let merge = (0..5).map(|_| run_merge(threads)).min().unwrap();
+        let locked = (0..5).map(|_| run_locked(threads)).min().unwrap();
+        println!("{} threads: merge {:?}, locked {:?}", threads, merge, locked);
+    }
+}
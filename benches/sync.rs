@@ -0,0 +1,81 @@
+// Synchronous chains against the same pipelines written with std::iter:
+// pushed through observers with subscribe, and lowered to a pull loop with
+// Pull. Run with cargo bench --bench sync.
+use std::sync::{Arc, Mutex};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use rx::prelude::*;
+
+const ITEMS: u64 = 1_000_000;
+
+fn chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("range_map_filter_take_fold");
+
+    group.bench_function("std_iter", |b| b.iter(|| {
+        (0..black_box(ITEMS))
+            .map(|x| x.wrapping_mul(3))
+            .filter(|x| x % 7 != 0)
+            .take(ITEMS as usize / 2)
+            .fold(0u64, |acc, x| acc.wrapping_add(x))
+    }));
+
+    group.bench_function("subscribe", |b| b.iter(|| {
+        let total = Arc::new(Mutex::new(0u64));
+        let result = total.clone();
+        let mut sum = 0u64;
+        range(0, black_box(ITEMS))
+            .map(|x| x.wrapping_mul(3))
+            .filter(|x| x % 7 != 0)
+            .take(ITEMS as usize / 2)
+            .subscribe(FnObserver::new(move |x: u64| sum = sum.wrapping_add(x))
+                .on_completed(move || *result.lock().unwrap() = sum));
+        let total = *total.lock().unwrap();
+        total
+    }));
+
+    group.bench_function("pull", |b| b.iter(|| {
+        range(0, black_box(ITEMS))
+            .map(|x| x.wrapping_mul(3))
+            .filter(|x| x % 7 != 0)
+            .take(ITEMS as usize / 2)
+            .fold(0u64, |acc, x| acc.wrapping_add(x))
+    }));
+
+    group.finish();
+}
+
+fn long_chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eight_stages_fold");
+
+    group.bench_function("std_iter", |b| b.iter(|| {
+        (0..black_box(ITEMS))
+            .map(|x| x.wrapping_mul(3))
+            .filter(|x| x % 7 != 0)
+            .map(|x| x ^ 0x55)
+            .map(|x| x.wrapping_add(11))
+            .filter(|x| x % 5 != 0)
+            .map(|x| x >> 1)
+            .map(|x| x.wrapping_mul(13))
+            .filter(|x| x & 1 == 0)
+            .fold(0u64, |acc, x| acc.wrapping_add(x))
+    }));
+
+    group.bench_function("pull", |b| b.iter(|| {
+        range(0, black_box(ITEMS))
+            .map(|x| x.wrapping_mul(3))
+            .filter(|x| x % 7 != 0)
+            .map(|x| x ^ 0x55)
+            .map(|x| x.wrapping_add(11))
+            .filter(|x| x % 5 != 0)
+            .map(|x| x >> 1)
+            .map(|x| x.wrapping_mul(13))
+            .filter(|x| x & 1 == 0)
+            .fold(0u64, |acc, x| acc.wrapping_add(x))
+    }));
+
+    group.finish();
+}
+
+criterion_group!(benches, chain, long_chain);
+criterion_main!(benches);
@@ -0,0 +1,14 @@
+use rx::prelude::*;
+
+fn main() {
+    range(0, 10).
+        flat_map(|a| value(a * 10)).
+        take(3).
+        map(|a| a + 5).
+        subscribe_with(
+            |a| println!("Got {}", a),
+            |err| println!("Called error: {}", err),
+            || println!("Called completed"));
+
+    range(0, 3).subscribe(FnObserver::new(|a| println!("Got {}", a)).on_completed(|| println!("Done")));
+}
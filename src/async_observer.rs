@@ -0,0 +1,231 @@
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::pin;
+
+use crate::flow::{FlowSubscription, Subscriber};
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Continue;
+use crate::stream::block_on_poll;
+
+// An observer whose next finishes asynchronously, e.g. a database writer.
+// The source isn't handed another item until the last one's future has
+// resolved, so a slow consumer holds the source back rather than piling up
+// work.
+pub trait AsyncObserver<E = Error> {
+    type Item;
+
+    fn next(&mut self, val: Self::Item) -> impl Future<Output=IterationResult> + Send;
+    fn completed(&mut self);
+    fn error(&mut self, err: E);
+}
+
+// An async observer from a closure returning a future, e.g.
+// AsyncFnObserver::new(|val| async move { ... }). Completion and errors are
+// ignored.
+pub struct AsyncFnObserver<A, N, E = Error> {
+    pub(crate) next: N,
+    pub(crate) _marker: PhantomData<fn(A, E)>
+}
+
+impl<A, N, U, E> AsyncFnObserver<A, N, E>
+    where N: FnMut(A) -> U,
+          U: Future<Output=()> + Send {
+    pub fn new(next: N) -> AsyncFnObserver<A, N, E> {
+        AsyncFnObserver {next: next, _marker: PhantomData}
+    }
+}
+
+impl<A, N, U, E> AsyncObserver<E> for AsyncFnObserver<A, N, E>
+    where N: FnMut(A) -> U,
+          U: Future<Output=()> + Send {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> impl Future<Output=IterationResult> + Send {
+        let next = (self.next)(val);
+        async move {
+            next.await;
+            Continue
+        }
+    }
+
+    fn completed(&mut self) {}
+
+    fn error(&mut self, _: E) {}
+}
+
+// Runs an async observer as a plain one. With nothing here to spawn onto,
+// each future is polled to completion on the thread that delivered the item,
+// which is what keeps the source from getting ahead. As a subscriber it also
+// only requests the next item once the last has been handled.
+pub(crate) struct AsyncAdapter<N> {
+    pub(crate) observer: N,
+    pub(crate) subscription: Option<FlowSubscription>
+}
+
+impl<N> AsyncAdapter<N> {
+    pub(crate) fn new(observer: N) -> AsyncAdapter<N> {
+        AsyncAdapter {observer: observer, subscription: None}
+    }
+}
+
+impl<N, E> Observer<E> for AsyncAdapter<N>
+    where N: AsyncObserver<E> {
+    type Item = N::Item;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut next = pin!(self.observer.next(val));
+        let result = block_on_poll(|cx| next.as_mut().poll(cx));
+        if let (Continue, Some(subscription)) = (&result, &self.subscription) {
+            subscription.request(1);
+        }
+        result
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
+
+impl<N> Subscriber for AsyncAdapter<N>
+    where N: AsyncObserver {
+    fn on_subscribe(&mut self, subscription: FlowSubscription) {
+        self.subscription = Some(subscription.clone());
+        subscription.request(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::task::{Context, Poll};
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::async_observer::{AsyncObserver, AsyncFnObserver};
+    use crate::flow::Flowable;
+    use crate::observable::Observable;
+    use crate::observer::{Error, IterationResult};
+    use crate::observer::IterationResult::{Continue, Stop};
+    use crate::sources::{from_iter, range, throw};
+    use crate::testing::marbles::MarbleError;
+
+    // Resolves a little later, from another thread
+    struct Later {
+        done: Arc<AtomicBool>,
+        started: bool
+    }
+
+    fn later() -> Later {
+        Later {done: Arc::new(AtomicBool::new(false)), started: false}
+    }
+
+    impl Future for Later {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+            if self.done.load(Ordering::SeqCst) {
+                return Poll::Ready(());
+            }
+            if !self.started {
+                self.started = true;
+                let done = self.done.clone();
+                let waker = cx.waker().clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(5));
+                    done.store(true, Ordering::SeqCst);
+                    waker.wake();
+                });
+            }
+            Poll::Pending
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        log: Arc<Mutex<Vec<String>>>
+    }
+
+    impl AsyncObserver for Recorder {
+        type Item = i32;
+
+        fn next(&mut self, val: Self::Item) -> impl Future<Output=IterationResult> + Send {
+            let log = self.log.clone();
+            async move {
+                later().await;
+                log.lock().unwrap().push(val.to_string());
+                if val < 2 { Continue } else { Stop }
+            }
+        }
+
+        fn completed(&mut self) {
+            self.log.lock().unwrap().push("|".to_string());
+        }
+
+        fn error(&mut self, _: Error) {
+            self.log.lock().unwrap().push("#".to_string());
+        }
+    }
+
+    #[test]
+    fn subscribe_async_holds_the_source_until_each_future_resolves() {
+        let emitted = Arc::new(AtomicUsize::new(0));
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let counter = emitted.clone();
+        let seen = handled.clone();
+        range(0, 4)
+            .tap(move |_| { counter.fetch_add(1, Ordering::SeqCst); })
+            .subscribe_async_fn(move |val| {
+                let emitted = emitted.clone();
+                let seen = seen.clone();
+                async move {
+                    later().await;
+                    seen.lock().unwrap().push((val, emitted.load(Ordering::SeqCst)));
+                }
+            });
+        assert_eq!(*handled.lock().unwrap(), vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+    }
+
+    #[test]
+    fn subscribe_flow_async_requests_each_item_once_the_last_is_handled() {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let counter = pulled.clone();
+        let seen = handled.clone();
+        let observer = AsyncFnObserver::new(move |val: i32| {
+            let pulled = pulled.clone();
+            let seen = seen.clone();
+            async move {
+                later().await;
+                seen.lock().unwrap().push((val, pulled.load(Ordering::SeqCst)));
+            }
+        });
+        from_iter((0..3).inspect(move |_| { counter.fetch_add(1, Ordering::SeqCst); }))
+            .subscribe_flow_async(observer);
+        assert_eq!(*handled.lock().unwrap(), vec![(0, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn an_async_observer_can_stop_the_source() {
+        let observer = Recorder::default();
+        range(0, 10).subscribe_async(observer.clone());
+        assert_eq!(*observer.log.lock().unwrap(), vec!["0", "1", "2", "|"]);
+    }
+
+    #[test]
+    fn an_async_observer_sees_completion_and_errors() {
+        let observer = Recorder::default();
+        range(0, 2).subscribe_async(observer.clone());
+        assert_eq!(*observer.log.lock().unwrap(), vec!["0", "1", "|"]);
+
+        let observer = Recorder::default();
+        throw(Arc::new(MarbleError)).subscribe_async(observer.clone());
+        assert_eq!(*observer.log.lock().unwrap(), vec!["#"]);
+    }
+}
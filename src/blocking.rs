@@ -0,0 +1,320 @@
+use std::io::{self, BufRead, BufReader, Stdin};
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{Receiver, Sender, SyncSender, channel, sync_channel};
+use std::thread;
+
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::schedulers::current_thread::{drain_trampoline, trampoline_mark};
+use crate::schedulers::new_thread::NewThreadScheduler;
+use crate::sync::MutexExt;
+
+pub struct BlockingObservable<A> {
+    pub(crate) receiver: Receiver<Result<A, Error>>,
+    pub(crate) error: Option<Error>
+}
+
+impl<A> BlockingObservable<A> {
+    // The error that ended iteration early, if there was one
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+}
+
+impl<A> Iterator for BlockingObservable<A> {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self) -> Option<A> {
+        match self.receiver.recv() {
+            Ok(Ok(val)) => Some(val),
+            Ok(Err(err)) => {
+                self.error = Some(err);
+                None
+            },
+            Err(_) => None
+        }
+    }
+}
+
+// Either end of a channel a blocked caller reads from
+pub(crate) enum BlockingSender<T> {
+    Bounded(SyncSender<T>),
+    Unbounded(Sender<T>)
+}
+
+impl<T> BlockingSender<T> {
+    #[inline]
+    fn send(&self, val: T) -> bool {
+        match *self {
+            BlockingSender::Bounded(ref sender) => sender.send(val).is_ok(),
+            BlockingSender::Unbounded(ref sender) => sender.send(val).is_ok()
+        }
+    }
+}
+
+impl<T> From<SyncSender<T>> for BlockingSender<T> {
+    #[inline]
+    fn from(sender: SyncSender<T>) -> BlockingSender<T> {
+        BlockingSender::Bounded(sender)
+    }
+}
+
+pub(crate) struct ChannelObserver<A, E = Error> {
+    pub(crate) sender: Option<BlockingSender<Result<A, E>>>
+}
+
+impl<A, E> Observer<E> for ChannelObserver<A, E>
+    where A: Send,
+          E: Send {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let sent = match self.sender {
+            Some(ref sender) => sender.send(Ok(val)),
+            None => false
+        };
+
+        if sent {
+            Continue
+        } else {
+            Stop
+        }
+    }
+
+    fn completed(&mut self) {
+        self.sender.take();
+    }
+
+    fn error(&mut self, err: E) {
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(Err(err));
+        }
+    }
+}
+
+// Subscribes on the scheduler for a caller that waits on what comes back. The
+// channel's a rendezvous, so the source is never more than an item ahead of
+// the caller, and it's stopped once the receiver's dropped.
+pub(crate) fn subscribe_blocking<S, E, D>(source: S, scheduler: &D) -> Receiver<Result<<S as Observable<E>>::Item, E>>
+    where S: Observable<E> + Send + 'static,
+          <S as Observable<E>>::Item: Send + 'static,
+          E: Send + 'static,
+          D: Scheduler {
+    receive_blocking(scheduler, 0, move |observer| source.subscribe(observer))
+}
+
+// Hands subscribe an observer that sends into a channel holding up to bound
+// items, which the caller reads from.
+//
+// A scheduler that runs the task in place, as CurrentThreadScheduler does,
+// would have the source wait on a caller that can't read until the source is
+// done. A task that finds itself on the caller's thread sends into an
+// unbounded channel instead, so the whole stream is buffered before the
+// caller sees any of it, and an endless source never lets the caller go.
+// Called from inside a CurrentThreadScheduler task, the task is queued behind
+// the caller's own, so the caller runs the trampoline's queue itself before
+// it reads. A scheduler that only runs tasks when told to, like
+// TestScheduler, has to be driven from another thread.
+pub(crate) fn receive_blocking<A, E, D, F>(scheduler: &D, bound: usize, subscribe: F) -> Receiver<Result<A, E>>
+    where A: Send + 'static,
+          E: Send + 'static,
+          D: Scheduler,
+          F: FnOnce(ChannelObserver<A, E>) + Send + 'static {
+    let (sender, receiver) = sync_channel(bound);
+    let caller = thread::current().id();
+    let inline = Arc::new(Mutex::new(None));
+    let buffered = inline.clone();
+    let (trampolining, queued) = trampoline_mark();
+
+    scheduler.schedule(Box::new(move || {
+        let sender = if thread::current().id() == caller {
+            let (sender, receiver) = channel();
+            *buffered.acquire() = Some(receiver);
+            BlockingSender::Unbounded(sender)
+        } else {
+            BlockingSender::Bounded(sender)
+        };
+        subscribe(ChannelObserver {sender: Some(sender)});
+    }));
+
+    if trampolining && trampoline_mark().1 != queued {
+        drain_trampoline();
+    }
+
+    let buffered = inline.acquire().take();
+    buffered.unwrap_or(receiver)
+}
+
+// Reads a plain channel on the scheduler until every sender has gone, which
+// completes the stream. The loop blocks in recv, so the scheduler should be
+// one that tolerates blocking. A receiver can only be read from one place,
+// so later subscribers just see it complete.
+pub struct ReceiverObservable<A, D> {
+    pub(crate) receiver: Mutex<Option<Receiver<A>>>,
+    pub(crate) scheduler: D
+}
+
+impl<A, D> Observable for ReceiverObservable<A, D>
+    where A: Send + 'static,
+          D: Scheduler {
+    type Item = A;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let receiver = match self.receiver.acquire().take() {
+            Some(receiver) => receiver,
+            None => return observer.completed()
+        };
+
+        self.scheduler.schedule(Box::new(move || {
+            for val in receiver.iter() {
+                if let Stop = observer.next(val) {
+                    break;
+                }
+            }
+            observer.completed();
+        }));
+    }
+}
+
+pub fn from_receiver<A>(receiver: Receiver<A>) -> ReceiverObservable<A, NewThreadScheduler>
+    where A: Send + 'static {
+    from_receiver_on(receiver, NewThreadScheduler::io())
+}
+
+pub fn from_receiver_on<A, D>(receiver: Receiver<A>, scheduler: D) -> ReceiverObservable<A, D>
+    where A: Send + 'static,
+          D: Scheduler {
+    ReceiverObservable {receiver: Mutex::new(Some(receiver)), scheduler: scheduler}
+}
+
+// Emits each line of a reader, without its line ending, reading on the
+// scheduler as from_receiver does. A read error fails the stream, and as with
+// a receiver, only the first subscriber gets the lines.
+pub struct LinesObservable<R, D> {
+    pub(crate) reader: Mutex<Option<R>>,
+    pub(crate) scheduler: D
+}
+
+impl<R, D> Observable for LinesObservable<R, D>
+    where R: BufRead + Send + 'static,
+          D: Scheduler {
+    type Item = String;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let reader = match self.reader.acquire().take() {
+            Some(reader) => reader,
+            None => return observer.completed()
+        };
+
+        self.scheduler.schedule(Box::new(move || {
+            for line in reader.lines() {
+                match line {
+                    Ok(line) => {
+                        if let Stop = observer.next(line) {
+                            return;
+                        }
+                    },
+                    Err(err) => return observer.error(Arc::new(err))
+                }
+            }
+            observer.completed();
+        }));
+    }
+}
+
+pub fn from_lines<R>(reader: R) -> LinesObservable<R, NewThreadScheduler>
+    where R: BufRead + Send + 'static {
+    from_lines_on(reader, NewThreadScheduler::io())
+}
+
+pub fn from_lines_on<R, D>(reader: R, scheduler: D) -> LinesObservable<R, D>
+    where R: BufRead + Send + 'static,
+          D: Scheduler {
+    LinesObservable {reader: Mutex::new(Some(reader)), scheduler: scheduler}
+}
+
+// Standard input is shared by the whole process, so this is meant to be
+// subscribed once
+pub fn stdin_lines() -> LinesObservable<BufReader<Stdin>, NewThreadScheduler> {
+    from_lines(BufReader::new(io::stdin()))
+}
+
+// Forwards values into a channel, stopping once its receiver is dropped. The
+// sender is let go when the stream ends either way, so the receiving side
+// sees a disconnect; an error isn't sent on.
+pub(crate) struct SenderObserver<A> {
+    pub(crate) sender: Option<Sender<A>>
+}
+
+impl<A, E> Observer<E> for SenderObserver<A> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        match self.sender {
+            Some(ref sender) if sender.send(val).is_ok() => Continue,
+            _ => Stop
+        }
+    }
+
+    fn completed(&mut self) {
+        self.sender.take();
+    }
+
+    fn error(&mut self, _: E) {
+        self.sender.take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::schedulers::Scheduler;
+    use crate::schedulers::current_thread::CurrentThreadScheduler;
+    use crate::schedulers::thread_pool::ThreadPoolScheduler;
+    use crate::sources::{interval_on, range, throw};
+    use crate::testing::marbles::MarbleError;
+
+    #[test]
+    fn blocking_on_an_inline_scheduler_doesnt_deadlock() {
+        assert_eq!(range(0, 3).block_first_on(CurrentThreadScheduler).unwrap(), Some(0));
+        assert_eq!(range(0, 3).block_last_on(CurrentThreadScheduler).unwrap(), Some(2));
+
+        let mut seen = Vec::new();
+        range(0, 3).block_for_each_on(|x| seen.push(x), CurrentThreadScheduler).unwrap();
+        assert_eq!(seen, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn blocking_on_an_inline_scheduler_passes_errors_on() {
+        let result = throw::<i32>(Arc::new(MarbleError)).block_last_on(CurrentThreadScheduler);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn blocking_from_inside_a_trampoline_task_runs_the_queue_behind_it() {
+        let (sender, receiver) = channel();
+        CurrentThreadScheduler.schedule(Box::new(move || {
+            let last = range(0, 3).block_last_on(CurrentThreadScheduler).unwrap();
+            let ticks = interval_on(Duration::from_millis(1), CurrentThreadScheduler).take(3).block_last_on(CurrentThreadScheduler).unwrap();
+            let _ = sender.send((last, ticks));
+        }));
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok((Some(2), Some(2))));
+    }
+
+    #[test]
+    fn blocking_on_a_thread_pool_stops_the_source_after_the_first() {
+        let scheduler = ThreadPoolScheduler::new(2);
+        assert_eq!(range(5, i32::MAX).block_first_on(scheduler.clone()).unwrap(), Some(5));
+        assert_eq!(range(0, 100).block_last_on(scheduler.clone()).unwrap(), Some(99));
+        scheduler.shutdown();
+    }
+}
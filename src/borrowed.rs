@@ -0,0 +1,293 @@
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::sources::{IterObservable, RangeObservable, Step, ValueObservable};
+
+// An observer that's lent each item rather than given it, so a source can
+// emit what it holds without cloning it first. Whatever needs an item of its
+// own clones it then, e.g. through cloned().
+pub trait RefObserver<E = Error> {
+    type Item;
+
+    fn next(&mut self, val: &Self::Item) -> IterationResult;
+    fn completed(&mut self);
+    fn error(&mut self, err: E);
+}
+
+pub trait RefObservable<E = Error> {
+    type Item;
+
+    fn subscribe_ref<N>(&self, observer: N) where N: RefObserver<E, Item=Self::Item> + Send + Sync + 'static;
+
+    // Tests the lent items, passing the ones that pass on still lent
+    #[inline]
+    fn filter_ref<P>(self, predicate: P) -> RefFilterObservable<P, Self>
+        where P: Fn(&Self::Item) -> bool + Send + Sync + 'static,
+              Self: Sized {
+        RefFilterObservable {predicate: Arc::new(predicate), source: self}
+    }
+
+    // Back to owned items, built from the lent ones
+    #[inline]
+    fn map_ref<B, F>(self, f: F) -> RefMapObservable<F, Self>
+        where F: Fn(&Self::Item) -> B + Send + Sync + 'static,
+              Self: Sized {
+        RefMapObservable {f: Arc::new(f), source: self}
+    }
+
+    // Back to owned items, cloned from the lent ones
+    #[inline]
+    fn cloned(self) -> ClonedObservable<Self>
+        where Self::Item: Clone,
+              Self: Sized {
+        ClonedObservable {source: self}
+    }
+}
+
+//////////////Sources
+impl<A> RefObservable for RangeObservable<A>
+    where A: Step {
+    type Item = A;
+
+    fn subscribe_ref<N>(&self, mut observer: N)
+        where N: RefObserver<Item=Self::Item> + Send + Sync + 'static {
+        let mut state = self.start.clone();
+        while state < self.end {
+            if let Stop = observer.next(&state) {
+                break;
+            }
+            state = state.successor();
+        }
+        observer.completed();
+    }
+}
+
+impl<A> RefObservable for ValueObservable<A> {
+    type Item = A;
+
+    fn subscribe_ref<N>(&self, mut observer: N)
+        where N: RefObserver<Item=Self::Item> + Send + Sync + 'static {
+        observer.next(&self.value);
+        observer.completed();
+    }
+}
+
+// The iterator is still cloned per subscription; only its items are lent
+impl<I> RefObservable for IterObservable<I>
+    where I: IntoIterator + Clone {
+    type Item = I::Item;
+
+    fn subscribe_ref<N>(&self, mut observer: N)
+        where N: RefObserver<Item=Self::Item> + Send + Sync + 'static {
+        for val in self.iter.clone() {
+            if let Stop = observer.next(&val) {
+                break;
+            }
+        }
+        observer.completed();
+    }
+}
+//Sources//////////////
+
+//////////////FilterRef
+pub struct RefFilterObservable<P, S> {
+    pub(crate) predicate: Arc<P>,
+    pub(crate) source: S
+}
+
+impl<P, S, E> RefObservable<E> for RefFilterObservable<P, S>
+    where S: RefObservable<E>,
+          S::Item: 'static,
+          P: Fn(&S::Item) -> bool + Send + Sync + 'static,
+          E: 'static {
+    type Item = S::Item;
+
+    fn subscribe_ref<N>(&self, observer: N)
+        where N: RefObserver<E, Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe_ref(RefFilterObserver {predicate: self.predicate.clone(), observer: observer});
+    }
+}
+
+pub(crate) struct RefFilterObserver<P, N> {
+    pub(crate) predicate: Arc<P>,
+    pub(crate) observer: N
+}
+
+impl<P, N, E> RefObserver<E> for RefFilterObserver<P, N>
+    where N: RefObserver<E>,
+          P: Fn(&N::Item) -> bool {
+    type Item = N::Item;
+
+    fn next(&mut self, val: &Self::Item) -> IterationResult {
+        if (self.predicate)(val) {
+            self.observer.next(val)
+        } else {
+            Continue
+        }
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
+//FilterRef//////////////
+
+//////////////MapRef
+pub struct RefMapObservable<F, S> {
+    pub(crate) f: Arc<F>,
+    pub(crate) source: S
+}
+
+impl<B, F, S, E> Observable<E> for RefMapObservable<F, S>
+    where S: RefObservable<E>,
+          S::Item: 'static,
+          F: Fn(&S::Item) -> B + Send + Sync + 'static,
+          E: 'static {
+    type Item = B;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<E, Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe_ref(RefMapObserver {f: self.f.clone(), observer: observer, _marker: PhantomData});
+    }
+}
+
+pub struct ClonedObservable<S> {
+    pub(crate) source: S
+}
+
+impl<S, E> Observable<E> for ClonedObservable<S>
+    where S: RefObservable<E>,
+          S::Item: Clone + Send + Sync + 'static,
+          E: 'static {
+    type Item = S::Item;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<E, Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe_ref(ClonedObserver {observer: observer});
+    }
+}
+
+pub(crate) struct RefMapObserver<F, N, A> {
+    pub(crate) f: Arc<F>,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<fn(&A)>
+}
+
+impl<A, F, N, E> RefObserver<E> for RefMapObserver<F, N, A>
+    where N: Observer<E>,
+          F: Fn(&A) -> N::Item {
+    type Item = A;
+
+    fn next(&mut self, val: &Self::Item) -> IterationResult {
+        self.observer.next((self.f)(val))
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
+pub(crate) struct ClonedObserver<N> {
+    pub(crate) observer: N
+}
+
+impl<N, E> RefObserver<E> for ClonedObserver<N>
+    where N: Observer<E>,
+          N::Item: Clone {
+    type Item = N::Item;
+
+    fn next(&mut self, val: &Self::Item) -> IterationResult {
+        self.observer.next(val.clone())
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
+//MapRef//////////////
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::borrowed::RefObservable;
+    use crate::observable::Observable;
+    use crate::sources::{from_iter, range, value};
+    use crate::testing::observer::TestObserver;
+
+    // Counts its clones, which lent items shouldn't need
+    struct Tracked {
+        val: i32,
+        clones: Arc<AtomicUsize>
+    }
+
+    impl Clone for Tracked {
+        fn clone(&self) -> Tracked {
+            self.clones.fetch_add(1, Ordering::SeqCst);
+            Tracked {val: self.val, clones: self.clones.clone()}
+        }
+    }
+
+    fn outcome<S>(source: S) -> (Vec<S::Item>, usize)
+        where S: Observable,
+              S::Item: Clone + Send + 'static {
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        (observer.values(), observer.completion_count())
+    }
+
+    #[test]
+    fn filter_ref_and_map_ref_match_filter_and_map() {
+        assert_eq!(
+            outcome(range(0, 20).filter_ref(|val| val % 3 == 0).map_ref(|val| val * 2)),
+            outcome(range(0, 20).filter(|val| val % 3 == 0).map(|val| val * 2)));
+        assert_eq!(
+            outcome(from_iter(vec!["a", "bb", "ccc"]).filter_ref(|val| val.len() > 1).map_ref(|val| val.len())),
+            outcome(from_iter(vec!["a", "bb", "ccc"]).filter(|val| val.len() > 1).map(|val| val.len())));
+    }
+
+    #[test]
+    fn cloned_matches_the_owned_source() {
+        assert_eq!(outcome(range(0, 5).filter_ref(|val| val % 2 == 1).cloned()), (vec![1, 3], 1));
+        assert_eq!(outcome(value(7).cloned()), outcome(value(7)));
+    }
+
+    #[test]
+    fn lent_items_arent_cloned() {
+        let clones = Arc::new(AtomicUsize::new(0));
+        let source = value(Tracked {val: 4, clones: clones.clone()});
+        let observer = TestObserver::new();
+        source.filter_ref(|item| item.val > 0).map_ref(|item| item.val).subscribe(observer.clone());
+        observer.assert_values(&[4]);
+        assert_eq!(clones.load(Ordering::SeqCst), 0);
+
+        let observer = TestObserver::new();
+        let source = value(Tracked {val: 4, clones: clones.clone()});
+        source.filter_ref(|item| item.val > 0).cloned().map(|item| item.val).subscribe(observer.clone());
+        observer.assert_values(&[4]);
+        assert_eq!(clones.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn the_downstream_can_stop_a_lending_source() {
+        let observer = TestObserver::new();
+        range(0, i32::MAX).map_ref(|val| *val).take(3).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+}
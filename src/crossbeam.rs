@@ -0,0 +1,244 @@
+use crossbeam_channel::{Receiver, Select, Sender};
+
+use crate::observable::Observable;
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::schedulers::new_thread::NewThreadScheduler;
+
+//////////////FromCrossbeam
+// As from_receiver, for crossbeam channels, bounded or not. Receivers can be
+// cloned, so each subscriber reads with its own and the items are shared out
+// between them rather than each seeing every one.
+pub struct CrossbeamObservable<A, D> {
+    pub(crate) receiver: Receiver<A>,
+    pub(crate) scheduler: D
+}
+
+impl<A, D> Observable for CrossbeamObservable<A, D>
+    where A: Send + 'static,
+          D: Scheduler {
+    type Item = A;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let receiver = self.receiver.clone();
+        self.scheduler.schedule(Box::new(move || {
+            for val in receiver.iter() {
+                if let Stop = observer.next(val) {
+                    break;
+                }
+            }
+            observer.completed();
+        }));
+    }
+}
+
+pub fn from_crossbeam<A>(receiver: Receiver<A>) -> CrossbeamObservable<A, NewThreadScheduler>
+    where A: Send + 'static {
+    from_crossbeam_on(receiver, NewThreadScheduler::io())
+}
+
+pub fn from_crossbeam_on<A, D>(receiver: Receiver<A>, scheduler: D) -> CrossbeamObservable<A, D>
+    where A: Send + 'static,
+          D: Scheduler {
+    CrossbeamObservable {receiver: receiver, scheduler: scheduler}
+}
+//FromCrossbeam//////////////
+
+//////////////SelectSources
+// Merges several channels from a single thread that waits on all of them at
+// once, where merging from_crossbeam sources would tie up a thread per
+// channel. Each channel drops out as it disconnects, and the stream
+// completes once they all have.
+pub struct SelectObservable<A, D> {
+    pub(crate) receivers: Vec<Receiver<A>>,
+    pub(crate) scheduler: D
+}
+
+impl<A, D> Observable for SelectObservable<A, D>
+    where A: Send + 'static,
+          D: Scheduler {
+    type Item = A;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let receivers = self.receivers.clone();
+        self.scheduler.schedule(Box::new(move || {
+            let mut select = Select::new();
+            for receiver in receivers.iter() {
+                select.recv(receiver);
+            }
+
+            // Indices from Select stay put as others are removed
+            let mut open = receivers.len();
+            while open > 0 {
+                let operation = select.select();
+                let index = operation.index();
+                match operation.recv(&receivers[index]) {
+                    Ok(val) => {
+                        if let Stop = observer.next(val) {
+                            break;
+                        }
+                    },
+                    Err(_) => {
+                        select.remove(index);
+                        open -= 1;
+                    }
+                }
+            }
+            observer.completed();
+        }));
+    }
+}
+
+pub fn select_sources<A>(receivers: Vec<Receiver<A>>) -> SelectObservable<A, NewThreadScheduler>
+    where A: Send + 'static {
+    select_sources_on(receivers, NewThreadScheduler::io())
+}
+
+pub fn select_sources_on<A, D>(receivers: Vec<Receiver<A>>, scheduler: D) -> SelectObservable<A, D>
+    where A: Send + 'static,
+          D: Scheduler {
+    SelectObservable {receivers: receivers, scheduler: scheduler}
+}
+//SelectSources//////////////
+
+//////////////CrossbeamSender
+// As SenderObserver. A bounded channel that's full holds the source up until
+// there's room.
+pub(crate) struct CrossbeamObserver<A> {
+    pub(crate) sender: Option<Sender<A>>
+}
+
+impl<A, E> Observer<E> for CrossbeamObserver<A> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        match self.sender {
+            Some(ref sender) if sender.send(val).is_ok() => Continue,
+            _ => Stop
+        }
+    }
+
+    fn completed(&mut self) {
+        self.sender.take();
+    }
+
+    fn error(&mut self, _: E) {
+        self.sender.take();
+    }
+}
+//CrossbeamSender//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    use crossbeam_channel::{bounded, unbounded};
+
+    use crate::crossbeam::{from_crossbeam, select_sources};
+    use crate::observable::Observable;
+    use crate::sources::range;
+    use crate::testing::observer::TestObserver;
+
+    #[test]
+    fn from_crossbeam_completes_once_the_senders_are_gone() {
+        let (sender, receiver) = unbounded();
+        let observer = TestObserver::new();
+        from_crossbeam(receiver).subscribe(observer.clone());
+        sender.send(1).unwrap();
+        sender.send(2).unwrap();
+        assert!(!observer.await_terminal_event(Duration::from_millis(20)));
+
+        drop(sender);
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_values(&[1, 2]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn from_crossbeam_shares_items_out_between_subscribers() {
+        let (sender, receiver) = unbounded();
+        let source = from_crossbeam(receiver);
+        let first = TestObserver::new();
+        let second = TestObserver::new();
+        source.subscribe(first.clone());
+        source.subscribe(second.clone());
+        for val in 0..100 {
+            sender.send(val).unwrap();
+        }
+        drop(sender);
+        assert!(first.await_terminal_event(Duration::from_secs(5)));
+        assert!(second.await_terminal_event(Duration::from_secs(5)));
+
+        let mut values = first.values();
+        values.extend(second.values());
+        values.sort();
+        assert_eq!(values, (0..100).collect::<Vec<i32>>());
+    }
+
+    #[test]
+    fn select_sources_completes_only_once_every_channel_disconnects() {
+        let (first, first_receiver) = unbounded();
+        let (second, second_receiver) = unbounded();
+        let observer = TestObserver::new();
+        select_sources(vec![first_receiver, second_receiver]).subscribe(observer.clone());
+
+        first.send(1).unwrap();
+        drop(first);
+        second.send(2).unwrap();
+        assert!(!observer.await_terminal_event(Duration::from_millis(20)));
+        second.send(3).unwrap();
+        drop(second);
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+
+        let mut values = observer.values();
+        values.sort();
+        assert_eq!(values, vec![1, 2, 3]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn select_sources_stops_with_the_downstream() {
+        let (sender, receiver) = unbounded();
+        let (_other, other_receiver) = unbounded::<i32>();
+        let observer = TestObserver::new();
+        select_sources(vec![receiver, other_receiver]).take(2).subscribe(observer.clone());
+        // Once stopped the receivers are let go, so later sends can fail
+        for val in 0..5 {
+            let _ = sender.send(val);
+        }
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_values(&[0, 1]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn subscribe_crossbeam_waits_for_room_in_a_bounded_channel() {
+        let (sender, receiver) = bounded(1);
+        let emitted = Arc::new(AtomicUsize::new(0));
+        let counter = emitted.clone();
+        let producer = thread::spawn(move || {
+            range(0, 4).tap(move |_| { counter.fetch_add(1, Ordering::SeqCst); }).subscribe_crossbeam(sender);
+        });
+        thread::sleep(Duration::from_millis(20));
+        assert!(emitted.load(Ordering::SeqCst) <= 2);
+
+        assert_eq!(receiver.iter().collect::<Vec<i32>>(), vec![0, 1, 2, 3]);
+        producer.join().unwrap();
+    }
+
+    #[test]
+    fn subscribe_crossbeam_stops_once_the_receiver_is_gone() {
+        let (sender, receiver) = unbounded();
+        drop(receiver);
+        let emitted = Arc::new(AtomicUsize::new(0));
+        let counter = emitted.clone();
+        range(0, i32::MAX).tap(move |_| { counter.fetch_add(1, Ordering::SeqCst); }).subscribe_crossbeam(sender);
+        assert_eq!(emitted.load(Ordering::SeqCst), 1);
+    }
+}
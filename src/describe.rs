@@ -0,0 +1,118 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::any;
+use core::fmt::Write;
+
+// One stage of an assembled pipeline and the stages feeding it. Only what's
+// known before subscribing is here, so the inner observables of flat_map and
+// the like don't appear.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub name: String,
+    pub upstream: Vec<Node>
+}
+
+impl Node {
+    pub fn new<I>(name: &str, upstream: I) -> Node
+        where I: IntoIterator<Item=Node> {
+        Node {name: name.to_string(), upstream: upstream.into_iter().collect()}
+    }
+
+    pub fn leaf(name: &str) -> Node {
+        Node::new(name, [])
+    }
+
+    // e.g. RangeObservable -> MapObservable -> TakeObservable, with several
+    // upstreams bracketed: [IntervalObservable, TimerObservable] -> SampleObservable
+    pub fn describe(&self) -> String {
+        match self.upstream.len() {
+            0 => self.name.clone(),
+            1 => format!("{} -> {}", self.upstream[0].describe(), self.name),
+            _ => {
+                let upstream: Vec<String> = self.upstream.iter().map(Node::describe).collect();
+                format!("[{}] -> {}", upstream.join(", "), self.name)
+            }
+        }
+    }
+
+    // A Graphviz digraph with an edge from each stage to the one it feeds
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph pipeline {\n");
+        self.write_dot(&mut dot, &mut 0);
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        let _ = writeln!(dot, "    n{} [label=\"{}\"];", id, self.name);
+        for upstream in &self.upstream {
+            let upstream_id = upstream.write_dot(dot, next_id);
+            let _ = writeln!(dot, "    n{} -> n{};", upstream_id, id);
+        }
+        id
+    }
+}
+
+// A type's name without its path or parameters
+pub(crate) fn short_name<T: ?Sized>() -> &'static str {
+    let name = any::type_name::<T>();
+    let name = &name[..name.find('<').unwrap_or(name.len())];
+    &name[name.rfind("::").map_or(0, |i| i + 2)..]
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::time::Duration;
+
+    use crate::describe::{Node, short_name};
+    use crate::observable::Observable;
+    use crate::sources::{interval, range, timer};
+
+    #[test]
+    fn describe_reads_along_the_chain() {
+        let source = range(0, 10).map(|val| val * 2).take(3);
+        assert_eq!(source.describe(), "RangeObservable -> MapObservable -> TakeObservable");
+    }
+
+    #[test]
+    fn describe_brackets_several_upstreams() {
+        let source = interval(Duration::from_secs(1)).sample(timer(Duration::from_secs(1))).take(1);
+        assert_eq!(source.describe(), "[IntervalObservable, TimerObservable] -> SampleObservable -> TakeObservable");
+    }
+
+    #[test]
+    fn describe_sees_through_boxing() {
+        let source = range(0, 10).take(3).boxed();
+        assert_eq!(source.describe(), "RangeObservable -> TakeObservable");
+    }
+
+    #[test]
+    fn to_dot_has_an_edge_into_each_stage() {
+        let source = interval(Duration::from_secs(1)).sample(timer(Duration::from_secs(1)));
+        assert_eq!(source.to_dot(), concat!(
+            "digraph pipeline {\n",
+            "    n0 [label=\"SampleObservable\"];\n",
+            "    n1 [label=\"IntervalObservable\"];\n",
+            "    n1 -> n0;\n",
+            "    n2 [label=\"TimerObservable\"];\n",
+            "    n2 -> n0;\n",
+            "}\n"
+        ));
+    }
+
+    #[test]
+    fn nodes_can_be_built_by_hand() {
+        let node = Node::new("Sink", [Node::new("Middle", [Node::leaf("Source")])]);
+        assert_eq!(node.describe(), "Source -> Middle -> Sink");
+        assert_eq!(Node::leaf("Alone").describe(), "Alone");
+    }
+
+    #[test]
+    fn short_name_drops_the_path_and_parameters() {
+        assert_eq!(short_name::<Vec<Option<i32>>>(), "Vec");
+        assert_eq!(short_name::<crate::describe::Node>(), "Node");
+    }
+}
@@ -0,0 +1,333 @@
+use std::sync::{Arc, Mutex, TryLockError};
+
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+use crate::operators::flat_map_flow::FlatMapFlowable;
+#[cfg(feature = "futures")]
+use crate::async_observer::{AsyncObserver, AsyncAdapter};
+#[cfg(feature = "futures")]
+use crate::stream::ObservableStream;
+use crate::sync::MutexExt;
+
+// A demand driven counterpart to Observable, for sources that can produce
+// faster than their consumers. A subscriber is handed a FlowSubscription and
+// gets no more items than it has requested through it. Returning Stop from
+// next cancels, as it does for observers.
+pub trait Subscriber: Observer {
+    fn on_subscribe(&mut self, subscription: FlowSubscription);
+}
+
+pub trait Flowable {
+    type Item;
+
+    fn subscribe_flow<N>(&self, subscriber: N) where N: Subscriber<Item=Self::Item> + Send + Sync + 'static;
+
+    // Requests everything up front, for a plain observer that can keep up
+    #[inline]
+    fn subscribe_all<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.subscribe_flow(UnboundedSubscriber {observer: observer});
+    }
+
+    // Each item is requested once the previous one's future has resolved
+    #[cfg(feature = "futures")]
+    #[inline]
+    fn subscribe_flow_async<N>(&self, observer: N)
+        where N: AsyncObserver<Item=Self::Item> + Send + Sync + 'static {
+        self.subscribe_flow(AsyncAdapter::new(observer))
+    }
+
+    #[inline]
+    fn flat_map_flow<U, F>(self, f: F, max_concurrency: usize) -> FlatMapFlowable<Self, F>
+        where F: Fn(Self::Item) -> U,
+              U: Flowable,
+              Self: Sized {
+        FlatMapFlowable {f: Arc::new(f), max_concurrency: max_concurrency.max(1), source: self}
+    }
+
+    // Unlike Observable::into_stream, nothing is produced ahead of the
+    // stream being polled
+    #[cfg(feature = "futures")]
+    #[inline]
+    fn into_flow_stream(self) -> ObservableStream<Self::Item>
+        where Self: Sized + Send + 'static,
+              Self::Item: Send + 'static {
+        ObservableStream::new(Box::new(move |subscriber| self.subscribe_flow(subscriber)))
+    }
+}
+
+// What a FlowSubscription drives. Requests add up, and u64::MAX means
+// unbounded.
+pub trait FlowControl: Send + Sync {
+    fn request(&self, n: u64);
+    fn cancel(&self);
+}
+
+#[derive(Clone)]
+pub struct FlowSubscription {
+    pub(crate) control: Arc<dyn FlowControl>
+}
+
+impl FlowSubscription {
+    pub fn request(&self, n: u64) {
+        self.control.request(n);
+    }
+
+    pub fn cancel(&self) {
+        self.control.cancel();
+    }
+}
+
+pub(crate) struct UnboundedSubscriber<N> {
+    pub(crate) observer: N
+}
+
+impl<N> Observer for UnboundedSubscriber<N>
+    where N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.observer.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.error(err);
+    }
+}
+
+impl<N> Subscriber for UnboundedSubscriber<N>
+    where N: Observer {
+    fn on_subscribe(&mut self, subscription: FlowSubscription) {
+        subscription.request(u64::MAX);
+    }
+}
+
+pub(crate) fn add_demand(requested: u64, n: u64) -> u64 {
+    requested.saturating_add(n)
+}
+
+pub(crate) struct PullState {
+    pub(crate) requested: u64,
+    pub(crate) cancelled: bool,
+    pub(crate) draining: bool
+}
+
+// Drives a subscriber from a pull function, one item per unit of demand.
+// Whoever raises demand from zero drains on their own thread; requests made
+// while a drain is running just top it up, so a subscriber requesting from
+// inside next doesn't recurse.
+pub(crate) struct PullFlow<N, P> {
+    pub(crate) state: Mutex<PullState>,
+    pub(crate) source: Mutex<Option<(N, P)>>
+}
+
+impl<N, P> PullFlow<N, P>
+    where N: Subscriber,
+          P: FnMut() -> Option<N::Item> {
+    fn drain(&self) {
+        let mut source = self.source.acquire();
+        let (mut subscriber, mut pull) = match source.take() {
+            Some(source) => source,
+            None => return
+        };
+
+        loop {
+            {
+                let mut state = self.state.acquire();
+                if state.cancelled {
+                    return;
+                }
+                if state.requested == 0 {
+                    // The source goes back and its lock is let go before
+                    // the drain is over, so a cancel that sees no drain can
+                    // always take it
+                    *source = Some((subscriber, pull));
+                    drop(source);
+                    state.draining = false;
+                    return;
+                }
+                if state.requested != u64::MAX {
+                    state.requested -= 1;
+                }
+            }
+
+            match pull() {
+                Some(val) => {
+                    if let Stop = subscriber.next(val) {
+                        self.state.acquire().cancelled = true;
+                        return subscriber.completed();
+                    }
+                },
+                None => {
+                    self.state.acquire().cancelled = true;
+                    return subscriber.completed();
+                }
+            }
+        }
+    }
+}
+
+impl<N, P> FlowControl for PullFlow<N, P>
+    where N: Subscriber + Send,
+          P: FnMut() -> Option<N::Item> + Send {
+    fn request(&self, n: u64) {
+        {
+            let mut state = self.state.acquire();
+            if state.cancelled || n == 0 {
+                return;
+            }
+            state.requested = add_demand(state.requested, n);
+            if state.draining {
+                return;
+            }
+            state.draining = true;
+        }
+        self.drain();
+    }
+
+    // Cancelling drops the subscriber without terminating it. A running
+    // drain drops it on its way out; otherwise it's dropped here, since the
+    // subscriber usually holds this flow and would keep it alive. The lock is
+    // only tried, as a cancel from a drain that's still winding down must
+    // not wait on it.
+    fn cancel(&self) {
+        {
+            let mut state = self.state.acquire();
+            state.cancelled = true;
+            if state.draining {
+                return;
+            }
+        }
+
+        let source = match self.source.try_lock() {
+            Ok(mut source) => source.take(),
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().take(),
+            Err(TryLockError::WouldBlock) => None
+        };
+        drop(source);
+    }
+}
+
+pub(crate) fn subscribe_pull<N, P>(mut subscriber: N, pull: P)
+    where N: Subscriber + Send + 'static,
+          P: FnMut() -> Option<N::Item> + Send + 'static {
+    // Marked as draining until the subscriber is in place, so requests made
+    // from on_subscribe are held rather than lost
+    let flow = Arc::new(PullFlow {
+        state: Mutex::new(PullState {requested: 0, cancelled: false, draining: true}),
+        source: Mutex::new(None)
+    });
+
+    subscriber.on_subscribe(FlowSubscription {control: flow.clone()});
+    *flow.source.acquire() = Some((subscriber, pull));
+
+    let resume = {
+        let mut state = flow.state.acquire();
+        state.draining = state.requested > 0 && !state.cancelled;
+        state.draining
+    };
+    if resume {
+        flow.drain();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex, Weak};
+
+    use crate::observer::{Error, IterationResult, Observer};
+    use crate::observer::IterationResult::{Continue, Stop};
+    use crate::flow::{FlowSubscription, Subscriber, subscribe_pull};
+
+    // Keeps its own subscription, as most subscribers do, which is what
+    // makes the cycle through the flow
+    struct Holding {
+        subscription: Option<FlowSubscription>,
+        shared: Arc<Mutex<Option<FlowSubscription>>>,
+        received: Arc<Mutex<Vec<i32>>>,
+        stop_at: Option<i32>,
+        _alive: Arc<()>
+    }
+
+    impl Observer for Holding {
+        type Item = i32;
+
+        fn next(&mut self, val: i32) -> IterationResult {
+            self.received.lock().unwrap().push(val);
+            if self.stop_at == Some(val) {
+                self.subscription.as_ref().unwrap().cancel();
+                return Stop;
+            }
+            Continue
+        }
+
+        fn completed(&mut self) {}
+
+        fn error(&mut self, _: Error) {}
+    }
+
+    impl Subscriber for Holding {
+        fn on_subscribe(&mut self, subscription: FlowSubscription) {
+            *self.shared.lock().unwrap() = Some(subscription.clone());
+            self.subscription = Some(subscription);
+        }
+    }
+
+    fn subscribe_holding(stop_at: Option<i32>) -> (Arc<Mutex<Option<FlowSubscription>>>, Arc<Mutex<Vec<i32>>>, Weak<()>) {
+        let shared = Arc::new(Mutex::new(None));
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let alive = Arc::new(());
+        let weak = Arc::downgrade(&alive);
+        let mut count = 0;
+        subscribe_pull(Holding {
+            subscription: None,
+            shared: shared.clone(),
+            received: received.clone(),
+            stop_at: stop_at,
+            _alive: alive
+        }, move || {
+            count += 1;
+            Some(count)
+        });
+        (shared, received, weak)
+    }
+
+    #[test]
+    fn pull_flow_gives_what_was_requested() {
+        let (subscription, received, _) = subscribe_holding(None);
+        let subscription = subscription.lock().unwrap().take().unwrap();
+        subscription.request(2);
+        assert_eq!(*received.lock().unwrap(), vec![1, 2]);
+        subscription.request(1);
+        assert_eq!(*received.lock().unwrap(), vec![1, 2, 3]);
+        subscription.cancel();
+    }
+
+    #[test]
+    fn cancelling_an_idle_pull_flow_drops_the_subscriber() {
+        let (subscription, received, alive) = subscribe_holding(None);
+        let subscription = subscription.lock().unwrap().take().unwrap();
+        subscription.request(1);
+        assert_eq!(*received.lock().unwrap(), vec![1]);
+        assert!(alive.upgrade().is_some());
+
+        subscription.cancel();
+        assert!(alive.upgrade().is_none());
+        subscription.request(1);
+        assert_eq!(*received.lock().unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn cancelling_from_inside_next_drops_the_subscriber() {
+        let (subscription, received, alive) = subscribe_holding(Some(2));
+        let subscription = subscription.lock().unwrap().take().unwrap();
+        subscription.request(5);
+        assert_eq!(*received.lock().unwrap(), vec![1, 2]);
+        assert!(alive.upgrade().is_none());
+    }
+}
@@ -0,0 +1,274 @@
+use std::sync::{Arc, Mutex};
+use std::pin::Pin;
+use std::future::Future;
+use std::task::{Context, Poll, Waker};
+
+use crate::flow::{Flowable, Subscriber, subscribe_pull};
+use crate::observable::Observable;
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::stream::block_on_poll;
+use crate::sync::MutexExt;
+
+//////////////IntoFuture
+pub(crate) struct FutureSlot<T> {
+    pub(crate) value: Option<T>,
+    pub(crate) waker: Option<Waker>
+}
+
+impl<T> FutureSlot<T> {
+    // Only the first value counts; anything after it is dropped
+    pub(crate) fn resolve(slot: &Mutex<FutureSlot<T>>, value: T) {
+        let waker = {
+            let mut slot = slot.acquire();
+            if slot.value.is_some() {
+                return;
+            }
+            slot.value = Some(value);
+            slot.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+// Resolves once from whatever the source delivers to its observer. The
+// source is subscribed on the first poll, from the polling thread.
+pub struct ObservableFuture<T> {
+    pub(crate) subscribe: Option<Box<dyn FnOnce(Arc<Mutex<FutureSlot<T>>>) + Send>>,
+    pub(crate) slot: Arc<Mutex<FutureSlot<T>>>
+}
+
+impl<T> ObservableFuture<T> {
+    pub(crate) fn new(subscribe: Box<dyn FnOnce(Arc<Mutex<FutureSlot<T>>>) + Send>) -> ObservableFuture<T> {
+        ObservableFuture {
+            subscribe: Some(subscribe),
+            slot: Arc::new(Mutex::new(FutureSlot {value: None, waker: None}))
+        }
+    }
+}
+
+impl<T> Future for ObservableFuture<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<T> {
+        let this = self.get_mut();
+        if let Some(subscribe) = this.subscribe.take() {
+            subscribe(this.slot.clone());
+        }
+
+        let mut slot = this.slot.acquire();
+        match slot.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+pub(crate) struct FirstObserver<A, E> {
+    pub(crate) slot: Arc<Mutex<FutureSlot<Result<Option<A>, E>>>>
+}
+
+impl<A, E> Observer<E> for FirstObserver<A, E> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        FutureSlot::resolve(&self.slot, Ok(Some(val)));
+        Stop
+    }
+
+    fn completed(&mut self) {
+        FutureSlot::resolve(&self.slot, Ok(None));
+    }
+
+    fn error(&mut self, err: E) {
+        FutureSlot::resolve(&self.slot, Err(err));
+    }
+}
+
+// The Mutex is only there so the observer is Sync without requiring it of A
+pub(crate) struct CollectObserver<A, E> {
+    pub(crate) items: Mutex<Vec<A>>,
+    pub(crate) slot: Arc<Mutex<FutureSlot<Result<Vec<A>, E>>>>
+}
+
+impl<A, E> CollectObserver<A, E> {
+    pub(crate) fn new(slot: Arc<Mutex<FutureSlot<Result<Vec<A>, E>>>>) -> CollectObserver<A, E> {
+        CollectObserver {items: Mutex::new(Vec::new()), slot: slot}
+    }
+}
+
+impl<A, E> Observer<E> for CollectObserver<A, E> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.items.acquire_mut().push(val);
+        Continue
+    }
+
+    fn completed(&mut self) {
+        let items = ::std::mem::take(self.items.acquire_mut());
+        FutureSlot::resolve(&self.slot, Ok(items));
+    }
+
+    fn error(&mut self, err: E) {
+        FutureSlot::resolve(&self.slot, Err(err));
+    }
+}
+//IntoFuture//////////////
+
+//////////////FromFuture
+// Emits what the future resolves to, then completes. As with from_stream,
+// subscribing polls on the subscribing thread and a future only resolves
+// once, so later subscribers just see it complete.
+pub struct FutureObservable<F> {
+    pub(crate) future: Mutex<Option<Pin<Box<F>>>>
+}
+
+pub fn from_future<F>(future: F) -> FutureObservable<F>
+    where F: Future {
+    FutureObservable {future: Mutex::new(Some(Box::pin(future)))}
+}
+
+impl<F> FutureObservable<F>
+    where F: Future {
+    fn resolve(&self) -> Option<F::Output> {
+        let future = self.future.acquire().take();
+        future.map(|mut future| block_on_poll(|cx| future.as_mut().poll(cx)))
+    }
+}
+
+impl<F> Observable for FutureObservable<F>
+    where F: Future {
+    type Item = F::Output;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        if let Some(val) = self.resolve() {
+            observer.next(val);
+        }
+        observer.completed();
+    }
+}
+
+impl<F> Flowable for FutureObservable<F>
+    where F: Future + Send + 'static {
+    type Item = F::Output;
+
+    // The future isn't polled until the first request
+    fn subscribe_flow<N>(&self, subscriber: N)
+        where N: Subscriber<Item=Self::Item> + Send + Sync + 'static {
+        let mut future = self.future.acquire().take();
+        subscribe_pull(subscriber, move || {
+            future.take().map(|mut future| block_on_poll(|cx| future.as_mut().poll(cx)))
+        });
+    }
+}
+//FromFuture//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::flow::Flowable;
+    use crate::future::from_future;
+    use crate::observable::Observable;
+    use crate::sources::{create, from_iter, range, throw, Emitter};
+    use crate::stream::block_on_poll;
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::{TestObserver, TestSubscriber};
+
+    fn block_on<F>(future: F) -> F::Output
+        where F: Future {
+        let mut future = pin!(future);
+        block_on_poll(|cx| future.as_mut().poll(cx))
+    }
+
+    #[test]
+    fn first_future_resolves_to_the_first_value() {
+        assert_eq!(block_on(range(3, 10).first_future()).ok(), Some(Some(3)));
+    }
+
+    #[test]
+    fn first_future_resolves_to_none_on_an_empty_source() {
+        assert_eq!(block_on(from_iter(Vec::<i32>::new()).first_future()).ok(), Some(None));
+    }
+
+    #[test]
+    fn first_future_resolves_to_the_error() {
+        match block_on(throw::<i32>(Arc::new(MarbleError)).first_future()) {
+            Err(err) => assert!(err.downcast_ref::<MarbleError>().is_some()),
+            Ok(val) => panic!("expected the error, got {:?}", val)
+        }
+    }
+
+    #[test]
+    fn first_future_stops_the_source_after_one_value() {
+        let seen = Arc::new(AtomicUsize::new(0));
+        let counter = seen.clone();
+        let source = range(0, i32::MAX).tap(move |_| { counter.fetch_add(1, Ordering::SeqCst); });
+        assert_eq!(block_on(source.first_future()).ok(), Some(Some(0)));
+        assert_eq!(seen.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn into_future_collects_every_value() {
+        assert_eq!(block_on(range(0, 4).into_future()).ok(), Some(vec![0, 1, 2, 3]));
+        assert_eq!(block_on(from_iter(Vec::<i32>::new()).into_future()).ok(), Some(vec![]));
+    }
+
+    #[test]
+    fn into_future_resolves_to_the_error() {
+        let source = create(|emitter: Emitter<i32>| {
+            emitter.next(1);
+            emitter.error(Arc::new(MarbleError));
+        });
+        match block_on(source.into_future()) {
+            Err(err) => assert!(err.downcast_ref::<MarbleError>().is_some()),
+            Ok(vals) => panic!("expected the error, got {:?}", vals)
+        }
+    }
+
+    #[test]
+    fn from_future_emits_the_output_then_completes() {
+        let observer = TestObserver::new();
+        from_future(async { 7 }).subscribe(observer.clone());
+        observer.assert_values(&[7]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn from_future_resolves_once() {
+        let source = from_future(async { 7 });
+        let first = TestObserver::new();
+        let second = TestObserver::new();
+        source.subscribe(first.clone());
+        source.subscribe(second.clone());
+        first.assert_values(&[7]);
+        second.assert_values(&[]);
+        second.assert_completed();
+    }
+
+    #[test]
+    fn from_future_as_a_flowable_waits_for_a_request() {
+        let polled = Arc::new(AtomicUsize::new(0));
+        let counter = polled.clone();
+        let subscriber = TestSubscriber::new(0);
+        from_future(async move { counter.fetch_add(1, Ordering::SeqCst); 7 }).subscribe_flow(subscriber.clone());
+        assert_eq!(polled.load(Ordering::SeqCst), 0);
+
+        subscriber.request(1);
+        assert_eq!(polled.load(Ordering::SeqCst), 1);
+        subscriber.observer().assert_values(&[7]);
+        subscriber.request(1);
+        subscriber.observer().assert_completed();
+    }
+}
@@ -0,0 +1,259 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::observable::Observable;
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::materialize::Notification;
+use crate::sync::MutexExt;
+
+// An error read back from a recording. Only its message survives the trip.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedError(pub String);
+
+impl fmt::Display for RecordedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl StdError for RecordedError {}
+
+//////////////ToJsonLines
+// Writes each notification as a line of JSON, errors as their message. The
+// writer is flushed and let go when the stream ends, and a failed write stops
+// the stream.
+pub(crate) struct JsonLinesObserver<A, W> {
+    pub(crate) writer: Option<W>,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, W> JsonLinesObserver<A, W>
+    where A: Serialize,
+          W: Write {
+    fn write(&mut self, notification: Notification<&A, String>) -> IterationResult {
+        let written = match self.writer {
+            Some(ref mut writer) => serde_json::to_writer(&mut *writer, &notification).is_ok() && writer.write_all(b"\n").is_ok(),
+            None => false
+        };
+
+        if written {
+            Continue
+        } else {
+            self.writer.take();
+            Stop
+        }
+    }
+
+    fn finish(&mut self, notification: Notification<&A, String>) {
+        if let Continue = self.write(notification) {
+            if let Some(mut writer) = self.writer.take() {
+                let _ = writer.flush();
+            }
+        }
+    }
+}
+
+impl<A, W, E> Observer<E> for JsonLinesObserver<A, W>
+    where A: Serialize,
+          W: Write,
+          E: fmt::Display {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.write(Notification::Next(&val))
+    }
+
+    fn completed(&mut self) {
+        self.finish(Notification::Completed);
+    }
+
+    fn error(&mut self, err: E) {
+        self.finish(Notification::Error(err.to_string()));
+    }
+}
+//ToJsonLines//////////////
+
+//////////////FromJsonLines
+// Replays what to_json_lines wrote. A recorded error comes back as a
+// RecordedError, and a recording that stops without a terminal line just
+// completes. Lines that won't read or parse fail the stream. As with
+// from_receiver, the reader can only be read once, so later subscribers just
+// see it complete.
+pub struct JsonLinesObservable<R, A> {
+    pub(crate) reader: Mutex<Option<R>>,
+    pub(crate) _marker: PhantomData<fn() -> A>
+}
+
+impl<R, A> Observable for JsonLinesObservable<R, A>
+    where R: BufRead,
+          A: DeserializeOwned {
+    type Item = A;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let reader = match self.reader.acquire().take() {
+            Some(reader) => reader,
+            None => return observer.completed()
+        };
+
+        for line in reader.lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return observer.error(Arc::new(err))
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str(&line) {
+                Ok(Notification::Next(val)) => {
+                    if let Stop = observer.next(val) {
+                        return;
+                    }
+                },
+                Ok(Notification::Error(message)) => return observer.error(Arc::new(RecordedError(message))),
+                Ok(Notification::Completed) => return observer.completed(),
+                Err(err) => return observer.error(Arc::new(err))
+            }
+        }
+        observer.completed();
+    }
+}
+
+pub fn from_json_lines<A, R>(reader: R) -> JsonLinesObservable<R, A>
+    where R: BufRead,
+          A: DeserializeOwned {
+    JsonLinesObservable {reader: Mutex::new(Some(reader)), _marker: PhantomData}
+}
+//FromJsonLines//////////////
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io::{self, Cursor, Write};
+    use std::sync::{Arc, Mutex};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::json::{from_json_lines, RecordedError};
+    use crate::observable::Observable;
+    use crate::sources::{from_iter, range, throw};
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::TestObserver;
+
+    // A sink whose bytes can still be read once it's been handed off
+    #[derive(Clone, Default)]
+    pub(crate) struct SharedBuffer(pub(crate) Arc<Mutex<Vec<u8>>>);
+
+    impl SharedBuffer {
+        pub(crate) fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct BrokenWriter;
+
+    impl Write for BrokenWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Reading {
+        sensor: String,
+        value: f64
+    }
+
+    #[test]
+    fn to_json_lines_writes_a_line_per_notification() {
+        let buffer = SharedBuffer::default();
+        range(1, 3).to_json_lines(buffer.clone());
+        assert_eq!(buffer.text(), "{\"Next\":1}\n{\"Next\":2}\n\"Completed\"\n");
+
+        let buffer = SharedBuffer::default();
+        throw::<i32>(Arc::new(MarbleError)).to_json_lines(buffer.clone());
+        assert_eq!(buffer.text(), "{\"Error\":\"marble error\"}\n");
+    }
+
+    #[test]
+    fn json_lines_round_trip() {
+        let readings = vec![
+            Reading {sensor: "a".to_string(), value: 1.5},
+            Reading {sensor: "b".to_string(), value: -2.0}
+        ];
+        let buffer = SharedBuffer::default();
+        from_iter(readings.clone()).to_json_lines(buffer.clone());
+
+        let observer = TestObserver::new();
+        from_json_lines::<Reading, _>(Cursor::new(buffer.text())).subscribe(observer.clone());
+        observer.assert_values(&readings);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn a_recorded_error_comes_back_with_its_message() {
+        let buffer = SharedBuffer::default();
+        throw::<i32>(Arc::new(MarbleError)).to_json_lines(buffer.clone());
+
+        let observer = TestObserver::<i32>::new();
+        from_json_lines(Cursor::new(buffer.text())).subscribe(observer.clone());
+        observer.assert_error();
+        assert_eq!(observer.errors()[0].downcast_ref::<RecordedError>(), Some(&RecordedError("marble error".to_string())));
+    }
+
+    #[test]
+    fn from_json_lines_completes_a_cut_off_recording_and_fails_on_a_bad_line() {
+        let observer = TestObserver::new();
+        from_json_lines(Cursor::new("{\"Next\":1}\n\n{\"Next\":2}\n")).subscribe(observer.clone());
+        observer.assert_values(&[1, 2]);
+        observer.assert_completed();
+
+        let observer = TestObserver::<i32>::new();
+        from_json_lines(Cursor::new("{\"Next\":1}\nnot json\n{\"Next\":2}\n")).subscribe(observer.clone());
+        observer.assert_values(&[1]);
+        observer.assert_error();
+        assert!(observer.errors()[0].downcast_ref::<serde_json::Error>().is_some());
+    }
+
+    #[test]
+    fn from_json_lines_is_read_once() {
+        let source = from_json_lines::<i32, _>(Cursor::new("{\"Next\":1}\n\"Completed\"\n"));
+        let first = TestObserver::new();
+        let second = TestObserver::new();
+        source.subscribe(first.clone());
+        source.subscribe(second.clone());
+        first.assert_values(&[1]);
+        second.assert_values(&[]);
+        second.assert_completed();
+    }
+
+    #[test]
+    fn a_failed_write_stops_the_stream() {
+        let emitted = Arc::new(AtomicUsize::new(0));
+        let counter = emitted.clone();
+        range(0, i32::MAX).tap(move |_| { counter.fetch_add(1, Ordering::SeqCst); }).to_json_lines(BrokenWriter);
+        assert_eq!(emitted.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,50 @@
+#![allow(clippy::redundant_field_names, clippy::new_without_default, clippy::type_complexity)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod observer;
+pub mod observable;
+#[cfg(feature = "std")]
+pub mod flow;
+pub mod sources;
+pub mod operators;
+pub mod pipe;
+pub mod try_observable;
+#[cfg(feature = "std")]
+pub mod subjects;
+#[cfg(feature = "std")]
+pub mod schedulers;
+#[cfg(feature = "std")]
+pub mod blocking;
+#[cfg(feature = "std")]
+pub mod net;
+#[cfg(all(feature = "std", unix))]
+pub mod signal;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod local;
+pub mod borrowed;
+pub mod pull;
+pub mod describe;
+#[cfg(feature = "futures")]
+pub mod stream;
+#[cfg(feature = "futures")]
+pub mod future;
+#[cfg(feature = "futures")]
+pub mod async_observer;
+#[cfg(feature = "crossbeam")]
+pub mod crossbeam;
+#[cfg(feature = "serde")]
+pub mod json;
+#[cfg(feature = "serde")]
+pub mod record;
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(feature = "std")]
+mod sync;
+pub mod prelude;
+
+pub use observer::{Observer, IterationResult, Error};
+pub use observable::Observable;
+pub use sources::{range, value, from_iter};
@@ -0,0 +1,355 @@
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use core::cell::RefCell;
+use core::marker::PhantomData;
+
+use crate::observer::{Error, FnObserver, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::filter::FilterObserver;
+use crate::operators::map::MapObserver;
+use crate::operators::merge::MergeState;
+use crate::operators::take::TakeObserver;
+use crate::sources::Step;
+
+// Observable for pipelines that run start to finish on one thread. Observers
+// are never sent anywhere, so neither they nor the items need be Send or
+// Sync, and shared state is an Rc<RefCell> rather than an Arc<Mutex>. Only the
+// synchronous operators are here; anything involving a scheduler needs
+// Observable.
+pub trait LocalObservable {
+    type Item;
+
+    fn subscribe<N>(&self, observer: N) where N: Observer<Item=Self::Item> + 'static;
+
+    #[inline]
+    fn subscribe_fn<F>(&self, next: F)
+        where F: FnMut(Self::Item) + 'static,
+              Self::Item: 'static {
+        self.subscribe(FnObserver::new(next))
+    }
+
+    #[inline]
+    fn subscribe_with<F, G, C>(&self, next: F, error: G, completed: C)
+        where F: FnMut(Self::Item) + 'static,
+              G: FnMut(Error) + 'static,
+              C: FnMut() + 'static,
+              Self::Item: 'static {
+        self.subscribe(FnObserver::new(next).on_error(error).on_completed(completed))
+    }
+
+    #[inline]
+    fn map<B, F>(self, f: F) -> LocalMapObservable<F, Self>
+        where F: Fn(Self::Item) -> B,
+              Self: Sized {
+        LocalMapObservable {f: Arc::new(f), source: self}
+    }
+
+    #[inline]
+    fn filter<P>(self, predicate: P) -> LocalFilterObservable<P, Self>
+        where P: Fn(&Self::Item) -> bool,
+              Self: Sized {
+        LocalFilterObservable {predicate: Arc::new(predicate), source: self}
+    }
+
+    #[inline]
+    fn take(self, count: usize) -> LocalTakeObservable<Self>
+        where Self: Sized {
+        LocalTakeObservable {count: count, source: self}
+    }
+
+    #[inline]
+    fn merge_all(self) -> LocalMergeAllObservable<Self>
+        where Self: Sized {
+        LocalMergeAllObservable {source: self}
+    }
+
+    #[inline]
+    fn flat_map<U, F>(self, f: F) -> LocalMergeAllObservable<LocalMapObservable<F, Self>>
+        where F: Fn(Self::Item) -> U,
+              U: LocalObservable,
+              Self: Sized {
+        LocalMergeAllObservable {source: self.map(f)}
+    }
+}
+
+//////////////Sources
+pub struct LocalRangeObservable<A> {
+    pub(crate) start: A,
+    pub(crate) end: A
+}
+
+impl<A> LocalObservable for LocalRangeObservable<A>
+    where A: Step {
+    type Item = A;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + 'static {
+        let mut state = self.start.clone();
+        while state < self.end {
+            if let Stop = observer.next(state.clone()) {
+                break;
+            }
+            state = state.successor();
+        }
+        observer.completed();
+    }
+}
+
+pub fn range<A: Step>(start: A, end: A) -> LocalRangeObservable<A> {
+    LocalRangeObservable {start: start, end: end}
+}
+
+pub struct LocalValueObservable<A> {
+    pub(crate) value: A
+}
+
+impl<A> LocalObservable for LocalValueObservable<A>
+    where A: Clone {
+    type Item = A;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + 'static {
+        observer.next(self.value.clone());
+        observer.completed();
+    }
+}
+
+pub fn value<A: Clone>(value: A) -> LocalValueObservable<A> {
+    LocalValueObservable {value: value}
+}
+
+// Iterates a fresh clone of the iterator for every subscription
+pub struct LocalIterObservable<I> {
+    pub(crate) iter: I
+}
+
+impl<I> LocalObservable for LocalIterObservable<I>
+    where I: IntoIterator + Clone {
+    type Item = I::Item;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + 'static {
+        for val in self.iter.clone() {
+            if let Stop = observer.next(val) {
+                break;
+            }
+        }
+        observer.completed();
+    }
+}
+
+pub fn from_iter<I>(iter: I) -> LocalIterObservable<I>
+    where I: IntoIterator + Clone {
+    LocalIterObservable {iter: iter}
+}
+//Sources//////////////
+
+//////////////Operators
+// These reuse the observers behind the thread safe operators, which only
+// ask for Send and Sync when they're subscribed through Observable
+pub struct LocalMapObservable<F, S> {
+    pub(crate) f: Arc<F>,
+    pub(crate) source: S
+}
+
+impl<B, F, S> LocalObservable for LocalMapObservable<F, S>
+    where S: LocalObservable,
+          S::Item: 'static,
+          F: Fn(S::Item) -> B + 'static {
+    type Item = B;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + 'static {
+        self.source.subscribe(MapObserver {f: self.f.clone(), observer: observer, _marker: PhantomData});
+    }
+}
+
+pub struct LocalFilterObservable<P, S> {
+    pub(crate) predicate: Arc<P>,
+    pub(crate) source: S
+}
+
+impl<P, S> LocalObservable for LocalFilterObservable<P, S>
+    where S: LocalObservable,
+          S::Item: 'static,
+          P: Fn(&S::Item) -> bool + 'static {
+    type Item = S::Item;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + 'static {
+        self.source.subscribe(FilterObserver {predicate: self.predicate.clone(), observer: observer, _marker: PhantomData});
+    }
+}
+
+pub struct LocalTakeObservable<S> {
+    pub(crate) count: usize,
+    pub(crate) source: S
+}
+
+impl<S> LocalObservable for LocalTakeObservable<S>
+    where S: LocalObservable,
+          S::Item: 'static {
+    type Item = S::Item;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + 'static {
+        if self.count == 0 {
+            return observer.completed();
+        }
+        self.source.subscribe(TakeObserver {remaining: self.count, done: false, observer: observer, _marker: PhantomData});
+    }
+}
+
+pub struct LocalMergeAllObservable<S> {
+    pub(crate) source: S
+}
+
+impl<S, U> LocalObservable for LocalMergeAllObservable<S>
+    where S: LocalObservable<Item=U>,
+          U: LocalObservable + 'static,
+          U::Item: 'static {
+    type Item = U::Item;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + 'static {
+        let state = MergeState {observer: observer, active: 1, stopped: false, terminated: false};
+        self.source.subscribe(LocalMergeAllObserver {state: Rc::new(RefCell::new(state)), _marker: PhantomData});
+    }
+}
+
+// The borrow is never held across subscribing to an inner observable, which
+// may emit straight away
+pub(crate) struct LocalMergeAllObserver<N, U> {
+    pub(crate) state: Rc<RefCell<MergeState<N>>>,
+    pub(crate) _marker: PhantomData<U>
+}
+
+impl<N, U> Observer for LocalMergeAllObserver<N, U>
+    where U: LocalObservable,
+          U::Item: 'static,
+          N: Observer<Item=U::Item> + 'static {
+    type Item = U;
+
+    fn next(&mut self, val: U) -> IterationResult {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.stopped || state.terminated {
+                return Stop;
+            }
+            state.active += 1;
+        }
+
+        val.subscribe(LocalSharedObserver {state: self.state.clone()});
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.state.borrow_mut().completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.state.borrow_mut().error(err);
+    }
+}
+
+pub(crate) struct LocalSharedObserver<N> {
+    pub(crate) state: Rc<RefCell<MergeState<N>>>
+}
+
+impl<N> Observer for LocalSharedObserver<N>
+    where N: Observer {
+    type Item = N::Item;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.state.borrow_mut().next(val)
+    }
+
+    fn completed(&mut self) {
+        self.state.borrow_mut().completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.state.borrow_mut().error(err);
+    }
+}
+//Operators//////////////
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::cell::RefCell;
+    use std::fmt::Debug;
+    use std::rc::Rc;
+
+    use crate::local::{self, LocalObservable};
+    use crate::observable::Observable;
+    use crate::sources;
+    use crate::testing::observer::TestObserver;
+
+    // What the plain chain and its local twin should agree on
+    fn outcome<S>(source: S) -> (Vec<S::Item>, usize)
+        where S: Observable,
+              S::Item: Clone + Send + 'static {
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        (observer.values(), observer.completion_count())
+    }
+
+    fn local_outcome<S>(source: S) -> (Vec<S::Item>, usize)
+        where S: LocalObservable,
+              S::Item: Clone + Send + 'static {
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        (observer.values(), observer.completion_count())
+    }
+
+    fn assert_same<A>(expected: (Vec<A>, usize), actual: (Vec<A>, usize))
+        where A: PartialEq + Debug {
+        assert_eq!(actual, expected);
+        assert_eq!(actual.1, 1);
+    }
+
+    #[test]
+    fn map_filter_take_matches_observable() {
+        assert_same(
+            outcome(sources::range(0, 20).map(|val| val * 3).filter(|val| val % 2 == 0).take(4)),
+            local_outcome(local::range(0, 20).map(|val| val * 3).filter(|val| val % 2 == 0).take(4)));
+    }
+
+    #[test]
+    fn flat_map_matches_observable() {
+        assert_same(
+            outcome(sources::range(0, 5).flat_map(|val| sources::range(0, val))),
+            local_outcome(local::range(0, 5).flat_map(|val| local::range(0, val))));
+        assert_same(
+            outcome(sources::range(0, 5).flat_map(|val| sources::range(0, val)).take(4)),
+            local_outcome(local::range(0, 5).flat_map(|val| local::range(0, val)).take(4)));
+    }
+
+    #[test]
+    fn sources_match_observable() {
+        assert_same(outcome(sources::value(3)), local_outcome(local::value(3)));
+        assert_same(outcome(sources::from_iter(vec![1, 2, 3])), local_outcome(local::from_iter(vec![1, 2, 3])));
+        assert_same(outcome(sources::range(0, 4).take(0)), local_outcome(local::range(0, 4).take(0)));
+    }
+
+    #[test]
+    fn take_stops_an_endless_local_source() {
+        assert_same(
+            outcome(sources::range(0, i32::MAX).flat_map(|_| sources::range(0, 2)).take(3)),
+            local_outcome(local::range(0, i32::MAX).flat_map(|_| local::range(0, 2)).take(3)));
+    }
+
+    #[test]
+    fn items_and_observers_neednt_be_send() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let completed = Rc::new(RefCell::new(false));
+        let into = seen.clone();
+        let done = completed.clone();
+        let offset = Rc::new(10);
+        local::from_iter(vec![Rc::new(1), Rc::new(2), Rc::new(3)])
+            .map(move |val| *val + *offset)
+            .subscribe_with(move |val| into.borrow_mut().push(val), |_| {}, move || *done.borrow_mut() = true);
+        assert_eq!(*seen.borrow(), vec![11, 12, 13]);
+        assert!(*completed.borrow());
+    }
+}
@@ -0,0 +1,282 @@
+use std::io::{self, BufReader, Read, Write};
+use std::marker::PhantomData;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::blocking::{LinesObservable, from_lines_on};
+use crate::observable::Observable;
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::schedulers::new_thread::NewThreadScheduler;
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+//////////////Listen
+// Binds when subscribed and emits each connection accepted, so every
+// subscriber gets a listener of its own. Accepting blocks, as does reading a
+// connection, so the scheduler should be one that gives each a thread, like
+// the I/O one. The listener is closed once the downstream stops.
+pub struct TcpListenObservable<D> {
+    pub(crate) addrs: Vec<SocketAddr>,
+    pub(crate) scheduler: D
+}
+
+impl<D> Observable for TcpListenObservable<D>
+    where D: Scheduler + Clone + 'static {
+    type Item = Connection<D>;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let addrs = self.addrs.clone();
+        let scheduler = self.scheduler.clone();
+
+        self.scheduler.schedule(Box::new(move || {
+            let listener = match TcpListener::bind(&addrs[..]) {
+                Ok(listener) => listener,
+                Err(err) => return observer.error(Arc::new(err))
+            };
+
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(err) => return observer.error(Arc::new(err))
+                };
+                let peer = match stream.peer_addr() {
+                    Ok(peer) => peer,
+                    // Gone before it could be looked at
+                    Err(_) => continue
+                };
+
+                let connection = Connection {
+                    peer: peer,
+                    stream: stream,
+                    subscribed: AtomicBool::new(false),
+                    scheduler: scheduler.clone()
+                };
+                if let Stop = observer.next(connection) {
+                    return;
+                }
+            }
+        }));
+    }
+}
+
+// An address that doesn't resolve fails the stream when it's subscribed
+pub fn tcp_listen<A>(addr: A) -> TcpListenObservable<NewThreadScheduler>
+    where A: ToSocketAddrs {
+    tcp_listen_on(addr, NewThreadScheduler::io())
+}
+
+pub fn tcp_listen_on<A, D>(addr: A, scheduler: D) -> TcpListenObservable<D>
+    where A: ToSocketAddrs,
+          D: Scheduler {
+    let addrs = addr.to_socket_addrs().map(Iterator::collect).unwrap_or_default();
+    TcpListenObservable {addrs: addrs, scheduler: scheduler}
+}
+//Listen//////////////
+
+//////////////Connection
+// One accepted connection, observable as the chunks of bytes it receives
+// until the peer closes its side. The socket can only be read from one
+// place, so later subscribers, and lines once it's been subscribed, just see
+// it complete.
+pub struct Connection<D> {
+    pub(crate) peer: SocketAddr,
+    pub(crate) stream: TcpStream,
+    pub(crate) subscribed: AtomicBool,
+    pub(crate) scheduler: D
+}
+
+impl<D> Connection<D> {
+    #[inline]
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.peer
+    }
+
+    #[inline]
+    pub fn stream(&self) -> &TcpStream {
+        &self.stream
+    }
+
+    // A handle on the socket to hand to write_to
+    pub fn writer(&self) -> io::Result<TcpStream> {
+        self.stream.try_clone()
+    }
+
+    fn take_reader(&self) -> Option<io::Result<TcpStream>> {
+        if self.subscribed.swap(true, Ordering::SeqCst) {
+            None
+        } else {
+            Some(self.stream.try_clone())
+        }
+    }
+}
+
+impl<D> Connection<D>
+    where D: Scheduler + Clone {
+    // The received text a line at a time instead
+    pub fn lines(&self) -> io::Result<LinesObservable<BufReader<TcpStream>, D>> {
+        match self.take_reader() {
+            Some(reader) => Ok(from_lines_on(BufReader::new(reader?), self.scheduler.clone())),
+            None => Ok(LinesObservable {reader: Mutex::new(None), scheduler: self.scheduler.clone()})
+        }
+    }
+}
+
+impl<D> Observable for Connection<D>
+    where D: Scheduler {
+    type Item = Vec<u8>;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let mut reader = match self.take_reader() {
+            Some(Ok(reader)) => reader,
+            Some(Err(err)) => return observer.error(Arc::new(err)),
+            None => return observer.completed()
+        };
+
+        self.scheduler.schedule(Box::new(move || {
+            let mut buf = vec![0; CHUNK_SIZE];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(read) => {
+                        if let Stop = observer.next(buf[..read].to_vec()) {
+                            return;
+                        }
+                    },
+                    Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => return observer.error(Arc::new(err))
+                }
+            }
+            observer.completed();
+        }));
+    }
+}
+//Connection//////////////
+
+//////////////WriteTo
+// Writes each item's bytes out, flushing and letting go of the writer when
+// the stream ends. A failed write stops the stream.
+pub(crate) struct WriteObserver<W, A> {
+    pub(crate) writer: Option<W>,
+    pub(crate) _marker: PhantomData<fn(A)>
+}
+
+impl<W, A> WriteObserver<W, A>
+    where W: Write {
+    fn finish(&mut self) {
+        if let Some(mut writer) = self.writer.take() {
+            let _ = writer.flush();
+        }
+    }
+}
+
+impl<W, A, E> Observer<E> for WriteObserver<W, A>
+    where W: Write,
+          A: AsRef<[u8]> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let written = match self.writer {
+            Some(ref mut writer) => writer.write_all(val.as_ref()).is_ok(),
+            None => false
+        };
+
+        if written {
+            Continue
+        } else {
+            self.writer.take();
+            Stop
+        }
+    }
+
+    fn completed(&mut self) {
+        self.finish();
+    }
+
+    fn error(&mut self, _: E) {
+        self.finish();
+    }
+}
+//WriteTo//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use crate::net::tcp_listen;
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::testing::observer::TestObserver;
+
+    // A loopback address nothing's listening on, as far as can be told
+    fn free_addr() -> SocketAddr {
+        TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap()
+    }
+
+    // The listener binds on its own thread, so keep trying for a while
+    fn connect(addr: SocketAddr) -> TcpStream {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            match TcpStream::connect(addr) {
+                Ok(stream) => return stream,
+                Err(_) if Instant::now() < deadline => thread::sleep(Duration::from_millis(5)),
+                Err(err) => panic!("couldn't connect: {}", err)
+            }
+        }
+    }
+
+    #[test]
+    fn connections_can_be_echoed_back_through_write_to() {
+        let addr = free_addr();
+        tcp_listen(addr).take(1).subscribe_fn(|connection| {
+            let writer = connection.writer().unwrap();
+            connection.write_to(writer);
+        });
+
+        let mut client = connect(addr);
+        client.write_all(b"hello").unwrap();
+        client.shutdown(Shutdown::Write).unwrap();
+        let mut echoed = String::new();
+        client.read_to_string(&mut echoed).unwrap();
+        assert_eq!(echoed, "hello");
+    }
+
+    #[test]
+    fn a_connection_reads_as_lines_once() {
+        let addr = free_addr();
+        let lines = TestObserver::new();
+        let again = TestObserver::new();
+        let peers = TestObserver::new();
+        let (into, after, mut peer) = (lines.clone(), again.clone(), peers.clone());
+        tcp_listen(addr).take(1).subscribe_fn(move |connection| {
+            peer.next(connection.peer_addr());
+            connection.lines().unwrap().subscribe(into.clone());
+            connection.subscribe(after.clone());
+        });
+
+        let mut client = connect(addr);
+        client.write_all(b"first\nsecond\n").unwrap();
+        client.shutdown(Shutdown::Write).unwrap();
+        assert!(lines.await_terminal_event(Duration::from_secs(5)));
+        lines.assert_values(&["first".to_string(), "second".to_string()]);
+        lines.assert_completed();
+        again.assert_values(&[]);
+        again.assert_completed();
+        assert_eq!(peers.values(), vec![client.local_addr().unwrap()]);
+    }
+
+    #[test]
+    fn an_address_that_doesnt_resolve_fails_on_subscribe() {
+        let observer = TestObserver::new();
+        tcp_listen("no such host:80").subscribe(observer.clone());
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_error();
+    }
+}
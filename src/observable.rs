@@ -0,0 +1,1305 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicUsize;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::sync::mpsc::{sync_channel, Sender};
+#[cfg(feature = "std")]
+use std::thread;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+#[cfg(feature = "std")]
+use crate::blocking::{BlockingObservable, ChannelObserver, SenderObserver, subscribe_blocking};
+use crate::describe::{Node, short_name};
+use crate::observer::{BoxObserver, Error, FnObserver, Observer, WeakObserver};
+#[cfg(feature = "std")]
+use crate::net::WriteObserver;
+#[cfg(feature = "std")]
+use crate::observer::DynObserver;
+#[cfg(feature = "std")]
+use crate::schedulers::{Scheduler, TimerScheduler};
+#[cfg(feature = "std")]
+use crate::sources::{IntervalObservable, NeverObservable, ThrowObservable, TimerObservable, interval, interval_on, throw, timer, timer_on};
+#[cfg(feature = "std")]
+use crate::subjects::{AsyncSubject, BehaviorSubject, ReplaySubject, Subject};
+use crate::pipe::Pipeable;
+#[cfg(feature = "std")]
+use crate::operators::auto_connect::AutoConnectObservable;
+#[cfg(feature = "std")]
+use crate::operators::backpressure::{BackpressureFlowable, Overflow};
+#[cfg(feature = "std")]
+use crate::operators::batch::BatchObservable;
+#[cfg(feature = "std")]
+use crate::operators::buffer_toggle::BufferToggleObservable;
+use crate::operators::catch::CatchObservable;
+#[cfg(feature = "std")]
+use crate::operators::catch_panics::{CatchPanicsObservable, PanicPolicy};
+#[cfg(feature = "std")]
+use crate::operators::channelize::ChannelizeObservable;
+#[cfg(feature = "std")]
+use crate::operators::connectable::{ConnectableObservable, SubjectFactory};
+#[cfg(feature = "std")]
+use crate::operators::debounce::DebounceObservable;
+#[cfg(feature = "std")]
+use crate::operators::delay::{DelayEachObservable, DelayObservable};
+#[cfg(feature = "std")]
+use crate::operators::delay_subscription::DelaySubscriptionObservable;
+use crate::operators::finally::FinallyObservable;
+#[cfg(any(feature = "log", feature = "tracing"))]
+use crate::operators::instrument::InstrumentObservable;
+use crate::operators::chunk_by::{ChunkByObservable, ChunkByOperator};
+#[cfg(feature = "std")]
+use crate::operators::concat::ConcatWithObservable;
+use crate::operators::enumerate::{EnumerateObservable, EnumerateOperator, FilterWithIndexObservable, FilterWithIndexOperator, MapWithIndexObservable, MapWithIndexOperator};
+use crate::operators::filter::{FilterObservable, FilterOperator};
+use crate::operators::filter_map::{FilterMapObservable, FilterMapOperator};
+#[cfg(feature = "std")]
+use crate::operators::group_by::{GroupByObservable, GroupedObservable, never_closes};
+#[cfg(feature = "std")]
+use crate::operators::latest_only::LatestOnlyObservable;
+use crate::operators::lift::LiftObservable;
+use crate::operators::map::{MapObservable, MapOperator};
+use crate::operators::map_error::MapErrorObservable;
+use crate::operators::materialize::{DematerializeObservable, MaterializeObservable, Notification};
+#[cfg(feature = "std")]
+use crate::operators::merge::MergeAllObservable;
+#[cfg(feature = "std")]
+use crate::operators::metrics::{Metrics, MetricsObservable};
+#[cfg(feature = "std")]
+use crate::operators::multicast::MulticastObservable;
+#[cfg(feature = "std")]
+use crate::operators::observe_on::ObserveOnObservable;
+use crate::operators::on_error_resume_next::OnErrorResumeNextObservable;
+use crate::operators::on_error_return::OnErrorReturnObservable;
+#[cfg(feature = "std")]
+use crate::operators::prefetch::PrefetchObservable;
+#[cfg(feature = "std")]
+use crate::operators::ref_count::RefCountObservable;
+#[cfg(feature = "std")]
+use crate::operators::reorder::{LatePolicy, ReorderObservable};
+#[cfg(feature = "std")]
+use crate::operators::repeat::{RepeatObservable, RepeatWhileObservable};
+#[cfg(feature = "std")]
+use crate::operators::retry::{BackoffPolicy, FixedBackoff, RetryObservable};
+#[cfg(feature = "std")]
+use crate::operators::retry_when::{SignalObservable, WhenObservable};
+#[cfg(feature = "std")]
+use crate::operators::sample::SampleObservable;
+#[cfg(feature = "std")]
+use crate::operators::scan_shared::{ScanSharedObservable, StateHandle};
+#[cfg(feature = "std")]
+use crate::operators::subscribe_on::SubscribeOnObservable;
+use crate::operators::take::{TakeObservable, TakeOperator};
+#[cfg(feature = "std")]
+use crate::operators::take_until::{DropGuard, TakeUntilObservable};
+use crate::operators::tap::{DoOnCompleteObservable, DoOnErrorObservable, DoOnSubscribeObservable, TapObservable};
+#[cfg(feature = "std")]
+use crate::operators::rate_limit::{RateLimit, RateLimitObservable};
+#[cfg(feature = "std")]
+use crate::operators::throttle::{ThrottleMode, ThrottleObservable};
+#[cfg(feature = "std")]
+use crate::operators::time_interval::TimeIntervalObservable;
+#[cfg(feature = "std")]
+use crate::operators::timeout::{TimeoutError, TimeoutObservable};
+#[cfg(feature = "std")]
+use crate::operators::timestamp::TimestampObservable;
+#[cfg(feature = "std")]
+use crate::schedulers::new_thread::NewThreadScheduler;
+#[cfg(feature = "futures")]
+use crate::stream::ObservableStream;
+#[cfg(feature = "futures")]
+use std::future::Future;
+#[cfg(feature = "crossbeam")]
+use crate::crossbeam::CrossbeamObserver;
+#[cfg(feature = "serde")]
+use crate::json::JsonLinesObserver;
+#[cfg(feature = "serde")]
+use crate::record::RecordObservable;
+#[cfg(feature = "std")]
+use crate::operators::parallel::Parallel;
+#[cfg(feature = "rayon")]
+use crate::operators::par_map::{ParMapObservable, ParOrder};
+use crate::operators::zip_iter::{ZipIterObservable, ZipIterOperator};
+#[cfg(feature = "futures")]
+use crate::async_observer::{AsyncObserver, AsyncFnObserver, AsyncAdapter};
+#[cfg(feature = "futures")]
+use crate::future::{ObservableFuture, FirstObserver, CollectObserver};
+
+pub trait Observable<E = Error> {
+    type Item;
+
+    fn subscribe<N>(&self, observer: N) where N: Observer<E, Item=Self::Item> + Send + Sync + 'static;
+
+    // Subscribes by value, for a pipeline that's only subscribed once. Where
+    // subscribe has each operator share its closure with the observer it
+    // builds, bumping a reference count per operator, this hands it over.
+    // Sources that don't care just subscribe by reference.
+    #[inline]
+    fn subscribe_owned<N>(self, observer: N)
+        where N: Observer<E, Item=Self::Item> + Send + Sync + 'static,
+              Self: Sized {
+        self.subscribe(observer)
+    }
+
+    // This stage and the ones feeding it, as assembled. Sources are leaves,
+    // and operators add themselves on top of their upstreams.
+    fn node(&self) -> Node {
+        Node::leaf(short_name::<Self>())
+    }
+
+    // The pipeline as a chain, e.g. RangeObservable -> MapObservable
+    fn describe(&self) -> String {
+        self.node().describe()
+    }
+
+    // The pipeline as a Graphviz digraph
+    fn to_dot(&self) -> String {
+        self.node().to_dot()
+    }
+
+    #[inline]
+    fn subscribe_fn<F>(&self, next: F)
+        where F: FnMut(Self::Item) + Send + Sync + 'static,
+              Self::Item: 'static,
+              E: 'static {
+        self.subscribe(FnObserver::new(next))
+    }
+
+    #[inline]
+    fn subscribe_with<F, G, C>(&self, next: F, error: G, completed: C)
+        where F: FnMut(Self::Item) + Send + Sync + 'static,
+              G: FnMut(E) + Send + Sync + 'static,
+              C: FnMut() + Send + Sync + 'static,
+              Self::Item: 'static,
+              E: 'static {
+        self.subscribe(FnObserver::new(next).on_error(error).on_completed(completed))
+    }
+
+    #[inline]
+    fn subscribe_weak<T, F>(&self, owner: &Arc<T>, next: F)
+        where F: FnMut(&T, Self::Item) + Send + Sync + 'static,
+              T: Send + Sync + 'static,
+              Self::Item: 'static {
+        self.subscribe(WeakObserver::new(owner, next))
+    }
+
+    #[inline]
+    fn lift<O>(self, operator: O) -> LiftObservable<Self, O>
+        where Self: Sized {
+        LiftObservable {operator: operator, source: self}
+    }
+
+    #[inline]
+    fn map<B, F>(self, f: F) -> MapObservable<F, Self>
+        where F: Fn(Self::Item) -> B,
+              Self: Sized {
+        self.lift(MapOperator {f: Arc::new(f)})
+    }
+
+    #[inline]
+    fn take(self, count: usize) -> TakeObservable<Self>
+        where Self: Sized {
+        self.lift(TakeOperator {count: count})
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn take_until<T>(self, notifier: T) -> TakeUntilObservable<Self, T>
+        where T: Observable,
+              Self: Sized {
+        TakeUntilObservable {source: self, notifier: notifier}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn take_until_dropped(self, guard: &DropGuard) -> TakeUntilObservable<Self, AsyncSubject<()>>
+        where Self: Sized {
+        self.take_until(guard.dropped.clone())
+    }
+
+    #[inline]
+    fn map_error<X, F>(self, f: F) -> MapErrorObservable<F, Self, E>
+        where F: Fn(E) -> X,
+              Self: Sized {
+        MapErrorObservable {f: Arc::new(f), source: self, _marker: PhantomData}
+    }
+
+    // do_on_error for any error type, leaving the error as it was
+    #[inline]
+    fn inspect_err<F>(self, f: F) -> MapErrorObservable<impl Fn(E) -> E + Send + Sync + 'static, Self, E>
+        where F: Fn(&E) + Send + Sync + 'static,
+              Self: Sized {
+        self.map_error(move |err| {
+            f(&err);
+            err
+        })
+    }
+
+    // Converts errors with From, e.g. a library's error into the caller's
+    #[inline]
+    fn err_into<X>(self) -> MapErrorObservable<fn(E) -> X, Self, E>
+        where X: From<E>,
+              Self: Sized {
+        self.map_error(X::from as fn(E) -> X)
+    }
+
+    #[inline]
+    fn filter<P>(self, predicate: P) -> FilterObservable<P, Self>
+        where P: Fn(&Self::Item) -> bool,
+              Self: Sized {
+        self.lift(FilterOperator {predicate: Arc::new(predicate)})
+    }
+
+    #[inline]
+    fn filter_map<B, F>(self, f: F) -> FilterMapObservable<F, Self>
+        where F: Fn(Self::Item) -> Option<B>,
+              Self: Sized {
+        self.lift(FilterMapOperator {f: Arc::new(f)})
+    }
+
+    #[inline]
+    fn enumerate(self) -> EnumerateObservable<Self>
+        where Self: Sized {
+        self.lift(EnumerateOperator)
+    }
+
+    #[inline]
+    fn map_with_index<B, F>(self, f: F) -> MapWithIndexObservable<F, Self>
+        where F: Fn(usize, Self::Item) -> B,
+              Self: Sized {
+        self.lift(MapWithIndexOperator {f: Arc::new(f)})
+    }
+
+    #[inline]
+    fn filter_with_index<P>(self, predicate: P) -> FilterWithIndexObservable<P, Self>
+        where P: Fn(usize, &Self::Item) -> bool,
+              Self: Sized {
+        self.lift(FilterWithIndexOperator {predicate: Arc::new(predicate)})
+    }
+
+    // Emits each state folded from the seed, as a scan, alongside a handle
+    // that gives the latest of them to code outside the pipeline
+    #[cfg(feature = "std")]
+    #[inline]
+    fn scan_shared<B, F>(self, seed: B, f: F) -> (ScanSharedObservable<Self, F, B>, StateHandle<B>)
+        where F: Fn(B, Self::Item) -> B,
+              B: Clone,
+              Self: Sized {
+        let handle = StateHandle::new(seed.clone());
+        (ScanSharedObservable {seed: seed, f: Arc::new(f), handle: handle.clone(), source: self}, handle)
+    }
+
+    // Runs of consecutive items with the same key, as itertools' chunk_by
+    // makes of an iterator. Keys that come back later start a new run.
+    #[inline]
+    fn chunk_by<K, F>(self, key: F) -> ChunkByObservable<F, Self>
+        where F: Fn(&Self::Item) -> K,
+              K: PartialEq,
+              Self: Sized {
+        self.lift(ChunkByOperator {key: Arc::new(key)})
+    }
+
+    // Pairs each item with the iterator's next value, ending with whichever
+    // runs out first. The iterator is only pulled as items arrive.
+    #[inline]
+    fn zip_iter<I>(self, iter: I) -> ZipIterObservable<I, Self>
+        where I: IntoIterator + Clone,
+              Self: Sized {
+        self.lift(ZipIterOperator {iter: iter})
+    }
+
+    #[inline]
+    fn pipe<P>(self, pipeable: P) -> P::Output
+        where P: Pipeable<Self>,
+              Self: Sized {
+        pipeable.apply(self)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn merge_all<U>(self) -> MergeAllObservable<Self, U>
+        where Self: Sized {
+        MergeAllObservable {source: self, _marker: PhantomData}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn flat_map<U, F>(self, f: F) -> MergeAllObservable<MapObservable<F, Self>, U>
+        where F: Fn(Self::Item) -> U,
+              U: Observable<Item=Self::Item> + Send + Sync,
+              Self: Sized {
+        MergeAllObservable {source: self.map(f), _marker: PhantomData}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn group_by<K, F>(self, key: F) -> GroupByObservable<Self, F, fn(&GroupedObservable<K, Self::Item>) -> NeverObservable<()>>
+        where F: Fn(&Self::Item) -> K,
+              Self: Sized {
+        self.group_by_until(key, never_closes as fn(&GroupedObservable<K, Self::Item>) -> NeverObservable<()>)
+    }
+
+    // Closes each group when the observable made for it emits or terminates
+    #[cfg(feature = "std")]
+    #[inline]
+    fn group_by_until<K, F, G, U>(self, key: F, duration: G) -> GroupByObservable<Self, F, G>
+        where F: Fn(&Self::Item) -> K,
+              G: Fn(&GroupedObservable<K, Self::Item>) -> U,
+              Self: Sized {
+        GroupByObservable {key: Arc::new(key), duration: Arc::new(duration), source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn delay(self, delay: Duration) -> DelayObservable<Self, TimerScheduler>
+        where Self: Sized {
+        self.delay_on(delay, TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn delay_on<D>(self, delay: Duration, scheduler: D) -> DelayObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        DelayObservable {delay: delay, scheduler: scheduler, source: self}
+    }
+
+    // Holds each item for as long as the selector gives for it, such as a
+    // retry_after the item carries
+    #[cfg(feature = "std")]
+    #[inline]
+    fn delay_each<F>(self, selector: F) -> DelayEachObservable<Self, F, TimerScheduler>
+        where F: Fn(&Self::Item) -> Duration,
+              Self: Sized {
+        self.delay_each_on(selector, TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn delay_each_on<F, D>(self, selector: F, scheduler: D) -> DelayEachObservable<Self, F, D>
+        where F: Fn(&Self::Item) -> Duration,
+              D: Scheduler,
+              Self: Sized {
+        DelayEachObservable {selector: Arc::new(selector), scheduler: scheduler, source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn delay_subscription(self, delay: Duration) -> DelaySubscriptionObservable<Self, TimerObservable<TimerScheduler>>
+        where Self: Sized {
+        self.delay_subscription_until(timer(delay))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn delay_subscription_on<D>(self, delay: Duration, scheduler: D) -> DelaySubscriptionObservable<Self, TimerObservable<D>>
+        where D: Scheduler,
+              Self: Sized {
+        self.delay_subscription_until(timer_on(delay, scheduler))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn delay_subscription_until<T>(self, trigger: T) -> DelaySubscriptionObservable<Self, T>
+        where T: Observable,
+              Self: Sized {
+        DelaySubscriptionObservable {source: Arc::new(self), trigger: trigger}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn debounce(self, quiet: Duration) -> DebounceObservable<Self, TimerScheduler>
+        where Self: Sized {
+        self.debounce_on(quiet, TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn debounce_on<D>(self, quiet: Duration, scheduler: D) -> DebounceObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        DebounceObservable {quiet: quiet, scheduler: scheduler, source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn throttle(self, window: Duration) -> ThrottleObservable<Self, TimerScheduler>
+        where Self: Sized {
+        self.throttle_with(window, ThrottleMode::Leading)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn throttle_with(self, window: Duration, mode: ThrottleMode) -> ThrottleObservable<Self, TimerScheduler>
+        where Self: Sized {
+        self.throttle_with_on(window, mode, TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn throttle_with_on<D>(self, window: Duration, mode: ThrottleMode, scheduler: D) -> ThrottleObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        ThrottleObservable {window: window, mode: mode, scheduler: scheduler, source: self}
+    }
+
+    // At most permits items per interval, delaying the rest
+    #[cfg(feature = "std")]
+    #[inline]
+    fn rate_limit(self, permits: usize, interval: Duration) -> RateLimitObservable<Self, TimerScheduler>
+        where Self: Sized {
+        self.rate_limit_with(RateLimit::new(permits, interval))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn rate_limit_with(self, limit: RateLimit) -> RateLimitObservable<Self, TimerScheduler>
+        where Self: Sized {
+        self.rate_limit_with_on(limit, TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn rate_limit_with_on<D>(self, limit: RateLimit, scheduler: D) -> RateLimitObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        RateLimitObservable {limit: limit, scheduler: scheduler, source: self}
+    }
+
+    // Puts a stream that's slightly out of order back in order by key,
+    // holding each item for up to max_lateness. Stragglers behind what's
+    // already out are dropped.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn reorder_by<K, F>(self, key: F, max_lateness: Duration) -> ReorderObservable<Self, F, TimerScheduler>
+        where F: Fn(&Self::Item) -> K,
+              K: Ord,
+              Self: Sized {
+        self.reorder_by_with(key, max_lateness, LatePolicy::Drop)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn reorder_by_with<K, F>(self, key: F, max_lateness: Duration, policy: LatePolicy) -> ReorderObservable<Self, F, TimerScheduler>
+        where F: Fn(&Self::Item) -> K,
+              K: Ord,
+              Self: Sized {
+        self.reorder_by_with_on(key, max_lateness, policy, TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn reorder_by_with_on<K, F, D>(self, key: F, max_lateness: Duration, policy: LatePolicy, scheduler: D) -> ReorderObservable<Self, F, D>
+        where F: Fn(&Self::Item) -> K,
+              K: Ord,
+              D: Scheduler,
+              Self: Sized {
+        ReorderObservable {key: Arc::new(key), lateness: max_lateness, policy: policy, scheduler: scheduler, source: self}
+    }
+
+    // Vecs of up to max_size items, sent no later than max_delay after their
+    // first item, for writing out in bulk
+    #[cfg(feature = "std")]
+    #[inline]
+    fn batch(self, max_size: usize, max_delay: Duration) -> BatchObservable<Self, TimerScheduler>
+        where Self: Sized {
+        self.batch_on(max_size, max_delay, TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn batch_on<D>(self, max_size: usize, max_delay: Duration, scheduler: D) -> BatchObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        BatchObservable {max_size: max_size, max_delay: max_delay, scheduler: scheduler, source: self}
+    }
+
+    // Collects what the source sends between each item of openings and the
+    // closing observable built from it, as between start and stop markers
+    #[cfg(feature = "std")]
+    #[inline]
+    fn buffer_toggle<O, F, U>(self, openings: O, closing_selector: F) -> BufferToggleObservable<Self, O, F>
+        where O: Observable,
+              F: Fn(O::Item) -> U,
+              U: Observable,
+              Self: Sized {
+        BufferToggleObservable {openings: openings, closing: Arc::new(closing_selector), source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn sample<T>(self, sampler: T) -> SampleObservable<Self, T>
+        where T: Observable,
+              Self: Sized {
+        SampleObservable {source: self, sampler: sampler}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn sample_time(self, period: Duration) -> SampleObservable<Self, IntervalObservable<TimerScheduler>>
+        where Self: Sized {
+        self.sample(interval(period))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn sample_time_on<D>(self, period: Duration, scheduler: D) -> SampleObservable<Self, IntervalObservable<D>>
+        where D: Scheduler,
+              Self: Sized {
+        self.sample(interval_on(period, scheduler))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn timeout(self, duration: Duration) -> TimeoutObservable<Self, ThrowObservable<Self::Item>, TimerScheduler>
+        where Self: Sized {
+        self.timeout_on(duration, TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn timeout_on<D>(self, duration: Duration, scheduler: D) -> TimeoutObservable<Self, ThrowObservable<Self::Item>, D>
+        where D: Scheduler,
+              Self: Sized {
+        self.timeout_with_on(duration, throw(Arc::new(TimeoutError {duration: duration})), scheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn timeout_with<U>(self, duration: Duration, fallback: U) -> TimeoutObservable<Self, U, TimerScheduler>
+        where U: Observable<Item=Self::Item>,
+              Self: Sized {
+        self.timeout_with_on(duration, fallback, TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn timeout_with_on<U, D>(self, duration: Duration, fallback: U, scheduler: D) -> TimeoutObservable<Self, U, D>
+        where U: Observable<Item=Self::Item>,
+              D: Scheduler,
+              Self: Sized {
+        TimeoutObservable {duration: duration, scheduler: scheduler, source: self, fallback: Arc::new(fallback)}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn timestamp(self) -> TimestampObservable<Self, TimerScheduler>
+        where Self: Sized {
+        self.timestamp_on(TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn timestamp_on<D>(self, scheduler: D) -> TimestampObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        TimestampObservable {scheduler: scheduler, source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn time_interval(self) -> TimeIntervalObservable<Self, TimerScheduler>
+        where Self: Sized {
+        self.time_interval_on(TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn time_interval_on<D>(self, scheduler: D) -> TimeIntervalObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        TimeIntervalObservable {scheduler: scheduler, source: self}
+    }
+
+    // Counts and timings for everything passing through, read from handle()
+    #[cfg(feature = "std")]
+    #[inline]
+    fn metrics(self) -> MetricsObservable<Self, TimerScheduler>
+        where Self: Sized {
+        self.metrics_on(TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn metrics_on<D>(self, scheduler: D) -> MetricsObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        MetricsObservable {metrics: Metrics::new(scheduler), source: self}
+    }
+
+    #[cfg(feature = "serde")]
+    #[inline]
+    fn record<W>(self, sink: W) -> RecordObservable<Self, W, TimerScheduler>
+        where W: std::io::Write,
+              Self: Sized {
+        self.record_on(sink, TimerScheduler)
+    }
+
+    #[cfg(feature = "serde")]
+    #[inline]
+    fn record_on<W, D>(self, sink: W, scheduler: D) -> RecordObservable<Self, W, D>
+        where W: std::io::Write,
+              D: Scheduler,
+              Self: Sized {
+        RecordObservable {sink: std::sync::Mutex::new(Some(sink)), scheduler: scheduler, source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn retry(self, count: usize) -> RetryObservable<Self, FixedBackoff, TimerScheduler>
+        where Self: Sized {
+        self.retry_with(FixedBackoff::new(Duration::from_secs(0), count))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn retry_with<P>(self, policy: P) -> RetryObservable<Self, P, TimerScheduler>
+        where P: BackoffPolicy,
+              Self: Sized {
+        self.retry_with_on(policy, TimerScheduler)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn retry_with_on<P, D>(self, policy: P, scheduler: D) -> RetryObservable<Self, P, D>
+        where P: BackoffPolicy,
+              D: Scheduler,
+              Self: Sized {
+        RetryObservable {policy: policy, scheduler: scheduler, source: Arc::new(self)}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn retry_when<U, F>(self, handler: F) -> WhenObservable<Self, F, Error>
+        where F: Fn(SignalObservable<Error>) -> U,
+              U: Observable,
+              Self: Sized {
+        WhenObservable {handler: handler, source: Arc::new(self), _marker: PhantomData}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn repeat(self) -> RepeatObservable<Self>
+        where Self: Sized {
+        RepeatObservable {count: None, source: Arc::new(self)}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn repeat_n(self, count: usize) -> RepeatObservable<Self>
+        where Self: Sized {
+        RepeatObservable {count: Some(count), source: Arc::new(self)}
+    }
+
+    // Repeats until a round ends on an item matching the predicate, as when
+    // polling a job until its status reads done
+    #[cfg(feature = "std")]
+    #[inline]
+    fn repeat_until<P>(self, predicate: P) -> RepeatWhileObservable<Self, P>
+        where P: Fn(&Self::Item) -> bool,
+              Self: Sized {
+        RepeatWhileObservable {predicate: Arc::new(predicate), until: true, source: Arc::new(self)}
+    }
+
+    // Repeats for as long as each round ends on an item matching the predicate
+    #[cfg(feature = "std")]
+    #[inline]
+    fn do_while<P>(self, predicate: P) -> RepeatWhileObservable<Self, P>
+        where P: Fn(&Self::Item) -> bool,
+              Self: Sized {
+        RepeatWhileObservable {predicate: Arc::new(predicate), until: false, source: Arc::new(self)}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn repeat_when<U, F>(self, handler: F) -> WhenObservable<Self, F, ()>
+        where F: Fn(SignalObservable<()>) -> U,
+              U: Observable,
+              Self: Sized {
+        WhenObservable {handler: handler, source: Arc::new(self), _marker: PhantomData}
+    }
+
+    // Carries on with each of the sources in turn once this one completes
+    #[cfg(feature = "std")]
+    #[inline]
+    fn on_complete_resume_with<I>(self, rest: I) -> ConcatWithObservable<Self, I>
+        where I: IntoIterator + Clone,
+              I::Item: Observable<Item=Self::Item>,
+              Self: Sized {
+        ConcatWithObservable {source: self, rest: rest}
+    }
+
+    #[inline]
+    fn catch<U, F>(self, handler: F) -> CatchObservable<Self, F>
+        where F: Fn(Error) -> U,
+              U: Observable<Item=Self::Item>,
+              Self: Sized {
+        CatchObservable {handler: Arc::new(handler), source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn catch_panics(self) -> CatchPanicsObservable<Self>
+        where Self: Sized {
+        self.catch_panics_with(PanicPolicy::Error)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn catch_panics_with(self, policy: PanicPolicy) -> CatchPanicsObservable<Self>
+        where Self: Sized {
+        CatchPanicsObservable {policy: policy, source: self}
+    }
+
+    #[inline]
+    fn on_error_resume_next<U>(self, next: U) -> OnErrorResumeNextObservable<Self, U>
+        where U: Observable<Item=Self::Item>,
+              Self: Sized {
+        OnErrorResumeNextObservable {next: Arc::new(next), source: self}
+    }
+
+    #[inline]
+    fn on_error_return<F>(self, f: F) -> OnErrorReturnObservable<Self, F>
+        where F: Fn(Error) -> Self::Item,
+              Self: Sized {
+        OnErrorReturnObservable {f: Arc::new(f), source: self}
+    }
+
+    #[inline]
+    fn materialize(self) -> MaterializeObservable<Self>
+        where Self: Sized {
+        MaterializeObservable {source: self}
+    }
+
+    #[inline]
+    fn dematerialize<A>(self) -> DematerializeObservable<Self>
+        where Self: Observable<Item=Notification<A, Error>> + Sized {
+        DematerializeObservable {source: self}
+    }
+
+    #[inline]
+    fn tap<F>(self, f: F) -> TapObservable<Self, F>
+        where F: Fn(&Self::Item),
+              Self: Sized {
+        TapObservable {f: Arc::new(f), source: self}
+    }
+
+    #[inline]
+    fn do_on_complete<F>(self, f: F) -> DoOnCompleteObservable<Self, F>
+        where F: Fn(),
+              Self: Sized {
+        DoOnCompleteObservable {f: Arc::new(f), source: self}
+    }
+
+    #[inline]
+    fn do_on_error<F>(self, f: F) -> DoOnErrorObservable<Self, F>
+        where F: Fn(&Error),
+              Self: Sized {
+        DoOnErrorObservable {f: Arc::new(f), source: self}
+    }
+
+    #[inline]
+    fn do_on_subscribe<F>(self, f: F) -> DoOnSubscribeObservable<Self, F>
+        where F: Fn(),
+              Self: Sized {
+        DoOnSubscribeObservable {f: f, source: self}
+    }
+
+    #[inline]
+    fn finally<F>(self, f: F) -> FinallyObservable<Self, F>
+        where F: Fn(),
+              Self: Sized {
+        FinallyObservable {f: Arc::new(f), source: self}
+    }
+
+    // Reports subscribe, each item, termination and unsubscribe under the
+    // name, along with where the stage sits in the chain
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    #[inline]
+    fn instrument(self, name: &'static str) -> InstrumentObservable<Self>
+        where Self: Sized {
+        InstrumentObservable {name: name, source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn observe_on<D>(self, scheduler: D) -> ObserveOnObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        ObserveOnObservable {scheduler: scheduler, source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn subscribe_on<D>(self, scheduler: D) -> SubscribeOnObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        SubscribeOnObservable {scheduler: scheduler, source: Arc::new(self)}
+    }
+
+    // Deals the items out to rails in turn, to be built on one by one and
+    // merged back with sequential()
+    #[cfg(feature = "std")]
+    #[inline]
+    fn parallel(self, rails: usize) -> Parallel<Self>
+        where Self: Sized {
+        Parallel {rails: rails.max(1), source: Arc::new(self)}
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn par_map<B, F>(self, f: F) -> ParMapObservable<Self, F>
+        where F: Fn(Self::Item) -> B + Send + Sync + 'static,
+              Self: Sized {
+        self.par_map_with(f, ParOrder::Ordered)
+    }
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    fn par_map_with<B, F>(self, f: F, order: ParOrder) -> ParMapObservable<Self, F>
+        where F: Fn(Self::Item) -> B + Send + Sync + 'static,
+              Self: Sized {
+        ParMapObservable {f: Arc::new(f), order: order, source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn on_backpressure_drop(self) -> BackpressureFlowable<Self>
+        where Self: Sized {
+        BackpressureFlowable {capacity: 0, overflow: Overflow::DropNewest, source: self}
+    }
+
+    // Only the most recent item nobody has asked for yet is kept
+    #[cfg(feature = "std")]
+    #[inline]
+    fn on_backpressure_latest(self) -> BackpressureFlowable<Self>
+        where Self: Sized {
+        BackpressureFlowable {capacity: 1, overflow: Overflow::DropOldest, source: self}
+    }
+
+    // An item nobody has asked for yet fails the stream
+    #[cfg(feature = "std")]
+    #[inline]
+    fn on_backpressure_error(self) -> BackpressureFlowable<Self>
+        where Self: Sized {
+        BackpressureFlowable {capacity: 0, overflow: Overflow::Error, source: self}
+    }
+
+    // Holds up to capacity items nobody has asked for yet
+    #[cfg(feature = "std")]
+    #[inline]
+    fn on_backpressure_buffer(self, capacity: usize, overflow: Overflow) -> BackpressureFlowable<Self>
+        where Self: Sized {
+        BackpressureFlowable {capacity: capacity, overflow: overflow, source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn prefetch(self, count: usize) -> PrefetchObservable<Self, NewThreadScheduler>
+        where Self: Sized {
+        self.prefetch_on(count, NewThreadScheduler::io())
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn prefetch_on<D>(self, count: usize, scheduler: D) -> PrefetchObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        PrefetchObservable {count: count, scheduler: scheduler, source: Arc::new(self)}
+    }
+
+    // Lets a slow consumer skip to the newest item instead of working through
+    // a backlog, as a render loop wants: the source never waits, and whatever
+    // arrived while the last item was being handled is thrown away but the
+    // newest. Without a scheduler the downstream is given items on an I/O
+    // thread.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn latest_only(self) -> LatestOnlyObservable<Self, NewThreadScheduler>
+        where Self: Sized {
+        self.latest_only_on(NewThreadScheduler::io())
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn latest_only_on<D>(self, scheduler: D) -> LatestOnlyObservable<Self, D>
+        where D: Scheduler,
+              Self: Sized {
+        LatestOnlyObservable {scheduler: scheduler, source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn channelize(self, capacity: usize) -> ChannelizeObservable<Self>
+        where Self: Sized {
+        ChannelizeObservable {capacity: capacity, overflow: None, source: Arc::new(self)}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn channelize_with(self, capacity: usize, overflow: Overflow) -> ChannelizeObservable<Self>
+        where Self: Sized {
+        ChannelizeObservable {capacity: capacity, overflow: Some(overflow), source: Arc::new(self)}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn publish(self) -> ConnectableObservable<Self, fn() -> Subject<Self::Item>, Subject<Self::Item>>
+        where Self: Sized {
+        self.multicast(Subject::new)
+    }
+
+    // Subscribers get the latest value straight away, the initial one until
+    // the source has sent something. Each connection starts over from initial.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn publish_behavior(self, initial: Self::Item) -> ConnectableObservable<Self, SubjectFactory<BehaviorSubject<Self::Item>>, BehaviorSubject<Self::Item>>
+        where Self::Item: Clone + Send + Sync + 'static,
+              Self: Sized {
+        let factory: SubjectFactory<BehaviorSubject<Self::Item>> = Box::new(move || BehaviorSubject::new(initial.clone()));
+        self.multicast(factory)
+    }
+
+    // Subscribers get up to max_count of the most recent values first. Each
+    // connection starts with an empty buffer.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn publish_replay(self, max_count: usize) -> ConnectableObservable<Self, SubjectFactory<ReplaySubject<Self::Item>>, ReplaySubject<Self::Item>>
+        where Self: Sized {
+        let factory: SubjectFactory<ReplaySubject<Self::Item>> = Box::new(move || ReplaySubject::with_count(max_count));
+        self.multicast(factory)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn multicast<F, J>(self, factory: F) -> ConnectableObservable<Self, F, J>
+        where F: Fn() -> J,
+              Self: Sized {
+        ConnectableObservable::new(self, factory)
+    }
+
+    // Each subscription gets its own subject and connection, shared only
+    // within the pipeline the selector builds from it
+    #[cfg(feature = "std")]
+    #[inline]
+    fn multicast_with<F, J, G, U>(self, factory: F, selector: G) -> MulticastObservable<Self, F, G>
+        where F: Fn() -> J,
+              G: Fn(J) -> U,
+              Self: Sized {
+        MulticastObservable {factory: factory, selector: selector, source: self}
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn share(self) -> RefCountObservable<Self, fn() -> Subject<Self::Item>, Subject<Self::Item>>
+        where Self: Sized {
+        self.publish().ref_count()
+    }
+
+    // Late subscribers get up to max_count of the most recent values. Like
+    // share(), the buffer starts over once every subscriber has gone or the
+    // source has terminated.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn share_replay(self, max_count: usize) -> RefCountObservable<Self, SubjectFactory<ReplaySubject<Self::Item>>, ReplaySubject<Self::Item>>
+        where Self: Sized {
+        self.publish_replay(max_count).ref_count()
+    }
+
+    // Subscribes to the source once, for the first subscriber, and replays
+    // everything it sent, termination included, to every subscriber after.
+    // Unlike share_replay it never disconnects, so the source is never run
+    // twice.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn cache(self) -> AutoConnectObservable<Self, SubjectFactory<ReplaySubject<Self::Item>>, ReplaySubject<Self::Item>>
+        where Self: Sized {
+        self.cache_with(None, None)
+    }
+
+    // Only the most recent items within the bounds are replayed
+    #[cfg(feature = "std")]
+    #[inline]
+    fn cache_with(self, max_count: Option<usize>, max_age: Option<Duration>) -> AutoConnectObservable<Self, SubjectFactory<ReplaySubject<Self::Item>>, ReplaySubject<Self::Item>>
+        where Self: Sized {
+        self.cache_with_on(max_count, max_age, TimerScheduler)
+    }
+
+    // Ages are measured on the scheduler's clock
+    #[cfg(feature = "std")]
+    #[inline]
+    fn cache_with_on<D>(self, max_count: Option<usize>, max_age: Option<Duration>, scheduler: D) -> AutoConnectObservable<Self, SubjectFactory<ReplaySubject<Self::Item>>, ReplaySubject<Self::Item>>
+        where D: Scheduler + Clone + 'static,
+              Self: Sized {
+        let factory: SubjectFactory<ReplaySubject<Self::Item>> = Box::new(move || ReplaySubject::new_on(max_count, max_age, scheduler.clone()));
+        AutoConnectObservable {
+            min_subscribers: 1,
+            subscribers: Arc::new(AtomicUsize::new(0)),
+            connectable: Arc::new(self.multicast(factory))
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn into_iter(self) -> BlockingObservable<<Self as Observable<E>>::Item>
+        where Self: Observable<Item=<Self as Observable<E>>::Item> + Sized + Send + 'static,
+              <Self as Observable<E>>::Item: Send + 'static {
+        // A rendezvous channel keeps the producer in lockstep with the consumer
+        let (sender, receiver) = sync_channel(0);
+        thread::spawn(move || Observable::<Error>::subscribe(&self, ChannelObserver {sender: Some(sender.into())}));
+        BlockingObservable {receiver: receiver, error: None}
+    }
+
+    // Parks the calling thread until the first value, and None if the source
+    // completes without one. The source is told to stop after it.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn block_first(self) -> Result<Option<Self::Item>, E>
+        where Self: Sized + Send + 'static,
+              Self::Item: Send + 'static,
+              E: Send + 'static {
+        self.block_first_on(NewThreadScheduler::io())
+    }
+
+    // A scheduler that runs tasks in place buffers the whole source first,
+    // so only a finite source should be blocked on that way
+    #[cfg(feature = "std")]
+    #[inline]
+    fn block_first_on<D>(self, scheduler: D) -> Result<Option<Self::Item>, E>
+        where D: Scheduler,
+              Self: Sized + Send + 'static,
+              Self::Item: Send + 'static,
+              E: Send + 'static {
+        match subscribe_blocking(self, &scheduler).recv() {
+            Ok(Ok(val)) => Ok(Some(val)),
+            Ok(Err(err)) => Err(err),
+            Err(_) => Ok(None)
+        }
+    }
+
+    // Parks the calling thread until the source completes, for its last value
+    #[cfg(feature = "std")]
+    #[inline]
+    fn block_last(self) -> Result<Option<Self::Item>, E>
+        where Self: Sized + Send + 'static,
+              Self::Item: Send + 'static,
+              E: Send + 'static {
+        self.block_last_on(NewThreadScheduler::io())
+    }
+
+    // As with block_first_on, an inline scheduler buffers the source first
+    #[cfg(feature = "std")]
+    #[inline]
+    fn block_last_on<D>(self, scheduler: D) -> Result<Option<Self::Item>, E>
+        where D: Scheduler,
+              Self: Sized + Send + 'static,
+              Self::Item: Send + 'static,
+              E: Send + 'static {
+        let mut last = None;
+        for result in subscribe_blocking(self, &scheduler) {
+            last = Some(result?);
+        }
+        Ok(last)
+    }
+
+    // Runs f on the calling thread for every value, returning once the source
+    // has completed, as a main thread driving a pipeline wants
+    #[cfg(feature = "std")]
+    #[inline]
+    fn block_for_each<F>(self, f: F) -> Result<(), E>
+        where F: FnMut(Self::Item),
+              Self: Sized + Send + 'static,
+              Self::Item: Send + 'static,
+              E: Send + 'static {
+        self.block_for_each_on(f, NewThreadScheduler::io())
+    }
+
+    // As with block_first_on, an inline scheduler buffers the source first
+    #[cfg(feature = "std")]
+    #[inline]
+    fn block_for_each_on<F, D>(self, mut f: F, scheduler: D) -> Result<(), E>
+        where F: FnMut(Self::Item),
+              D: Scheduler,
+              Self: Sized + Send + 'static,
+              Self::Item: Send + 'static,
+              E: Send + 'static {
+        for result in subscribe_blocking(self, &scheduler) {
+            f(result?);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "futures")]
+    #[inline]
+    fn into_stream(self) -> ObservableStream<Self::Item, E>
+        where Self: Sized + Send + 'static,
+              Self::Item: Send + 'static,
+              E: Send + 'static {
+        ObservableStream::new(Box::new(move |observer| self.subscribe(observer)))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn subscribe_sender(&self, sender: Sender<Self::Item>)
+        where Self::Item: Send + 'static,
+              E: 'static {
+        self.subscribe(SenderObserver {sender: Some(sender)})
+    }
+
+    #[cfg(feature = "crossbeam")]
+    #[inline]
+    fn subscribe_crossbeam(&self, sender: crossbeam_channel::Sender<Self::Item>)
+        where Self::Item: Send + 'static,
+              E: 'static {
+        self.subscribe(CrossbeamObserver {sender: Some(sender)})
+    }
+
+    // Writes each item's bytes, say to a Connection's writer
+    #[cfg(feature = "std")]
+    #[inline]
+    fn write_to<W>(&self, writer: W)
+        where W: std::io::Write + Send + Sync + 'static,
+              Self::Item: AsRef<[u8]> + 'static,
+              E: 'static {
+        self.subscribe(WriteObserver {writer: Some(writer), _marker: PhantomData})
+    }
+
+    #[cfg(feature = "serde")]
+    #[inline]
+    fn to_json_lines<W>(&self, writer: W)
+        where W: std::io::Write + Send + Sync + 'static,
+              Self::Item: serde::Serialize + Send + Sync + 'static,
+              E: std::fmt::Display {
+        self.subscribe(JsonLinesObserver {writer: Some(writer), _marker: PhantomData})
+    }
+
+    #[cfg(feature = "futures")]
+    #[inline]
+    fn subscribe_async<N>(&self, observer: N)
+        where N: AsyncObserver<E, Item=Self::Item> + Send + Sync + 'static {
+        self.subscribe(AsyncAdapter::new(observer))
+    }
+
+    #[cfg(feature = "futures")]
+    #[inline]
+    fn subscribe_async_fn<F, U>(&self, next: F)
+        where F: FnMut(Self::Item) -> U + Send + Sync + 'static,
+              U: Future<Output=()> + Send,
+              Self::Item: 'static,
+              E: 'static {
+        self.subscribe_async(AsyncFnObserver::new(next))
+    }
+
+    // The first value, or None if the source completes without one
+    #[cfg(feature = "futures")]
+    #[inline]
+    fn first_future(self) -> ObservableFuture<Result<Option<Self::Item>, E>>
+        where Self: Sized + Send + 'static,
+              Self::Item: Send + 'static,
+              E: Send + 'static {
+        ObservableFuture::new(Box::new(move |slot| self.subscribe(FirstObserver {slot: slot})))
+    }
+
+    // Every value, once the source completes
+    #[cfg(feature = "futures")]
+    #[inline]
+    fn into_future(self) -> ObservableFuture<Result<Vec<Self::Item>, E>>
+        where Self: Sized + Send + 'static,
+              Self::Item: Send + 'static,
+              E: Send + 'static {
+        ObservableFuture::new(Box::new(move |slot| self.subscribe(CollectObserver::new(slot))))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn subscribe_dyn(&self, observer: Box<dyn Observer<E, Item=Self::Item> + Send>)
+        where Self::Item: 'static,
+              E: 'static {
+        self.subscribe(DynObserver::new(observer))
+    }
+
+    #[inline]
+    fn boxed(self) -> BoxObservable<Self::Item, E>
+        where Self: Sized + Send + Sync + 'static,
+              E: 'static {
+        BoxObservable {source: Box::new(self)}
+    }
+}
+
+//////////////Subscription
+// A handle for cancelling work from outside the observer chain. Cancellation
+// is cooperative: the observer holding the other end returns Stop the next
+// time it's called.
+#[derive(Clone)]
+pub struct Subscription {
+    pub(crate) cancelled: Arc<AtomicBool>
+}
+
+impl Subscription {
+    pub fn new() -> Subscription {
+        Subscription {cancelled: Arc::new(AtomicBool::new(false))}
+    }
+
+    pub fn unsubscribe(&self) {
+        self.cancelled.store(true, AtomicOrdering::SeqCst);
+    }
+
+    pub fn is_unsubscribed(&self) -> bool {
+        self.cancelled.load(AtomicOrdering::SeqCst)
+    }
+}
+//Subscription//////////////
+
+//////////////Boxed
+// Erases a pipeline's type so it can be stored, returned or collected. The
+// observer is boxed on the way in, since subscribe itself is generic.
+pub trait DynObservable<E = Error>: Send + Sync {
+    type Item;
+
+    fn subscribe_boxed(&self, observer: BoxObserver<Self::Item, E>);
+
+    fn node_boxed(&self) -> Node;
+}
+
+impl<S, E> DynObservable<E> for S
+    where S: Observable<E> + Send + Sync,
+          S::Item: 'static,
+          E: 'static {
+    type Item = S::Item;
+
+    #[inline]
+    fn subscribe_boxed(&self, observer: BoxObserver<Self::Item, E>) {
+        self.subscribe(observer)
+    }
+
+    #[inline]
+    fn node_boxed(&self) -> Node {
+        self.node()
+    }
+}
+
+pub struct BoxObservable<A, E = Error> {
+    pub(crate) source: Box<dyn DynObservable<E, Item=A>>
+}
+
+impl<A, E> Observable<E> for BoxObservable<A, E>
+    where E: 'static {
+    type Item = A;
+
+    // Boxing is invisible, the erased pipeline shows through
+    fn node(&self) -> Node {
+        self.source.node_boxed()
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<E, Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe_boxed(Box::new(observer))
+    }
+}
+
+//Boxed//////////////
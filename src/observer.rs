@@ -0,0 +1,174 @@
+use alloc::boxed::Box;
+use alloc::sync::{Arc, Weak};
+use core::marker::PhantomData;
+use core::error::Error as StdError;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+use self::IterationResult::{Stop, Continue};
+#[cfg(feature = "std")]
+use crate::sync::MutexExt;
+
+pub enum IterationResult {
+    Stop,
+    Continue
+}
+
+// Shared so that a single failure can be handed to every subscriber. It's
+// the default error of Observer and Observable, and the one the built-in
+// operators speak; map_error converts to and from anything else.
+pub type Error = Arc<dyn StdError + Send + Sync>;
+
+pub trait Observer<E = Error> {
+    type Item;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult;
+    fn completed(&mut self);
+    fn error(&mut self, err: E);
+}
+
+// An observer built from closures, e.g.
+// FnObserver::new(|val| ...).on_error(|err| ...).on_completed(|| ...).
+// Whatever isn't given is ignored.
+pub struct FnObserver<A, N, G, C, E = Error> {
+    pub(crate) next: N,
+    pub(crate) error: G,
+    pub(crate) completed: C,
+    pub(crate) _marker: PhantomData<fn(A, E)>
+}
+
+impl<A, N, E> FnObserver<A, N, fn(E), fn(), E>
+    where N: FnMut(A) {
+    pub fn new(next: N) -> FnObserver<A, N, fn(E), fn(), E> {
+        FnObserver {next: next, error: |_| {}, completed: || {}, _marker: PhantomData}
+    }
+}
+
+impl<A, N, G, C, E> FnObserver<A, N, G, C, E> {
+    pub fn on_error<H>(self, error: H) -> FnObserver<A, N, H, C, E>
+        where H: FnMut(E) {
+        FnObserver {next: self.next, error: error, completed: self.completed, _marker: PhantomData}
+    }
+
+    pub fn on_completed<H>(self, completed: H) -> FnObserver<A, N, G, H, E>
+        where H: FnMut() {
+        FnObserver {next: self.next, error: self.error, completed: completed, _marker: PhantomData}
+    }
+}
+
+impl<A, N, G, C, E> Observer<E> for FnObserver<A, N, G, C, E>
+    where N: FnMut(A),
+          G: FnMut(E),
+          C: FnMut() {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        (self.next)(val);
+        Continue
+    }
+
+    #[inline]
+    fn completed(&mut self) {
+        (self.completed)()
+    }
+
+    #[inline]
+    fn error(&mut self, err: E) {
+        (self.error)(err)
+    }
+}
+
+// Hands each item to whoever owns the state, holding it only weakly. Once
+// the owner is dropped the next item stops the subscription, so a long lived
+// source doesn't keep dead listeners around. Termination isn't passed on.
+pub struct WeakObserver<T, F, A> {
+    pub(crate) owner: Weak<T>,
+    pub(crate) next: F,
+    pub(crate) _marker: PhantomData<fn(A)>
+}
+
+impl<T, F, A> WeakObserver<T, F, A>
+    where F: FnMut(&T, A) {
+    pub fn new(owner: &Arc<T>, next: F) -> WeakObserver<T, F, A> {
+        WeakObserver {owner: Arc::downgrade(owner), next: next, _marker: PhantomData}
+    }
+}
+
+impl<T, F, A, E> Observer<E> for WeakObserver<T, F, A>
+    where F: FnMut(&T, A) {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        match self.owner.upgrade() {
+            Some(owner) => {
+                (self.next)(&owner, val);
+                Continue
+            },
+            None => Stop
+        }
+    }
+
+    #[inline]
+    fn completed(&mut self) {}
+
+    #[inline]
+    fn error(&mut self, _: E) {}
+}
+
+pub type BoxObserver<A, E = Error> = Box<dyn Observer<E, Item=A> + Send + Sync>;
+
+impl<N, E> Observer<E> for Box<N>
+    where N: Observer<E> + ?Sized {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        (**self).next(val)
+    }
+
+    #[inline]
+    fn completed(&mut self) {
+        (**self).completed()
+    }
+
+    #[inline]
+    fn error(&mut self, err: E) {
+        (**self).error(err)
+    }
+}
+
+// Lets an observer picked at runtime, which needn't be Sync, subscribe
+// anywhere a concrete one could
+#[cfg(feature = "std")]
+pub struct DynObserver<A, E = Error> {
+    pub(crate) observer: Mutex<Box<dyn Observer<E, Item=A> + Send>>
+}
+
+#[cfg(feature = "std")]
+impl<A, E> DynObserver<A, E> {
+    pub fn new(observer: Box<dyn Observer<E, Item=A> + Send>) -> DynObserver<A, E> {
+        DynObserver {observer: Mutex::new(observer)}
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A, E> Observer<E> for DynObserver<A, E> {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.observer.acquire_mut().next(val)
+    }
+
+    #[inline]
+    fn completed(&mut self) {
+        self.observer.acquire_mut().completed()
+    }
+
+    #[inline]
+    fn error(&mut self, err: E) {
+        self.observer.acquire_mut().error(err)
+    }
+}
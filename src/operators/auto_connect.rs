@@ -0,0 +1,85 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::Observer;
+use crate::operators::connectable::ConnectableObservable;
+
+pub struct AutoConnectObservable<S, F, J> {
+    pub(crate) min_subscribers: usize,
+    pub(crate) subscribers: Arc<AtomicUsize>,
+    pub(crate) connectable: Arc<ConnectableObservable<S, F, J>>
+}
+
+impl<S, F, J> Clone for AutoConnectObservable<S, F, J> {
+    fn clone(&self) -> AutoConnectObservable<S, F, J> {
+        AutoConnectObservable {
+            min_subscribers: self.min_subscribers,
+            subscribers: self.subscribers.clone(),
+            connectable: self.connectable.clone()
+        }
+    }
+}
+
+impl<S, F, J> Observable for AutoConnectObservable<S, F, J>
+    where S: Observable,
+          F: Fn() -> J,
+          J: Observer<Item=S::Item> + Observable<Item=S::Item> + Clone + Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.connectable.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.connectable.subscribe(observer);
+        // Only the subscriber that reaches the threshold connects, so later
+        // ones never reconnect
+        if self.subscribers.fetch_add(1, AtomicOrdering::SeqCst) + 1 == self.min_subscribers {
+            self.connectable.connect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+    use crate::observable::Observable;
+    use crate::sources::range;
+    use crate::testing::observer::TestObserver;
+
+    #[test]
+    fn auto_connect_waits_for_min_subscribers() {
+        let connected = range(0, 3).publish().auto_connect(2);
+        let first = TestObserver::new();
+        let second = TestObserver::new();
+        connected.subscribe(first.clone());
+        first.assert_values(&[]);
+        connected.subscribe(second.clone());
+        first.assert_values(&[0, 1, 2]);
+        first.assert_completed();
+        second.assert_values(&[0, 1, 2]);
+        second.assert_completed();
+    }
+
+    #[test]
+    fn cache_subscribes_to_the_source_once() {
+        let subscriptions = Arc::new(AtomicUsize::new(0));
+        let counter = subscriptions.clone();
+        let cached = range(0, 3)
+            .tap(move |&val: &i32| if val == 0 { counter.fetch_add(1, AtomicOrdering::SeqCst); })
+            .cache();
+
+        for _ in 0..3 {
+            let observer = TestObserver::new();
+            cached.subscribe(observer.clone());
+            observer.assert_values(&[0, 1, 2]);
+            observer.assert_completed();
+        }
+        assert_eq!(subscriptions.load(AtomicOrdering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,376 @@
+use std::sync::{Arc, Mutex};
+use std::collections::VecDeque;
+use std::fmt;
+use std::error::Error as StdError;
+
+use crate::flow::{FlowControl, FlowSubscription, Flowable, Subscriber, add_demand};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::subjects::Terminal;
+use crate::sync::MutexExt;
+
+// What to do with an item that arrives when the buffer is full and there's no
+// demand for it
+#[derive(Clone, Copy)]
+pub enum Overflow {
+    Error,
+    DropOldest,
+    DropNewest
+}
+
+#[derive(Debug)]
+pub struct BackpressureError {
+    pub(crate) capacity: usize
+}
+
+impl fmt::Display for BackpressureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "item arrived with no demand and a full buffer of {}", self.capacity)
+    }
+}
+
+impl StdError for BackpressureError {}
+
+// Bridges a push based source to a demand driven subscriber. Items beyond
+// the outstanding demand are held in a buffer of the given capacity, and the
+// overflow policy decides what happens once that's full.
+pub struct BackpressureFlowable<S> {
+    pub(crate) capacity: usize,
+    pub(crate) overflow: Overflow,
+    pub(crate) source: S
+}
+
+impl<S> Flowable for BackpressureFlowable<S>
+    where S: Observable,
+          S::Item: Send + 'static {
+    type Item = S::Item;
+
+    fn subscribe_flow<N>(&self, subscriber: N)
+        where N: Subscriber<Item=Self::Item> + Send + Sync + 'static {
+        // Marked as draining until the subscriber is ready for items
+        let bridge = Arc::new(Bridge {
+            state: Mutex::new(BridgeState {
+                queue: VecDeque::new(),
+                requested: 0,
+                terminal: None,
+                overflowed: false,
+                cancelled: false,
+                draining: true,
+                missed: false
+            }),
+            subscriber: Mutex::new(subscriber)
+        });
+
+        bridge.subscriber.acquire().on_subscribe(FlowSubscription {control: bridge.clone()});
+        bridge.state.acquire().draining = false;
+        bridge.drain();
+
+        self.source.subscribe(BridgeObserver {
+            capacity: self.capacity,
+            overflow: self.overflow,
+            bridge: bridge
+        });
+    }
+}
+
+pub(crate) struct BridgeState<A> {
+    pub(crate) queue: VecDeque<A>,
+    pub(crate) requested: u64,
+    pub(crate) terminal: Option<Terminal>,
+    pub(crate) overflowed: bool,
+    pub(crate) cancelled: bool,
+    pub(crate) draining: bool,
+    pub(crate) missed: bool
+}
+
+pub(crate) enum BridgeStep<A> {
+    Next(A),
+    Terminate(Terminal),
+    Again,
+    Idle
+}
+
+// Drained one thread at a time, like MergeFlow
+pub(crate) struct Bridge<A, N> {
+    pub(crate) state: Mutex<BridgeState<A>>,
+    pub(crate) subscriber: Mutex<N>
+}
+
+impl<A, N> Bridge<A, N>
+    where N: Subscriber<Item=A> {
+    fn drain(&self) {
+        {
+            let mut state = self.state.acquire();
+            if state.draining {
+                state.missed = true;
+                return;
+            }
+            state.draining = true;
+        }
+
+        let mut subscriber = self.subscriber.acquire();
+        loop {
+            let step = {
+                let mut state = self.state.acquire();
+                if state.cancelled {
+                    state.draining = false;
+                    BridgeStep::Idle
+                } else if state.overflowed {
+                    // An overflow error jumps the queue
+                    state.cancelled = true;
+                    state.queue.clear();
+                    BridgeStep::Terminate(state.terminal.take().unwrap())
+                } else if state.requested > 0 && !state.queue.is_empty() {
+                    if state.requested != u64::MAX {
+                        state.requested -= 1;
+                    }
+                    BridgeStep::Next(state.queue.pop_front().unwrap())
+                } else if state.queue.is_empty() && state.terminal.is_some() {
+                    state.cancelled = true;
+                    BridgeStep::Terminate(state.terminal.take().unwrap())
+                } else if state.missed {
+                    state.missed = false;
+                    BridgeStep::Again
+                } else {
+                    state.draining = false;
+                    BridgeStep::Idle
+                }
+            };
+
+            match step {
+                BridgeStep::Next(val) => {
+                    if let Stop = subscriber.next(val) {
+                        self.state.acquire().cancelled = true;
+                        return subscriber.completed();
+                    }
+                },
+                BridgeStep::Terminate(terminal) => return terminal.deliver(&mut *subscriber),
+                BridgeStep::Again => {},
+                BridgeStep::Idle => return
+            }
+        }
+    }
+
+    fn terminate(&self, terminal: Terminal) {
+        {
+            let mut state = self.state.acquire();
+            if state.terminal.is_some() {
+                return;
+            }
+            state.terminal = Some(terminal);
+        }
+        self.drain();
+    }
+}
+
+impl<A, N> FlowControl for Bridge<A, N>
+    where A: Send,
+          N: Subscriber<Item=A> + Send {
+    fn request(&self, n: u64) {
+        {
+            let mut state = self.state.acquire();
+            if state.cancelled || n == 0 {
+                return;
+            }
+            state.requested = add_demand(state.requested, n);
+        }
+        self.drain();
+    }
+
+    fn cancel(&self) {
+        let mut state = self.state.acquire();
+        state.cancelled = true;
+        state.queue.clear();
+    }
+}
+
+pub(crate) struct BridgeObserver<A, N> {
+    pub(crate) capacity: usize,
+    pub(crate) overflow: Overflow,
+    pub(crate) bridge: Arc<Bridge<A, N>>
+}
+
+impl<A, N> Observer for BridgeObserver<A, N>
+    where N: Subscriber<Item=A> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        {
+            let mut state = self.bridge.state.acquire();
+            if state.cancelled || state.terminal.is_some() {
+                return Stop;
+            }
+
+            // Room for whatever's already been asked for, plus the buffer
+            let room = state.requested.saturating_add(self.capacity as u64);
+            if (state.queue.len() as u64) < room {
+                state.queue.push_back(val);
+            } else {
+                match self.overflow {
+                    Overflow::DropNewest => {},
+                    Overflow::DropOldest => {
+                        state.queue.pop_front();
+                        if self.capacity > 0 {
+                            state.queue.push_back(val);
+                        }
+                    },
+                    Overflow::Error => {
+                        state.overflowed = true;
+                        state.terminal = Some(Terminal::Failed(Arc::new(BackpressureError {capacity: self.capacity})));
+                    }
+                }
+            }
+        }
+
+        self.bridge.drain();
+        if self.bridge.state.acquire().cancelled {
+            Stop
+        } else {
+            Continue
+        }
+    }
+
+    fn completed(&mut self) {
+        self.bridge.terminate(Terminal::Completed);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.bridge.terminate(Terminal::Failed(err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::flow::Flowable;
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::operators::backpressure::{BackpressureError, Overflow};
+    use crate::sources::range;
+    use crate::subjects::Subject;
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::TestSubscriber;
+
+    // A hot source, so items arrive whether or not they're wanted
+    fn pushed<F>(subscribe: F) -> (Subject<i32>, TestSubscriber<i32>)
+        where F: FnOnce(Subject<i32>, TestSubscriber<i32>) {
+        let subject = Subject::new();
+        let subscriber = TestSubscriber::new(0);
+        subscribe(subject.clone(), subscriber.clone());
+        (subject, subscriber)
+    }
+
+    #[test]
+    fn buffer_holds_items_until_requested() {
+        let (mut subject, subscriber) = pushed(|source, subscriber| source.on_backpressure_buffer(8, Overflow::Error).subscribe_flow(subscriber));
+        for i in 0..5 {
+            subject.next(i);
+        }
+        subscriber.observer().assert_values(&[]);
+
+        subscriber.request(2);
+        subscriber.observer().assert_values(&[0, 1]);
+        subject.completed();
+        subscriber.observer().assert_not_completed();
+
+        subscriber.request(10);
+        subscriber.observer().assert_values(&[0, 1, 2, 3, 4]);
+        subscriber.observer().assert_completed();
+    }
+
+    #[test]
+    fn buffer_fails_once_full() {
+        let (mut subject, subscriber) = pushed(|source, subscriber| source.on_backpressure_buffer(2, Overflow::Error).subscribe_flow(subscriber));
+        subject.next(0);
+        subject.next(1);
+        subscriber.observer().assert_values(&[]);
+        subject.next(2);
+
+        // The error doesn't wait behind what was buffered
+        let errors = subscriber.observer().errors();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].downcast_ref::<BackpressureError>().is_some());
+        subscriber.observer().assert_values(&[]);
+    }
+
+    #[test]
+    fn buffer_drops_the_oldest_when_full() {
+        let (mut subject, subscriber) = pushed(|source, subscriber| source.on_backpressure_buffer(2, Overflow::DropOldest).subscribe_flow(subscriber));
+        for i in 0..5 {
+            subject.next(i);
+        }
+        subscriber.request(5);
+        subscriber.observer().assert_values(&[3, 4]);
+    }
+
+    #[test]
+    fn drop_only_passes_what_was_asked_for() {
+        let (mut subject, subscriber) = pushed(|source, subscriber| source.on_backpressure_drop().subscribe_flow(subscriber));
+        subject.next(0);
+        subscriber.request(2);
+        subject.next(1);
+        subject.next(2);
+        subject.next(3);
+        subscriber.observer().assert_values(&[1, 2]);
+    }
+
+    #[test]
+    fn latest_keeps_the_newest_unrequested_item() {
+        let (mut subject, subscriber) = pushed(|source, subscriber| source.on_backpressure_latest().subscribe_flow(subscriber));
+        for i in 0..5 {
+            subject.next(i);
+        }
+        subscriber.request(1);
+        subscriber.observer().assert_values(&[4]);
+        subject.next(5);
+        subject.next(6);
+        subject.completed();
+        subscriber.request(1);
+        subscriber.observer().assert_values(&[4, 6]);
+        subscriber.observer().assert_completed();
+    }
+
+    #[test]
+    fn error_fails_on_the_first_unrequested_item() {
+        let (mut subject, subscriber) = pushed(|source, subscriber| source.on_backpressure_error().subscribe_flow(subscriber));
+        subscriber.request(1);
+        subject.next(0);
+        subject.next(1);
+        subscriber.observer().assert_values(&[0]);
+        subscriber.observer().assert_error();
+    }
+
+    #[test]
+    fn errors_from_the_source_wait_for_the_buffer() {
+        let (mut subject, subscriber) = pushed(|source, subscriber| source.on_backpressure_buffer(4, Overflow::Error).subscribe_flow(subscriber));
+        subject.next(0);
+        subject.error(Arc::new(MarbleError));
+        subscriber.observer().assert_values(&[]);
+        subscriber.request(1);
+        subscriber.observer().assert_values(&[0]);
+        subscriber.observer().assert_error();
+    }
+
+    #[test]
+    fn cancelling_stops_the_source() {
+        let subscriber = TestSubscriber::new(3);
+        let stopper = subscriber.clone();
+        let produced = Arc::new(AtomicUsize::new(0));
+        let counted = produced.clone();
+        range(0, 100)
+            .tap(move |&val: &i32| {
+                counted.fetch_add(1, Ordering::SeqCst);
+                if val == 1 {
+                    stopper.cancel();
+                }
+            })
+            .on_backpressure_buffer(100, Overflow::Error)
+            .subscribe_flow(subscriber.clone());
+        subscriber.observer().assert_values(&[0]);
+        subscriber.observer().assert_not_completed();
+        assert_eq!(produced.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,200 @@
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::sync::MutexExt;
+
+// Collects items into a Vec that goes out once it holds max_size of them or
+// max_delay after its first item, whichever comes first. What's left goes
+// out when the stream ends, on an error as well as on completion.
+pub struct BatchObservable<S, D> {
+    pub(crate) max_size: usize,
+    pub(crate) max_delay: Duration,
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, D> Observable for BatchObservable<S, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = Vec<S::Item>;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(BatchObserver {
+            max_size: self.max_size.max(1),
+            max_delay: self.max_delay,
+            scheduler: self.scheduler.clone(),
+            state: Arc::new(Mutex::new(BatchState {
+                observer: observer,
+                batch: Vec::new(),
+                generation: 0,
+                stopped: false
+            }))
+        });
+    }
+}
+
+// Every batch sent bumps the generation, so the timer of one that filled up
+// first finds a newer generation and does nothing
+pub(crate) struct BatchState<A, N> {
+    pub(crate) observer: N,
+    pub(crate) batch: Vec<A>,
+    pub(crate) generation: u64,
+    pub(crate) stopped: bool
+}
+
+impl<A, N> BatchState<A, N>
+    where N: Observer<Item=Vec<A>> {
+    fn flush(&mut self) {
+        self.generation += 1;
+        if self.batch.is_empty() || self.stopped {
+            return;
+        }
+
+        let batch = mem::take(&mut self.batch);
+        if let Stop = self.observer.next(batch) {
+            self.stopped = true;
+        }
+    }
+}
+
+pub(crate) struct BatchObserver<A, N, D> {
+    pub(crate) max_size: usize,
+    pub(crate) max_delay: Duration,
+    pub(crate) scheduler: D,
+    pub(crate) state: Arc<Mutex<BatchState<A, N>>>
+}
+
+impl<A, N, D> Observer for BatchObserver<A, N, D>
+    where A: Send + 'static,
+          N: Observer<Item=Vec<A>> + Send + 'static,
+          D: Scheduler {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut state = self.state.acquire();
+        if state.stopped {
+            return Stop;
+        }
+
+        state.batch.push(val);
+        if state.batch.len() >= self.max_size {
+            state.flush();
+            return if state.stopped { Stop } else { Continue };
+        }
+
+        if state.batch.len() == 1 {
+            // Let go of the lock first, as a scheduler that runs the timer in
+            // place would otherwise find it still held
+            let generation = state.generation;
+            drop(state);
+            let shared = self.state.clone();
+            self.scheduler.schedule_after(self.max_delay, Box::new(move || {
+                let mut state = shared.acquire();
+                if state.generation == generation {
+                    state.flush();
+                }
+            }));
+        }
+        Continue
+    }
+
+    fn completed(&mut self) {
+        let mut state = self.state.acquire();
+        state.flush();
+        state.stopped = true;
+        state.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        let mut state = self.state.acquire();
+        state.flush();
+        state.stopped = true;
+        state.observer.error(err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::schedulers::current_thread::CurrentThreadScheduler;
+    use crate::sources::range;
+    use crate::testing::marbles::cold;
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn batch_goes_out_when_full() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        cold(&scheduler, "abcde----|").batch_on(2, Duration::from_millis(100), scheduler.clone()).subscribe(observer.clone());
+
+        scheduler.advance_by(Duration::from_millis(40));
+        observer.assert_values(&[vec!['a', 'b'], vec!['c', 'd']]);
+        scheduler.advance_by(Duration::from_millis(60));
+        observer.assert_values(&[vec!['a', 'b'], vec!['c', 'd'], vec!['e']]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn batch_goes_out_after_the_delay() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        cold(&scheduler, "ab------c--|").batch_on(10, Duration::from_millis(25), scheduler.clone()).subscribe(observer.clone());
+
+        scheduler.advance_by(Duration::from_millis(20));
+        observer.assert_values(&[]);
+        scheduler.advance_by(Duration::from_millis(10));
+        observer.assert_values(&[vec!['a', 'b']]);
+        scheduler.advance_by(Duration::from_millis(90));
+        observer.assert_values(&[vec!['a', 'b'], vec!['c']]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn a_full_batch_cancels_its_timer() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        cold(&scheduler, "ab-c-----|").batch_on(2, Duration::from_millis(45), scheduler.clone()).subscribe(observer.clone());
+
+        // The timer from a's batch would otherwise send c's early
+        scheduler.advance_by(Duration::from_millis(50));
+        observer.assert_values(&[vec!['a', 'b']]);
+        scheduler.advance_by(Duration::from_millis(30));
+        observer.assert_values(&[vec!['a', 'b'], vec!['c']]);
+        observer.assert_not_completed();
+    }
+
+    #[test]
+    fn batch_sends_what_it_has_before_an_error() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        cold(&scheduler, "a#").batch_on(10, Duration::from_millis(100), scheduler.clone()).subscribe(observer.clone());
+
+        scheduler.advance_by(Duration::from_millis(20));
+        observer.assert_values(&[vec!['a']]);
+        observer.assert_error();
+    }
+
+    // Each batch's timer is slept out before the next item arrives
+    #[test]
+    fn batch_runs_on_a_scheduler_that_runs_timers_in_place() {
+        let observer = TestObserver::new();
+        range(0, 3).batch_on(10, Duration::from_millis(5), CurrentThreadScheduler).subscribe(observer.clone());
+        observer.assert_values(&[vec![0], vec![1], vec![2]]);
+        observer.assert_completed();
+    }
+}
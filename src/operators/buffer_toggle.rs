@@ -0,0 +1,239 @@
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::marker::PhantomData;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::sync::MutexExt;
+
+// Each item of openings starts a buffer, and the observable the closing
+// selector makes from it ends that buffer when it emits or completes, so
+// buffers can overlap and differ in length. Buffers still open when the
+// source completes are sent then; when openings completes, the open ones
+// carry on but no more are started.
+pub struct BufferToggleObservable<S, O, F> {
+    pub(crate) openings: O,
+    pub(crate) closing: Arc<F>,
+    pub(crate) source: S
+}
+
+impl<S, O, F, U> Observable for BufferToggleObservable<S, O, F>
+    where S: Observable,
+          S::Item: Clone + Send + Sync + 'static,
+          O: Observable,
+          O::Item: 'static,
+          F: Fn(O::Item) -> U + Send + Sync + 'static,
+          U: Observable,
+          U::Item: 'static {
+    type Item = Vec<S::Item>;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node(), self.openings.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let state = Arc::new(Mutex::new(ToggleState {
+            observer: observer,
+            buffers: Vec::new(),
+            next_id: 0,
+            stopped: false
+        }));
+        self.openings.subscribe(OpeningObserver {closing: self.closing.clone(), state: state.clone(), _marker: PhantomData});
+        self.source.subscribe(BufferToggleObserver {state: state});
+    }
+}
+
+// Buffers are kept in the order they opened, which is the order they're sent
+// in when the source completes
+pub(crate) struct ToggleState<A, N> {
+    pub(crate) observer: N,
+    pub(crate) buffers: Vec<(u64, Vec<A>)>,
+    pub(crate) next_id: u64,
+    pub(crate) stopped: bool
+}
+
+impl<A, N> ToggleState<A, N>
+    where N: Observer<Item=Vec<A>> {
+    fn emit(&mut self, buffer: Vec<A>) {
+        if !self.stopped {
+            if let Stop = self.observer.next(buffer) {
+                self.stopped = true;
+                self.buffers.clear();
+            }
+        }
+    }
+
+    fn close(&mut self, id: u64) {
+        if let Some(index) = self.buffers.iter().position(|&(open, _)| open == id) {
+            let (_, buffer) = self.buffers.remove(index);
+            self.emit(buffer);
+        }
+    }
+
+    fn finish(&mut self, err: Option<Error>) {
+        if self.stopped {
+            return;
+        }
+
+        match err {
+            Some(err) => {
+                self.stopped = true;
+                self.buffers.clear();
+                self.observer.error(err);
+            },
+            None => {
+                for (_, buffer) in mem::take(&mut self.buffers) {
+                    self.emit(buffer);
+                }
+                self.stopped = true;
+                self.observer.completed();
+            }
+        }
+    }
+}
+
+pub(crate) struct BufferToggleObserver<A, N> {
+    pub(crate) state: Arc<Mutex<ToggleState<A, N>>>
+}
+
+impl<A, N> Observer for BufferToggleObserver<A, N>
+    where A: Clone,
+          N: Observer<Item=Vec<A>> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut state = self.state.acquire();
+        if state.stopped {
+            return Stop;
+        }
+
+        for &mut (_, ref mut buffer) in state.buffers.iter_mut() {
+            buffer.push(val.clone());
+        }
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.state.acquire().finish(None);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.state.acquire().finish(Some(err));
+    }
+}
+
+pub(crate) struct OpeningObserver<F, A, N, B> {
+    pub(crate) closing: Arc<F>,
+    pub(crate) state: Arc<Mutex<ToggleState<A, N>>>,
+    pub(crate) _marker: PhantomData<fn(B)>
+}
+
+impl<F, A, N, B, U> Observer for OpeningObserver<F, A, N, B>
+    where F: Fn(B) -> U,
+          A: Send + 'static,
+          N: Observer<Item=Vec<A>> + Send + 'static,
+          U: Observable,
+          U::Item: 'static {
+    type Item = B;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let id = {
+            let mut state = self.state.acquire();
+            if state.stopped {
+                return Stop;
+            }
+
+            let id = state.next_id;
+            state.next_id += 1;
+            state.buffers.push((id, Vec::new()));
+            id
+        };
+
+        // Subscribed outside the lock, as a closing observable may fire
+        // straight away
+        (self.closing)(val).subscribe(ClosingObserver {id: id, state: self.state.clone(), _marker: PhantomData});
+        Continue
+    }
+
+    fn completed(&mut self) {}
+
+    fn error(&mut self, err: Error) {
+        self.state.acquire().finish(Some(err));
+    }
+}
+
+pub(crate) struct ClosingObserver<A, N, C> {
+    pub(crate) id: u64,
+    pub(crate) state: Arc<Mutex<ToggleState<A, N>>>,
+    pub(crate) _marker: PhantomData<fn(C)>
+}
+
+impl<A, N, C> Observer for ClosingObserver<A, N, C>
+    where N: Observer<Item=Vec<A>> {
+    type Item = C;
+
+    fn next(&mut self, _: Self::Item) -> IterationResult {
+        self.state.acquire().close(self.id);
+        Stop
+    }
+
+    fn completed(&mut self) {
+        self.state.acquire().close(self.id);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.state.acquire().finish(Some(err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::testing::marbles::cold;
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    fn toggled(openings: &str, closing: &'static str) -> TestObserver<Vec<char>> {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        let closer = scheduler.clone();
+        cold(&scheduler, "a-b-c-d-e|")
+            .buffer_toggle(cold(&scheduler, openings), move |_| cold(&closer, closing))
+            .subscribe(observer.clone());
+        scheduler.advance_by(Duration::from_millis(200));
+        observer
+    }
+
+    #[test]
+    fn buffer_toggle_collects_between_opening_and_closing() {
+        let observer = toggled("-x---y", "--z");
+        observer.assert_values(&[vec!['b'], vec!['d']]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn buffer_toggle_buffers_can_overlap() {
+        let observer = toggled("-x-y", "----z");
+        observer.assert_values(&[vec!['b', 'c'], vec!['c', 'd']]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn buffer_toggle_sends_open_buffers_on_completion() {
+        let observer = toggled("-x-y|", "");
+        observer.assert_values(&[vec!['b', 'c', 'd', 'e'], vec!['c', 'd', 'e']]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn a_closing_that_completes_ends_its_buffer() {
+        let observer = toggled("-x", "--|");
+        observer.assert_values(&[vec!['b']]);
+        observer.assert_completed();
+    }
+}
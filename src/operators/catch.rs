@@ -0,0 +1,109 @@
+use alloc::sync::Arc;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+
+pub struct CatchObservable<S, F> {
+    pub(crate) handler: Arc<F>,
+    pub(crate) source: S
+}
+
+impl<S, F, U> Observable for CatchObservable<S, F>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          F: Fn(Error) -> U + Send + Sync + 'static,
+          U: Observable<Item=S::Item> {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(CatchObserver {handler: self.handler.clone(), observer: Some(observer)});
+    }
+}
+
+// The downstream observer moves over to the fallback when the source fails
+pub(crate) struct CatchObserver<F, N> {
+    pub(crate) handler: Arc<F>,
+    pub(crate) observer: Option<N>
+}
+
+impl<F, N, U> Observer for CatchObserver<F, N>
+    where F: Fn(Error) -> U,
+          U: Observable<Item=N::Item>,
+          N: Observer + Send + Sync + 'static {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        match self.observer {
+            Some(ref mut observer) => observer.next(val),
+            None => Stop
+        }
+    }
+
+    fn completed(&mut self) {
+        if let Some(ref mut observer) = self.observer {
+            observer.completed();
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        if let Some(observer) = self.observer.take() {
+            (self.handler)(err).subscribe(observer);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::observable::Observable;
+    use crate::sources::{range, throw};
+    use crate::testing::marbles::{assert_marbles, cold, MarbleError};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn catch_switches_to_the_handlers_observable() {
+        let scheduler = TestScheduler::new();
+        let fallback = scheduler.clone();
+        let source = cold(&scheduler, "a-#").catch(move |_| cold(&fallback, "xy|"));
+        assert_marbles(&scheduler, source, "a-xy|");
+    }
+
+    #[test]
+    fn catch_leaves_completion_alone() {
+        let scheduler = TestScheduler::new();
+        let fallback = scheduler.clone();
+        let source = cold(&scheduler, "a-b|").catch(move |_| cold(&fallback, "xy|"));
+        assert_marbles(&scheduler, source, "a-b|");
+    }
+
+    #[test]
+    fn catch_hands_the_error_to_the_handler() {
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(MarbleError))
+            .catch(|err| {
+                assert!(err.downcast_ref::<MarbleError>().is_some());
+                throw(err)
+            })
+            .subscribe(observer.clone());
+        observer.assert_error();
+        assert!(observer.errors()[0].downcast_ref::<MarbleError>().is_some());
+    }
+
+    #[test]
+    fn catch_stops_the_fallback_with_the_downstream() {
+        let observer = TestObserver::new();
+        throw(Arc::new(MarbleError)).catch(|_| range(0, i32::MAX)).take(3).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+}
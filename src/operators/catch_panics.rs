@@ -0,0 +1,196 @@
+use std::any::Any;
+use std::error::Error as StdError;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+
+// What to do when handling an item panics
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PanicPolicy {
+    // Fail the stream with a PanicError
+    Error,
+    // Drop the item and carry on, trusting the downstream to cope
+    Skip
+}
+
+// A panic caught by catch_panics, with its message where it had one
+#[derive(Clone, Debug, PartialEq)]
+pub struct PanicError(pub String);
+
+impl PanicError {
+    pub(crate) fn from_payload(payload: Box<dyn Any + Send>) -> PanicError {
+        match payload.downcast::<String>() {
+            Ok(message) => PanicError(*message),
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => PanicError(message.to_string()),
+                Err(_) => PanicError("panicked".to_string())
+            }
+        }
+    }
+}
+
+impl fmt::Display for PanicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "panicked: {}", self.0)
+    }
+}
+
+impl StdError for PanicError {}
+
+// Contains panics from everything downstream, which includes every closure
+// of the operators after it, so it belongs near the source. A panic in
+// completed or error is swallowed, since the stream is ending anyway.
+pub struct CatchPanicsObservable<S> {
+    pub(crate) policy: PanicPolicy,
+    pub(crate) source: S
+}
+
+impl<S> Observable for CatchPanicsObservable<S>
+    where S: Observable,
+          S::Item: Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(CatchPanicsObserver {policy: self.policy, done: false, observer: observer});
+    }
+}
+
+pub(crate) struct CatchPanicsObserver<N> {
+    pub(crate) policy: PanicPolicy,
+    pub(crate) done: bool,
+    pub(crate) observer: N
+}
+
+impl<N> Observer for CatchPanicsObserver<N>
+    where N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        if self.done {
+            return Stop;
+        }
+
+        match panic::catch_unwind(AssertUnwindSafe(|| self.observer.next(val))) {
+            Ok(result) => result,
+            Err(_) if self.policy == PanicPolicy::Skip => Continue,
+            Err(payload) => {
+                self.error(Arc::new(PanicError::from_payload(payload)));
+                Stop
+            }
+        }
+    }
+
+    fn completed(&mut self) {
+        if !self.done {
+            self.done = true;
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| self.observer.completed()));
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        if !self.done {
+            self.done = true;
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| self.observer.error(err)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::operators::catch_panics::{PanicError, PanicPolicy};
+    use crate::sources::range;
+    use crate::subjects::Subject;
+    use crate::testing::observer::TestObserver;
+
+    fn panicky(val: i32) -> i32 {
+        if val == 2 {
+            panic!("no twos");
+        }
+        val
+    }
+
+    #[test]
+    fn a_panic_in_map_becomes_an_error() {
+        let emitted = Arc::new(AtomicUsize::new(0));
+        let counter = emitted.clone();
+        let observer = TestObserver::new();
+        range(0, 5)
+            .tap(move |_| { counter.fetch_add(1, Ordering::SeqCst); })
+            .catch_panics()
+            .map(panicky)
+            .subscribe(observer.clone());
+        observer.assert_values(&[0, 1]);
+        assert_eq!(observer.errors()[0].downcast_ref::<PanicError>(), Some(&PanicError("no twos".to_string())));
+        assert_eq!(emitted.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn skip_drops_the_item_that_panicked() {
+        let observer = TestObserver::new();
+        range(0, 5).catch_panics_with(PanicPolicy::Skip).map(panicky).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 3, 4]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn a_panic_doesnt_spoil_later_subscriptions() {
+        let source = range(0, 5).catch_panics().map(panicky);
+        for _ in 0..2 {
+            let observer = TestObserver::new();
+            source.subscribe(observer.clone());
+            observer.assert_values(&[0, 1]);
+            observer.assert_error();
+        }
+
+        let mut subject = Subject::new();
+        let failing = TestObserver::new();
+        let healthy = TestObserver::new();
+        subject.clone().catch_panics().map(panicky).subscribe(failing.clone());
+        subject.clone().subscribe(healthy.clone());
+        for val in 0..4 {
+            subject.next(val);
+        }
+        let late = TestObserver::new();
+        subject.clone().catch_panics().map(panicky).subscribe(late.clone());
+        subject.next(5);
+        subject.completed();
+
+        failing.assert_values(&[0, 1]);
+        failing.assert_error();
+        healthy.assert_values(&[0, 1, 2, 3, 5]);
+        healthy.assert_completed();
+        late.assert_values(&[5]);
+        late.assert_completed();
+    }
+
+    #[test]
+    fn a_panic_while_ending_is_swallowed() {
+        let observer = TestObserver::new();
+        range(0, 1).catch_panics().do_on_complete(|| panic!("too late")).subscribe(observer.clone());
+        observer.assert_values(&[0]);
+    }
+
+    #[test]
+    fn panic_error_keeps_the_message() {
+        let observer = TestObserver::<i32>::new();
+        range(0, 1).catch_panics().map(|val| panic!("formatted {}", val)).subscribe(observer.clone());
+        assert_eq!(observer.errors()[0].to_string(), "panicked: formatted 0");
+    }
+}
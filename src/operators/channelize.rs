@@ -0,0 +1,217 @@
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::collections::VecDeque;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::backpressure::{BackpressureError, Overflow};
+use crate::operators::materialize::Notification;
+use crate::sync::MutexExt;
+
+// Hands items from a producer thread running the source to a consumer thread
+// driving the observer, through a queue of at most capacity items. With no
+// overflow strategy the producer blocks while the queue is full.
+pub struct ChannelizeObservable<S> {
+    pub(crate) capacity: usize,
+    pub(crate) overflow: Option<Overflow>,
+    pub(crate) source: Arc<S>
+}
+
+pub(crate) struct ChannelState<A> {
+    pub(crate) queue: VecDeque<Notification<A, Error>>,
+    pub(crate) terminated: bool,
+    pub(crate) closed: bool
+}
+
+pub(crate) struct ChannelQueue<A> {
+    pub(crate) state: Mutex<ChannelState<A>>,
+    pub(crate) condvar: Condvar
+}
+
+impl<A> ChannelQueue<A> {
+    fn close(&self) {
+        let mut state = self.state.acquire();
+        state.closed = true;
+        state.queue.clear();
+        self.condvar.notify_all();
+    }
+
+    // Nothing more is coming once the queue is empty and either end has
+    // finished with it
+    fn recv(&self) -> Option<Notification<A, Error>> {
+        let mut state = self.state.acquire();
+        loop {
+            if let Some(notification) = state.queue.pop_front() {
+                self.condvar.notify_all();
+                return Some(notification);
+            }
+            if state.terminated || state.closed {
+                return None;
+            }
+            state = self.condvar.wait(state).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+impl<S> Observable for ChannelizeObservable<S>
+    where S: Observable + Send + Sync + 'static,
+          S::Item: Send + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let queue = Arc::new(ChannelQueue {
+            state: Mutex::new(ChannelState {queue: VecDeque::new(), terminated: false, closed: false}),
+            condvar: Condvar::new()
+        });
+
+        let consumer_queue = queue.clone();
+        thread::spawn(move || {
+            loop {
+                match consumer_queue.recv() {
+                    Some(Notification::Next(val)) => {
+                        if let Stop = observer.next(val) {
+                            consumer_queue.close();
+                            return observer.completed();
+                        }
+                    },
+                    Some(Notification::Error(err)) => return observer.error(err),
+                    Some(Notification::Completed) => return observer.completed(),
+                    None => return
+                }
+            }
+        });
+
+        let source = self.source.clone();
+        let producer = ChannelizeObserver {capacity: self.capacity, overflow: self.overflow, queue: queue};
+        thread::spawn(move || source.subscribe(producer));
+    }
+}
+
+pub(crate) struct ChannelizeObserver<A> {
+    pub(crate) capacity: usize,
+    pub(crate) overflow: Option<Overflow>,
+    pub(crate) queue: Arc<ChannelQueue<A>>
+}
+
+impl<A> ChannelizeObserver<A> {
+    fn terminate(&mut self, notification: Notification<A, Error>) {
+        let mut state = self.queue.state.acquire();
+        if !state.terminated && !state.closed {
+            state.terminated = true;
+            state.queue.push_back(notification);
+            self.queue.condvar.notify_all();
+        }
+    }
+}
+
+// A source that lets go of its observer without terminating it, or panics
+// on the producer thread, would otherwise leave the consumer waiting forever
+impl<A> Drop for ChannelizeObserver<A> {
+    fn drop(&mut self) {
+        self.terminate(Notification::Completed);
+    }
+}
+
+impl<A> Observer for ChannelizeObserver<A> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut state = self.queue.state.acquire();
+        // A capacity of zero still lets one item through at a time
+        let capacity = self.capacity.max(1);
+        if self.overflow.is_none() {
+            while state.queue.len() >= capacity && !state.closed {
+                state = self.queue.condvar.wait(state).unwrap_or_else(PoisonError::into_inner);
+            }
+        }
+
+        if state.terminated || state.closed {
+            return Stop;
+        }
+
+        if state.queue.len() < capacity {
+            state.queue.push_back(Notification::Next(val));
+        } else {
+            match self.overflow {
+                Some(Overflow::DropNewest) | None => {},
+                Some(Overflow::DropOldest) => {
+                    state.queue.pop_front();
+                    state.queue.push_back(Notification::Next(val));
+                },
+                Some(Overflow::Error) => {
+                    state.terminated = true;
+                    state.queue.push_back(Notification::Error(Arc::new(BackpressureError {capacity: self.capacity})));
+                    self.queue.condvar.notify_all();
+                    return Stop;
+                }
+            }
+        }
+
+        self.queue.condvar.notify_all();
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.terminate(Notification::Completed);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.terminate(Notification::Error(err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::sources::range;
+    use crate::testing::observer::TestObserver;
+
+    // Sends one item and then drops its observer without a terminal event
+    struct Abandoning;
+
+    impl Observable for Abandoning {
+        type Item = i32;
+
+        fn subscribe<N>(&self, mut observer: N)
+            where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+            observer.next(1);
+        }
+    }
+
+    #[test]
+    fn channelize_hands_everything_across() {
+        let observer = TestObserver::new();
+        range(0, 100).channelize(4).subscribe(observer.clone());
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_values(&(0..100).collect::<Vec<i32>>());
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn channelize_completes_when_the_source_lets_go() {
+        let observer = TestObserver::new();
+        Abandoning.channelize(4).subscribe(observer.clone());
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_values(&[1]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn channelize_stops_the_producer_when_the_consumer_stops() {
+        let observer = TestObserver::new();
+        range(0, i32::MAX).channelize(4).take(3).subscribe(observer.clone());
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,138 @@
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::lift::{LiftObservable, Operator};
+
+pub type ChunkByObservable<F, S> = LiftObservable<S, ChunkByOperator<F>>;
+
+pub struct ChunkByOperator<F> {
+    pub(crate) key: Arc<F>
+}
+
+impl<A, K, F, E> Operator<A, E> for ChunkByOperator<F>
+    where A: Send + Sync + 'static,
+          K: PartialEq + Send + Sync + 'static,
+          F: Fn(&A) -> K + Send + Sync + 'static {
+    type Item = Vec<A>;
+    type Upstream<N> = ChunkByObserver<F, K, A, N>
+        where N: Observer<E, Item=Vec<A>> + Send + Sync + 'static;
+
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=Vec<A>> + Send + Sync + 'static {
+        ChunkByObserver {key: self.key.clone(), chunk: None, done: false, observer: observer, _marker: PhantomData}
+    }
+
+    fn call_owned<N>(self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=Vec<A>> + Send + Sync + 'static {
+        ChunkByObserver {key: self.key, chunk: None, done: false, observer: observer, _marker: PhantomData}
+    }
+}
+
+// Holds the run in progress with its key. It goes out when an item with a
+// different key arrives, or on completion; an error drops it.
+pub struct ChunkByObserver<F, K, A, N> {
+    pub(crate) key: Arc<F>,
+    pub(crate) chunk: Option<(K, Vec<A>)>,
+    pub(crate) done: bool,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<F, K, A, N, E> Observer<E> for ChunkByObserver<F, K, A, N>
+    where F: Fn(&A) -> K,
+          K: PartialEq,
+          N: Observer<E, Item=Vec<A>> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        if self.done {
+            return Stop;
+        }
+
+        let key = (self.key)(&val);
+        if let Some((ref current, ref mut items)) = self.chunk {
+            if *current == key {
+                items.push(val);
+                return Continue;
+            }
+        }
+
+        let finished = self.chunk.replace((key, vec![val]));
+        match finished {
+            Some((_, items)) => {
+                let result = self.observer.next(items);
+                if let Stop = result {
+                    self.done = true;
+                    self.chunk = None;
+                }
+                result
+            },
+            None => Continue
+        }
+    }
+
+    fn completed(&mut self) {
+        if self.done {
+            return;
+        }
+
+        self.done = true;
+        if let Some((_, items)) = self.chunk.take() {
+            self.observer.next(items);
+        }
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        if !self.done {
+            self.done = true;
+            self.chunk = None;
+            self.observer.error(err);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::sources::from_iter;
+    use crate::subjects::Subject;
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::TestObserver;
+
+    #[test]
+    fn chunk_by_groups_runs_of_equal_keys() {
+        let observer = TestObserver::new();
+        from_iter(vec![1, 3, 2, 4, 6, 5, 8]).chunk_by(|val| val % 2).subscribe(observer.clone());
+        observer.assert_values(&[vec![1, 3], vec![2, 4, 6], vec![5], vec![8]]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn chunk_by_drops_the_open_run_on_error() {
+        let subject = Subject::new();
+        let observer = TestObserver::new();
+        subject.clone().chunk_by(|val: &i32| *val).subscribe(observer.clone());
+        subject.clone().next(1);
+        subject.clone().next(1);
+        subject.clone().next(2);
+        subject.clone().error(Arc::new(MarbleError));
+        observer.assert_values(&[vec![1, 1]]);
+        observer.assert_error();
+    }
+
+    #[test]
+    fn chunk_by_stops_with_its_downstream() {
+        let observer = TestObserver::new();
+        from_iter(vec![1, 1, 2, 3, 3]).chunk_by(|val| *val).take(1).subscribe(observer.clone());
+        observer.assert_values(&[vec![1, 1]]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,226 @@
+use std::sync::{Arc, Mutex};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+use crate::operators::repeat::{Resubscribe, trampoline};
+use crate::sync::MutexExt;
+
+// Sources are pulled from the iterator one at a time, only once the one
+// before has completed, so building the next one can wait on the last, say
+// for the next page of results. Every subscription iterates a fresh clone of
+// the iterator, as from_iter does.
+pub struct ConcatIterObservable<I> {
+    pub(crate) sources: I
+}
+
+impl<I, U> Observable for ConcatIterObservable<I>
+    where I: IntoIterator<Item=U> + Clone,
+          I::IntoIter: Send + 'static,
+          U: Observable + 'static,
+          U::Item: Send + Sync + 'static {
+    type Item = U::Item;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let sources = Arc::new(Mutex::new(self.sources.clone().into_iter()));
+        let rounds = Arc::new(Mutex::new(Resubscribe {pending: None, active: false}));
+        subscribe_next(&sources, &rounds, observer);
+    }
+}
+
+pub fn concat_iter<I>(sources: I) -> ConcatIterObservable<I>
+    where I: IntoIterator + Clone,
+          I::Item: Observable {
+    ConcatIterObservable {sources: sources}
+}
+
+// The source, then once it's completed the ones from the iterator in turn
+pub struct ConcatWithObservable<S, I> {
+    pub(crate) source: S,
+    pub(crate) rest: I
+}
+
+impl<S, I> Observable for ConcatWithObservable<S, I>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          I: IntoIterator + Clone,
+          I::IntoIter: Send + 'static,
+          I::Item: Observable<Item=S::Item> + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(ConcatObserver {
+            sources: Arc::new(Mutex::new(self.rest.clone().into_iter())),
+            rounds: Arc::new(Mutex::new(Resubscribe {pending: None, active: false})),
+            stopped: false,
+            observer: Some(observer)
+        });
+    }
+}
+
+pub(crate) fn subscribe_next<I, N>(sources: &Arc<Mutex<I>>, rounds: &Arc<Mutex<Resubscribe<N>>>, observer: N)
+    where I: Iterator + Send + 'static,
+          I::Item: Observable + 'static,
+          N: Observer<Item=<I::Item as Observable>::Item> + Send + Sync + 'static {
+    trampoline(rounds, observer, |mut observer| {
+        // Taken out first, so the lock isn't held while it subscribes
+        let next = sources.acquire().next();
+        match next {
+            Some(source) => source.subscribe(ConcatObserver {
+                sources: sources.clone(),
+                rounds: rounds.clone(),
+                stopped: false,
+                observer: Some(observer)
+            }),
+            None => observer.completed()
+        }
+    });
+}
+
+pub(crate) struct ConcatObserver<I, N> {
+    pub(crate) sources: Arc<Mutex<I>>,
+    pub(crate) rounds: Arc<Mutex<Resubscribe<N>>>,
+    pub(crate) stopped: bool,
+    pub(crate) observer: Option<N>
+}
+
+impl<I, N> Observer for ConcatObserver<I, N>
+    where I: Iterator + Send + 'static,
+          I::Item: Observable + 'static,
+          N: Observer<Item=<I::Item as Observable>::Item> + Send + Sync + 'static {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let result = match self.observer {
+            Some(ref mut observer) => observer.next(val),
+            None => Stop
+        };
+
+        if let Stop = result {
+            self.stopped = true;
+        }
+        result
+    }
+
+    fn completed(&mut self) {
+        let mut observer = match self.observer.take() {
+            Some(observer) => observer,
+            None => return
+        };
+
+        if self.stopped {
+            return observer.completed();
+        }
+        subscribe_next(&self.sources, &self.rounds, observer);
+    }
+
+    fn error(&mut self, err: Error) {
+        if let Some(mut observer) = self.observer.take() {
+            observer.error(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::operators::concat::concat_iter;
+    use crate::sources::{range, throw};
+    use crate::testing::marbles::{assert_marbles, cold, MarbleError};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn concat_iter_subscribes_each_source_once_the_last_completes() {
+        let scheduler = TestScheduler::new();
+        let sources = ["x|", "-y|", "z|"].into_iter().map({
+            let scheduler = scheduler.clone();
+            move |diagram| cold(&scheduler, diagram)
+        });
+        assert_marbles(&scheduler, concat_iter(sources), "x-yz|");
+    }
+
+    #[test]
+    fn concat_iter_only_builds_a_source_when_its_turn_comes() {
+        let scheduler = TestScheduler::new();
+        let built = Arc::new(AtomicUsize::new(0));
+        let counter = built.clone();
+        let factory = scheduler.clone();
+        let source = concat_iter((0..3).map(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            cold(&factory, "-a|")
+        }));
+        assert_eq!(built.load(Ordering::SeqCst), 0);
+
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        assert_eq!(built.load(Ordering::SeqCst), 1);
+        scheduler.advance_by(Duration::from_millis(20));
+        assert_eq!(built.load(Ordering::SeqCst), 2);
+        scheduler.advance_by(Duration::from_millis(100));
+        assert_eq!(built.load(Ordering::SeqCst), 3);
+        assert_eq!(observer.value_count(), 3);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn concat_iter_leaves_the_rest_unbuilt_once_stopped_or_failed() {
+        let built = Arc::new(AtomicUsize::new(0));
+        let counter = built.clone();
+        let observer = TestObserver::new();
+        concat_iter((0..10).map(move |val| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            range(val * 10, val * 10 + 2)
+        })).take(3).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 10]);
+        observer.assert_completed();
+        assert_eq!(built.load(Ordering::SeqCst), 2);
+
+        let scheduler = TestScheduler::new();
+        let sources = vec![cold(&scheduler, "a#"), cold(&scheduler, "b|")];
+        assert_marbles(&scheduler, concat_iter(sources), "a#");
+    }
+
+    #[test]
+    fn concat_iter_starts_over_for_every_subscription() {
+        let source = concat_iter([0, 5].into_iter().map(|start| range(start, start + 2)));
+        for _ in 0..2 {
+            let observer = TestObserver::new();
+            source.subscribe(observer.clone());
+            observer.assert_values(&[0, 1, 5, 6]);
+            observer.assert_completed();
+        }
+    }
+
+    #[test]
+    fn a_long_run_of_synchronous_sources_doesnt_grow_the_stack() {
+        let observer = TestObserver::new();
+        concat_iter((0..100_000).map(|val| range(val, val + 1))).subscribe(observer.clone());
+        assert_eq!(observer.value_count(), 100_000);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn on_complete_resume_with_follows_the_source_with_the_rest() {
+        let scheduler = TestScheduler::new();
+        let rest = vec![cold(&scheduler, "b|"), cold(&scheduler, "c|")];
+        assert_marbles(&scheduler, cold(&scheduler, "a|").on_complete_resume_with(rest), "abc|");
+
+        let observer = TestObserver::new();
+        throw(Arc::new(MarbleError)).on_complete_resume_with((0..1).map(|_| range(0, 2))).subscribe(observer.clone());
+        observer.assert_values(&[]);
+        observer.assert_error();
+    }
+}
@@ -0,0 +1,255 @@
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+
+use crate::describe::{Node, short_name};
+use crate::observable::{Observable, Subscription};
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+use crate::operators::auto_connect::AutoConnectObservable;
+use crate::operators::ref_count::{RefCountObservable, RefCountState};
+use crate::sync::MutexExt;
+
+// For subjects that need configuring, which a plain fn can't capture
+pub type SubjectFactory<J> = Box<dyn Fn() -> J + Send + Sync>;
+
+pub(crate) struct ConnectState<J> {
+    pub(crate) subject: J,
+    pub(crate) connection: Option<Subscription>,
+    pub(crate) terminated: Arc<AtomicBool>
+}
+
+// Multicasts a single subscription to its source through a subject, which
+// isn't made until connect() is called. Subscribers stay attached across a
+// disconnect, but once the source terminates the next connect() starts over
+// with a fresh subject.
+pub struct ConnectableObservable<S, F, J> {
+    pub(crate) source: Arc<S>,
+    pub(crate) factory: F,
+    pub(crate) state: Arc<Mutex<ConnectState<J>>>
+}
+
+impl<S, F, J> Clone for ConnectableObservable<S, F, J>
+    where F: Clone {
+    fn clone(&self) -> ConnectableObservable<S, F, J> {
+        ConnectableObservable {source: self.source.clone(), factory: self.factory.clone(), state: self.state.clone()}
+    }
+}
+
+impl<S, F, J> ConnectableObservable<S, F, J>
+    where F: Fn() -> J {
+    pub fn new(source: S, factory: F) -> ConnectableObservable<S, F, J> {
+        let subject = factory();
+        ConnectableObservable {
+            source: Arc::new(source),
+            factory: factory,
+            state: Arc::new(Mutex::new(ConnectState {
+                subject: subject,
+                connection: None,
+                terminated: Arc::new(AtomicBool::new(false))
+            }))
+        }
+    }
+
+    // Connecting while already connected hands back the live connection
+    pub fn connect(&self) -> Subscription
+        where S: Observable,
+              J: Observer<Item=S::Item> + Clone + Send + Sync + 'static {
+        self.connect_with(Subscription::new())
+    }
+
+    // Lets the caller hold the connection's handle before the source starts
+    // emitting, so it can disconnect from within a synchronous source
+    pub(crate) fn connect_with(&self, connection: Subscription) -> Subscription
+        where S: Observable,
+              J: Observer<Item=S::Item> + Clone + Send + Sync + 'static {
+        let (subject, connection, terminated) = {
+            let mut state = self.state.acquire();
+            if let Some(ref connection) = state.connection {
+                if !connection.is_unsubscribed() {
+                    return connection.clone();
+                }
+            }
+            self.reset_terminated(&mut state);
+            state.connection = Some(connection.clone());
+            (state.subject.clone(), connection, state.terminated.clone())
+        };
+
+        self.source.subscribe(ConnectObserver {
+            subject: subject,
+            connection: connection.clone(),
+            terminated: terminated
+        });
+        connection
+    }
+
+    // Swaps out a terminated subject so the subscribers that follow wait on
+    // the next connection instead
+    pub fn reset(&self) {
+        self.reset_terminated(&mut self.state.acquire());
+    }
+
+    // Starts over with a fresh subject whether or not the last one terminated,
+    // for when nobody's left subscribed to the old one
+    pub(crate) fn renew(&self) {
+        let mut state = self.state.acquire();
+        state.subject = (self.factory)();
+        state.terminated = Arc::new(AtomicBool::new(false));
+    }
+
+    pub(crate) fn reset_terminated(&self, state: &mut ConnectState<J>) {
+        if state.terminated.load(AtomicOrdering::SeqCst) {
+            state.subject = (self.factory)();
+            state.terminated = Arc::new(AtomicBool::new(false));
+        }
+    }
+
+    pub fn ref_count(self) -> RefCountObservable<S, F, J> {
+        RefCountObservable {
+            connectable: Arc::new(self),
+            state: Arc::new(Mutex::new(RefCountState {count: 0, connection: None}))
+        }
+    }
+
+    // Connects once min_subscribers have subscribed, and stays connected
+    pub fn auto_connect(self, min_subscribers: usize) -> AutoConnectObservable<S, F, J>
+        where S: Observable,
+              J: Observer<Item=S::Item> + Clone + Send + Sync + 'static {
+        if min_subscribers == 0 {
+            self.connect();
+        }
+        AutoConnectObservable {
+            min_subscribers: min_subscribers,
+            subscribers: Arc::new(AtomicUsize::new(0)),
+            connectable: Arc::new(self)
+        }
+    }
+}
+
+impl<S, F, J> Observable for ConnectableObservable<S, F, J>
+    where S: Observable,
+          J: Observable + Clone {
+    type Item = J::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let subject = self.state.acquire().subject.clone();
+        subject.subscribe(observer);
+    }
+}
+
+pub(crate) struct ConnectObserver<J> {
+    pub(crate) subject: J,
+    pub(crate) connection: Subscription,
+    pub(crate) terminated: Arc<AtomicBool>
+}
+
+impl<J> Observer for ConnectObserver<J>
+    where J: Observer {
+    type Item = J::Item;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        if self.connection.is_unsubscribed() {
+            return Stop;
+        }
+        self.subject.next(val)
+    }
+
+    // A disconnect leaves subscribers waiting on the next connection rather
+    // than terminating them
+    fn completed(&mut self) {
+        if !self.connection.is_unsubscribed() {
+            self.terminated.store(true, AtomicOrdering::SeqCst);
+            self.connection.unsubscribe();
+            self.subject.completed();
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        if !self.connection.is_unsubscribed() {
+            self.terminated.store(true, AtomicOrdering::SeqCst);
+            self.connection.unsubscribe();
+            self.subject.error(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::sources::range;
+    use crate::subjects::Subject;
+    use crate::testing::observer::TestObserver;
+
+    #[test]
+    fn publish_waits_for_connect() {
+        let subject = Subject::new();
+        let published = subject.clone().publish();
+        let first = TestObserver::new();
+        let second = TestObserver::new();
+        published.subscribe(first.clone());
+        published.subscribe(second.clone());
+
+        subject.clone().next(1);
+        published.connect();
+        subject.clone().next(2);
+        subject.clone().next(3);
+        first.assert_values(&[2, 3]);
+        second.assert_values(&[2, 3]);
+    }
+
+    #[test]
+    fn connecting_twice_keeps_the_one_connection() {
+        let subject = Subject::new();
+        let published = subject.clone().publish();
+        let observer = TestObserver::new();
+        published.subscribe(observer.clone());
+
+        let connection = published.connect();
+        published.connect();
+        subject.clone().next(1);
+        observer.assert_values(&[1]);
+        assert_eq!(subject.observer_count(), 1);
+        connection.unsubscribe();
+    }
+
+    #[test]
+    fn disconnecting_leaves_subscribers_for_the_next_connection() {
+        let subject = Subject::new();
+        let published = subject.clone().publish();
+        let observer = TestObserver::new();
+        published.subscribe(observer.clone());
+
+        let connection = published.connect();
+        subject.clone().next(1);
+        connection.unsubscribe();
+        subject.clone().next(2);
+        published.connect();
+        subject.clone().next(3);
+        observer.assert_values(&[1, 3]);
+        observer.assert_not_completed();
+    }
+
+    #[test]
+    fn reset_after_the_source_terminates_starts_a_fresh_subject() {
+        let published = range(0, 3).publish();
+        let first = TestObserver::new();
+        published.subscribe(first.clone());
+        published.connect();
+        first.assert_values(&[0, 1, 2]);
+        first.assert_completed();
+
+        published.reset();
+        let second = TestObserver::new();
+        published.subscribe(second.clone());
+        second.assert_not_completed();
+        published.connect();
+        second.assert_values(&[0, 1, 2]);
+        second.assert_completed();
+        assert_eq!(first.completion_count(), 1);
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::sync::MutexExt;
+
+pub struct DebounceObservable<S, D> {
+    pub(crate) quiet: Duration,
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, D> Observable for DebounceObservable<S, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(DebounceObserver {
+            quiet: self.quiet,
+            scheduler: self.scheduler.clone(),
+            state: Arc::new(Mutex::new(DebounceState {
+                observer: observer,
+                pending: None,
+                generation: 0,
+                stopped: false
+            }))
+        });
+    }
+}
+
+// Each item bumps the generation, so a timer that fires for a superseded item
+// finds a newer generation and does nothing
+pub(crate) struct DebounceState<A, N> {
+    pub(crate) observer: N,
+    pub(crate) pending: Option<A>,
+    pub(crate) generation: u64,
+    pub(crate) stopped: bool
+}
+
+impl<A, N> DebounceState<A, N>
+    where N: Observer<Item=A> {
+    fn flush(&mut self) {
+        if let Some(val) = self.pending.take() {
+            if !self.stopped {
+                if let Stop = self.observer.next(val) {
+                    self.stopped = true;
+                }
+            }
+        }
+    }
+}
+
+pub(crate) struct DebounceObserver<A, N, D> {
+    pub(crate) quiet: Duration,
+    pub(crate) scheduler: D,
+    pub(crate) state: Arc<Mutex<DebounceState<A, N>>>
+}
+
+impl<A, N, D> Observer for DebounceObserver<A, N, D>
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut state = self.state.acquire();
+        if state.stopped {
+            return Stop;
+        }
+
+        state.pending = Some(val);
+        state.generation += 1;
+
+        let generation = state.generation;
+        // A scheduler that runs the timer in place would otherwise find the
+        // lock still held
+        drop(state);
+        let shared = self.state.clone();
+        self.scheduler.schedule_after(self.quiet, Box::new(move || {
+            let mut state = shared.acquire();
+            if state.generation == generation {
+                state.flush();
+            }
+        }));
+        Continue
+    }
+
+    fn completed(&mut self) {
+        let mut state = self.state.acquire();
+        state.generation += 1;
+        state.flush();
+        state.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        let mut state = self.state.acquire();
+        state.generation += 1;
+        state.pending = None;
+        state.observer.error(err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::schedulers::current_thread::CurrentThreadScheduler;
+    use crate::sources::range;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn debounce_lets_out_the_last_of_a_burst() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "ab-----c---d|").debounce_on(Duration::from_millis(25), scheduler.clone());
+        assert_marbles(&scheduler, source, "---b-----c--(d|)");
+    }
+
+    #[test]
+    fn debounce_flushes_on_completion() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").debounce_on(Duration::from_millis(25), scheduler.clone());
+        assert_marbles(&scheduler, source, "---(b|)");
+    }
+
+    #[test]
+    fn debounce_drops_what_was_waiting_on_an_error() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-#").debounce_on(Duration::from_millis(25), scheduler.clone());
+        assert_marbles(&scheduler, source, "--#-");
+    }
+
+    // The trampoline sleeps out each timer as it's scheduled, so every item
+    // gets its quiet period before the next arrives
+    #[test]
+    fn debounce_runs_on_a_scheduler_that_runs_timers_in_place() {
+        let observer = TestObserver::new();
+        range(0, 3).debounce_on(Duration::from_millis(5), CurrentThreadScheduler).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,367 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::materialize::Notification;
+use crate::operators::reorder::Held;
+use crate::schedulers::Scheduler;
+use crate::sync::MutexExt;
+
+//////////////DelayQueue
+// Items wait in a heap by deadline, and every timer only says how far along
+// the clock is. A single drain lets out whatever's due, earliest first, so on
+// a scheduler with more than one thread, timers that fire out of order or at
+// once still can't reorder items or let completion overtake them. Equal
+// deadlines keep their arrival order.
+pub(crate) struct DelayQueue<A> {
+    pub(crate) held: BinaryHeap<Reverse<Held<Instant, A>>>,
+    pub(crate) arrivals: u64,
+    // The latest deadline a timer has fired for. Going by that rather than
+    // the clock means a timer that fires a touch early still lets its item
+    // out.
+    pub(crate) until: Option<Instant>,
+    // When completion is due, once the source has completed
+    pub(crate) completion: Option<Instant>,
+    pub(crate) error: Option<Error>,
+    pub(crate) draining: bool,
+    pub(crate) stopped: bool
+}
+
+// The observer has a lock of its own, so the source can keep pushing while
+// an item is being delivered, which matters when the downstream feeds back
+// into the source (retry_when, repeat_when)
+pub(crate) struct DelayShared<A, N> {
+    pub(crate) queue: Mutex<DelayQueue<A>>,
+    pub(crate) observer: Mutex<N>
+}
+
+impl<A, N> DelayShared<A, N>
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static {
+    pub(crate) fn new(observer: N) -> DelayShared<A, N> {
+        DelayShared {
+            queue: Mutex::new(DelayQueue {
+                held: BinaryHeap::new(),
+                arrivals: 0,
+                until: None,
+                completion: None,
+                error: None,
+                draining: false,
+                stopped: false
+            }),
+            observer: Mutex::new(observer)
+        }
+    }
+
+    pub(crate) fn hold<D>(self: &Arc<Self>, scheduler: &D, delay: Duration, val: A) -> IterationResult
+        where D: Scheduler {
+        let due = scheduler.now() + delay;
+        {
+            let mut queue = self.queue.acquire();
+            if queue.stopped || queue.completion.is_some() {
+                return Stop;
+            }
+
+            let arrival = queue.arrivals;
+            queue.arrivals += 1;
+            queue.held.push(Reverse(Held {key: due, arrival: arrival, due: due, val: val}));
+        }
+
+        let shared = self.clone();
+        scheduler.schedule_after(delay, Box::new(move || shared.release(due)));
+        Continue
+    }
+
+    pub(crate) fn complete<D>(self: &Arc<Self>, scheduler: &D, delay: Duration)
+        where D: Scheduler {
+        let due = scheduler.now() + delay;
+        {
+            let mut queue = self.queue.acquire();
+            if queue.stopped || queue.completion.is_some() {
+                return;
+            }
+            queue.completion = Some(due);
+        }
+
+        let shared = self.clone();
+        scheduler.schedule_after(delay, Box::new(move || shared.release(due)));
+    }
+
+    // Errors aren't delayed, and anything still waiting is dropped
+    pub(crate) fn fail(&self, err: Error) {
+        {
+            let mut queue = self.queue.acquire();
+            if queue.stopped {
+                return;
+            }
+            queue.stopped = true;
+            queue.held.clear();
+            queue.error = Some(err);
+            if queue.draining {
+                return;
+            }
+            queue.draining = true;
+        }
+        self.drain();
+    }
+
+    fn release(&self, due: Instant) {
+        {
+            let mut queue = self.queue.acquire();
+            queue.until = Some(queue.until.map_or(due, |until| until.max(due)));
+            if queue.draining {
+                return;
+            }
+            queue.draining = true;
+        }
+        self.drain();
+    }
+
+    // Only one drain runs at a time, and a timer that fires during one leaves
+    // its deadline for it to pick up
+    fn drain(&self) {
+        let mut observer = self.observer.acquire();
+
+        loop {
+            let event = {
+                let mut queue = self.queue.acquire();
+                let due = match (queue.held.peek(), queue.until) {
+                    (Some(Reverse(held)), Some(until)) => held.due <= until,
+                    _ => false
+                };
+
+                if let Some(err) = queue.error.take() {
+                    Notification::Error(err)
+                } else if queue.stopped {
+                    queue.draining = false;
+                    return;
+                } else if due {
+                    Notification::Next(queue.held.pop().unwrap().0.val)
+                } else if queue.held.is_empty() && queue.completion.is_some_and(|at| queue.until.is_some_and(|until| at <= until)) {
+                    queue.stopped = true;
+                    Notification::Completed
+                } else {
+                    queue.draining = false;
+                    return;
+                }
+            };
+
+            match event {
+                Notification::Next(val) => {
+                    if let Stop = observer.next(val) {
+                        let mut queue = self.queue.acquire();
+                        queue.stopped = true;
+                        queue.held.clear();
+                    }
+                },
+                Notification::Completed => return observer.completed(),
+                Notification::Error(err) => return observer.error(err)
+            }
+        }
+    }
+}
+//DelayQueue//////////////
+
+//////////////Delay
+// Completion is delayed as much as the items are, so it always comes after
+// the last of them
+pub struct DelayObservable<S, D> {
+    pub(crate) delay: Duration,
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, D> Observable for DelayObservable<S, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(DelayObserver {
+            delay: self.delay,
+            scheduler: self.scheduler.clone(),
+            shared: Arc::new(DelayShared::new(observer))
+        });
+    }
+}
+
+pub(crate) struct DelayObserver<A, N, D> {
+    pub(crate) delay: Duration,
+    pub(crate) scheduler: D,
+    pub(crate) shared: Arc<DelayShared<A, N>>
+}
+
+impl<A, N, D> Observer for DelayObserver<A, N, D>
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.shared.hold(&self.scheduler, self.delay, val)
+    }
+
+    fn completed(&mut self) {
+        self.shared.complete(&self.scheduler, self.delay);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.shared.fail(err);
+    }
+}
+//Delay//////////////
+
+//////////////DelayEach
+// Each item is held for however long the selector says, so one with a short
+// delay can overtake one with a long delay. Completion waits for the last
+// held item, and errors, as with delay, go straight through.
+pub struct DelayEachObservable<S, F, D> {
+    pub(crate) selector: Arc<F>,
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, F, D> Observable for DelayEachObservable<S, F, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          F: Fn(&S::Item) -> Duration + Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(DelayEachObserver {
+            selector: self.selector.clone(),
+            scheduler: self.scheduler.clone(),
+            shared: Arc::new(DelayShared::new(observer))
+        });
+    }
+}
+
+pub(crate) struct DelayEachObserver<F, A, N, D> {
+    pub(crate) selector: Arc<F>,
+    pub(crate) scheduler: D,
+    pub(crate) shared: Arc<DelayShared<A, N>>
+}
+
+impl<F, A, N, D> Observer for DelayEachObserver<F, A, N, D>
+    where F: Fn(&A) -> Duration,
+          A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let delay = (self.selector)(&val);
+        self.shared.hold(&self.scheduler, delay, val)
+    }
+
+    fn completed(&mut self) {
+        self.shared.complete(&self.scheduler, Duration::from_secs(0));
+    }
+
+    fn error(&mut self, err: Error) {
+        self.shared.fail(err);
+    }
+}
+//DelayEach//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::operators::materialize::Notification;
+    use crate::schedulers::thread_pool::ThreadPoolScheduler;
+    use crate::sources::{from_iter, range};
+    use crate::subjects::Subject;
+    use crate::testing::marbles::{assert_marbles, cold, MarbleError};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn delay_shifts_items_and_completion() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b-|").delay_on(Duration::from_millis(20), scheduler.clone());
+        assert_marbles(&scheduler, source, "--a-b-|");
+    }
+
+    #[test]
+    fn delay_passes_errors_straight_through() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-#").delay_on(Duration::from_millis(30), scheduler.clone());
+        assert_marbles(&scheduler, source, "--#");
+    }
+
+    #[test]
+    fn delay_keeps_order_on_a_thread_pool() {
+        let scheduler = ThreadPoolScheduler::new(8);
+        for _ in 0..20 {
+            let observer = TestObserver::new();
+            range(0, 200).delay_on(Duration::from_millis(1), scheduler.clone()).subscribe(observer.clone());
+            assert!(observer.await_terminal_event(Duration::from_secs(5)));
+
+            let events = observer.events();
+            assert!(matches!(events.last(), Some(&(_, Notification::Completed))));
+            observer.assert_values(&(0..200).collect::<Vec<i32>>());
+            observer.assert_completed();
+        }
+        scheduler.shutdown();
+    }
+
+    #[test]
+    fn delay_lets_the_downstream_feed_back_into_the_source() {
+        let scheduler = TestScheduler::new();
+        let subject = Subject::new();
+        let observer = TestObserver::new();
+        let feedback = subject.clone();
+        subject.clone()
+            .delay_on(Duration::from_millis(10), scheduler.clone())
+            .tap(move |&val: &i32| {
+                if val < 3 {
+                    feedback.clone().next(val + 1);
+                } else {
+                    feedback.clone().error(Arc::new(MarbleError));
+                }
+            })
+            .subscribe(observer.clone());
+
+        subject.clone().next(0);
+        scheduler.advance_by(Duration::from_millis(100));
+        observer.assert_values(&[0, 1, 2, 3]);
+        observer.assert_error();
+    }
+
+    #[test]
+    fn delay_each_releases_by_deadline() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        from_iter(vec![50u64, 10, 30, 10, 0])
+            .delay_each_on(|ms| Duration::from_millis(*ms), scheduler.clone())
+            .subscribe(observer.clone());
+
+        scheduler.advance_by(Duration::from_millis(20));
+        observer.assert_values(&[0, 10, 10]);
+        observer.assert_not_completed();
+        scheduler.advance_by(Duration::from_millis(30));
+        observer.assert_values(&[0, 10, 10, 30, 50]);
+        observer.assert_completed();
+    }
+}
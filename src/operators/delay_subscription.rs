@@ -0,0 +1,108 @@
+use std::sync::Arc;
+use std::marker::PhantomData;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+
+pub struct DelaySubscriptionObservable<S, T> {
+    pub(crate) source: Arc<S>,
+    pub(crate) trigger: T
+}
+
+impl<S, T> Observable for DelaySubscriptionObservable<S, T>
+    where S: Observable + Send + Sync + 'static,
+          T: Observable,
+          T::Item: Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node(), self.trigger.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.trigger.subscribe(TriggerObserver {
+            source: self.source.clone(),
+            observer: Some(observer),
+            _marker: PhantomData
+        });
+    }
+}
+
+// Subscribes the source on the trigger's first item, or on its completion if
+// it never emits one
+pub(crate) struct TriggerObserver<S, N, B> {
+    pub(crate) source: Arc<S>,
+    pub(crate) observer: Option<N>,
+    pub(crate) _marker: PhantomData<B>
+}
+
+impl<S, N, B> TriggerObserver<S, N, B>
+    where S: Observable,
+          N: Observer<Item=S::Item> + Send + Sync + 'static {
+    fn fire(&mut self) {
+        if let Some(observer) = self.observer.take() {
+            self.source.subscribe(observer);
+        }
+    }
+}
+
+impl<S, N, B> Observer for TriggerObserver<S, N, B>
+    where S: Observable,
+          N: Observer<Item=S::Item> + Send + Sync + 'static {
+    type Item = B;
+
+    fn next(&mut self, _: Self::Item) -> IterationResult {
+        self.fire();
+        Stop
+    }
+
+    fn completed(&mut self) {
+        self.fire();
+    }
+
+    fn error(&mut self, err: Error) {
+        if let Some(mut observer) = self.observer.take() {
+            observer.error(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn delay_subscription_starts_the_source_late() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").delay_subscription_on(Duration::from_millis(20), scheduler.clone());
+        assert_marbles(&scheduler, source, "--a-b|");
+    }
+
+    #[test]
+    fn delay_subscription_until_waits_for_the_first_item() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").delay_subscription_until(cold(&scheduler, "-xy|"));
+        assert_marbles(&scheduler, source, "-a-b|");
+    }
+
+    #[test]
+    fn a_trigger_that_only_completes_still_subscribes() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").delay_subscription_until(cold(&scheduler, "---|"));
+        assert_marbles(&scheduler, source, "---a-b|");
+    }
+
+    #[test]
+    fn a_failing_trigger_fails_the_stream() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").delay_subscription_until(cold(&scheduler, "--#"));
+        assert_marbles(&scheduler, source, "--#---");
+    }
+}
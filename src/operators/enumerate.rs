@@ -0,0 +1,222 @@
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::Continue;
+use crate::operators::lift::{LiftObservable, Operator};
+
+// Indices count the source's items from zero, per subscription, so a filter
+// sees every item's position whether or not it's let through
+
+//////////////Enumerate
+pub type EnumerateObservable<S> = LiftObservable<S, EnumerateOperator>;
+
+pub struct EnumerateOperator;
+
+impl<A, E> Operator<A, E> for EnumerateOperator
+    where A: Send + Sync + 'static {
+    type Item = (usize, A);
+    type Upstream<N> = EnumerateObserver<N, A>
+        where N: Observer<E, Item=(usize, A)> + Send + Sync + 'static;
+
+    #[inline]
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=(usize, A)> + Send + Sync + 'static {
+        EnumerateObserver {index: 0, observer: observer, _marker: PhantomData}
+    }
+}
+
+pub struct EnumerateObserver<N, A> {
+    pub(crate) index: usize,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, N, E> Observer<E> for EnumerateObserver<N, A>
+    where N: Observer<E, Item=(usize, A)> {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let index = self.index;
+        self.index += 1;
+        self.observer.next((index, val))
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
+//Enumerate//////////////
+
+//////////////MapWithIndex
+pub type MapWithIndexObservable<F, S> = LiftObservable<S, MapWithIndexOperator<F>>;
+
+pub struct MapWithIndexOperator<F> {
+    pub(crate) f: Arc<F>
+}
+
+impl<A, B, F, E> Operator<A, E> for MapWithIndexOperator<F>
+    where A: Send + Sync + 'static,
+          F: Fn(usize, A) -> B + Send + Sync + 'static {
+    type Item = B;
+    type Upstream<N> = MapWithIndexObserver<F, N, A>
+        where N: Observer<E, Item=B> + Send + Sync + 'static;
+
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=B> + Send + Sync + 'static {
+        MapWithIndexObserver {f: self.f.clone(), index: 0, observer: observer, _marker: PhantomData}
+    }
+
+    fn call_owned<N>(self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=B> + Send + Sync + 'static {
+        MapWithIndexObserver {f: self.f, index: 0, observer: observer, _marker: PhantomData}
+    }
+}
+
+pub struct MapWithIndexObserver<F, N, A> {
+    pub(crate) f: Arc<F>,
+    pub(crate) index: usize,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, N, F, E> Observer<E> for MapWithIndexObserver<F, N, A>
+    where N: Observer<E>,
+          F: Fn(usize, A) -> <N as Observer<E>>::Item {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let index = self.index;
+        self.index += 1;
+        self.observer.next((self.f)(index, val))
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
+//MapWithIndex//////////////
+
+//////////////FilterWithIndex
+pub type FilterWithIndexObservable<P, S> = LiftObservable<S, FilterWithIndexOperator<P>>;
+
+pub struct FilterWithIndexOperator<P> {
+    pub(crate) predicate: Arc<P>
+}
+
+impl<A, P, E> Operator<A, E> for FilterWithIndexOperator<P>
+    where A: Send + Sync + 'static,
+          P: Fn(usize, &A) -> bool + Send + Sync + 'static {
+    type Item = A;
+    type Upstream<N> = FilterWithIndexObserver<P, N, A>
+        where N: Observer<E, Item=A> + Send + Sync + 'static;
+
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=A> + Send + Sync + 'static {
+        FilterWithIndexObserver {predicate: self.predicate.clone(), index: 0, observer: observer, _marker: PhantomData}
+    }
+
+    fn call_owned<N>(self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=A> + Send + Sync + 'static {
+        FilterWithIndexObserver {predicate: self.predicate, index: 0, observer: observer, _marker: PhantomData}
+    }
+}
+
+pub struct FilterWithIndexObserver<P, N, A> {
+    pub(crate) predicate: Arc<P>,
+    pub(crate) index: usize,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, N, P, E> Observer<E> for FilterWithIndexObserver<P, N, A>
+    where N: Observer<E, Item=A>,
+          P: Fn(usize, &A) -> bool {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let index = self.index;
+        self.index += 1;
+        if (self.predicate)(index, &val) {
+            self.observer.next(val)
+        } else {
+            Continue
+        }
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
+//FilterWithIndex//////////////
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::observable::Observable;
+    use crate::sources::{from_iter, range};
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn enumerate_pairs_items_with_their_index() {
+        let observer = TestObserver::new();
+        from_iter(vec!['a', 'b', 'c']).enumerate().subscribe(observer.clone());
+        observer.assert_values(&[(0, 'a'), (1, 'b'), (2, 'c')]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn indices_start_over_for_every_subscription() {
+        let enumerated = range(10, 13).enumerate();
+        let mapped = range(10, 13).map_with_index(|index, val| index as i32 * 100 + val);
+        let filtered = range(10, 16).filter_with_index(|index, _| index % 2 == 0);
+        for _ in 0..2 {
+            let observer = TestObserver::new();
+            enumerated.subscribe(observer.clone());
+            observer.assert_values(&[(0, 10), (1, 11), (2, 12)]);
+
+            let observer = TestObserver::new();
+            mapped.subscribe(observer.clone());
+            observer.assert_values(&[10, 111, 212]);
+
+            let observer = TestObserver::new();
+            filtered.subscribe(observer.clone());
+            observer.assert_values(&[10, 12, 14]);
+        }
+    }
+
+    #[test]
+    fn filter_with_index_counts_the_items_it_drops() {
+        let observer = TestObserver::new();
+        range(0, 10)
+            .filter_with_index(|_, val| val % 3 != 0)
+            .filter_with_index(|index, _| index < 3)
+            .subscribe(observer.clone());
+        observer.assert_values(&[1, 2, 4]);
+    }
+
+    #[test]
+    fn indexed_operators_pass_errors_and_stops_on() {
+        let scheduler = TestScheduler::new();
+        assert_marbles(&scheduler, cold(&scheduler, "ab#").map_with_index(|index, val| if index == 0 { val } else { 'z' }), "az#");
+
+        let observer = TestObserver::new();
+        range(0, i32::MAX).enumerate().take(2).subscribe(observer.clone());
+        observer.assert_values(&[(0, 0), (1, 1)]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,125 @@
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::Continue;
+use crate::operators::filter_map::{FilterMapObservable, FilterMapOperator};
+use crate::operators::lift::{LiftObservable, Operator};
+
+pub type FilterObservable<P, S> = LiftObservable<S, FilterOperator<P>>;
+
+pub struct FilterOperator<P> {
+    pub(crate) predicate: Arc<P>
+}
+
+impl<A, P, E> Operator<A, E> for FilterOperator<P>
+    where A: Send + Sync + 'static,
+          P: Fn(&A) -> bool + Send + Sync + 'static {
+    type Item = A;
+    type Upstream<N> = FilterObserver<P, N, A>
+        where N: Observer<E, Item=A> + Send + Sync + 'static;
+
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=A> + Send + Sync + 'static {
+        FilterObserver {predicate: self.predicate.clone(), observer: observer, _marker: PhantomData}
+    }
+
+    fn call_owned<N>(self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=A> + Send + Sync + 'static {
+        FilterObserver {predicate: self.predicate, observer: observer, _marker: PhantomData}
+    }
+}
+
+pub struct FilterObserver<P, N, A> {
+    pub(crate) predicate: Arc<P>,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, N, P, E> Observer<E> for FilterObserver<P, N, A>
+    where N: Observer<E, Item=A>,
+          P: Fn(&A) -> bool {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        if (self.predicate)(&val) {
+            self.observer.next(val)
+        } else {
+            Continue
+        }
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
+
+// Fusion, as for map: whatever follows a filter is folded into it
+impl<S, P> LiftObservable<S, FilterOperator<P>> {
+    #[inline]
+    pub fn map<A, B, G>(self, g: G) -> FilterMapObservable<impl Fn(A) -> Option<B> + Send + Sync + 'static, S>
+        where P: Fn(&A) -> bool + Send + Sync + 'static,
+              G: Fn(A) -> B + Send + Sync + 'static {
+        let predicate = self.operator.predicate;
+        LiftObservable {operator: FilterMapOperator {f: Arc::new(move |val| Some(val).filter(|val| predicate(val)).map(&g))}, source: self.source}
+    }
+
+    #[inline]
+    pub fn filter<A, Q>(self, next: Q) -> FilterObservable<impl Fn(&A) -> bool + Send + Sync + 'static, S>
+        where P: Fn(&A) -> bool + Send + Sync + 'static,
+              Q: Fn(&A) -> bool + Send + Sync + 'static {
+        let predicate = self.operator.predicate;
+        LiftObservable {operator: FilterOperator {predicate: Arc::new(move |val: &A| predicate(val) && next(val))}, source: self.source}
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::observable::Observable;
+    use crate::operators::filter::FilterObservable;
+    use crate::operators::filter_map::FilterMapObservable;
+    use crate::sources::{range, RangeObservable};
+    use crate::testing::observer::TestObserver;
+
+    fn values<S>(source: S) -> Vec<S::Item>
+        where S: Observable,
+              S::Item: Clone + Send + 'static {
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        observer.assert_completed();
+        observer.values()
+    }
+
+    #[test]
+    fn filter_after_filter_is_one_stage() {
+        let fused: FilterObservable<_, RangeObservable<i32>> = range(0, 30).filter(|val| val % 2 == 0).filter(|val| val % 3 == 0);
+        let unfused = Observable::filter(Observable::filter(range(0, 30), |val| val % 2 == 0), |val| val % 3 == 0);
+        assert_eq!(values(fused), values(unfused));
+    }
+
+    #[test]
+    fn map_after_filter_is_one_stage() {
+        let fused: FilterMapObservable<_, RangeObservable<i32>> = range(0, 10).filter(|val| val % 2 == 1).map(|val| val * 10);
+        let unfused = Observable::map(Observable::filter(range(0, 10), |val| val % 2 == 1), |val| val * 10);
+        assert_eq!(values(fused), values(unfused));
+    }
+
+    #[test]
+    fn fused_filters_only_test_what_got_past_the_first() {
+        let tested = Arc::new(AtomicUsize::new(0));
+        let counter = tested.clone();
+        let source = range(0, 10).filter(|val| val % 2 == 0).filter(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            true
+        });
+        assert_eq!(values(source), vec![0, 2, 4, 6, 8]);
+        assert_eq!(tested.load(Ordering::SeqCst), 5);
+    }
+}
@@ -0,0 +1,115 @@
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::Continue;
+use crate::operators::lift::{LiftObservable, Operator};
+
+pub type FilterMapObservable<F, S> = LiftObservable<S, FilterMapOperator<F>>;
+
+pub struct FilterMapOperator<F> {
+    pub(crate) f: Arc<F>
+}
+
+impl<A, B, F, E> Operator<A, E> for FilterMapOperator<F>
+    where A: Send + Sync + 'static,
+          F: Fn(A) -> Option<B> + Send + Sync + 'static {
+    type Item = B;
+    type Upstream<N> = FilterMapObserver<F, N, A>
+        where N: Observer<E, Item=B> + Send + Sync + 'static;
+
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=B> + Send + Sync + 'static {
+        FilterMapObserver {f: self.f.clone(), observer: observer, _marker: PhantomData}
+    }
+
+    fn call_owned<N>(self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=B> + Send + Sync + 'static {
+        FilterMapObserver {f: self.f, observer: observer, _marker: PhantomData}
+    }
+}
+
+pub struct FilterMapObserver<F, N, A> {
+    pub(crate) f: Arc<F>,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, N, F, E> Observer<E> for FilterMapObserver<F, N, A>
+    where N: Observer<E>,
+          F: Fn(A) -> Option<<N as Observer<E>>::Item> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        match (self.f)(val) {
+            Some(val) => self.observer.next(val),
+            None => Continue
+        }
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
+
+// Fusion, as for map: whatever follows a filter_map is folded into it
+impl<S, F> LiftObservable<S, FilterMapOperator<F>> {
+    #[inline]
+    pub fn map<A, B, C, G>(self, g: G) -> FilterMapObservable<impl Fn(A) -> Option<C> + Send + Sync + 'static, S>
+        where F: Fn(A) -> Option<B> + Send + Sync + 'static,
+              G: Fn(B) -> C + Send + Sync + 'static {
+        let f = self.operator.f;
+        LiftObservable {operator: FilterMapOperator {f: Arc::new(move |val| f(val).map(&g))}, source: self.source}
+    }
+
+    #[inline]
+    pub fn filter<A, B, P>(self, predicate: P) -> FilterMapObservable<impl Fn(A) -> Option<B> + Send + Sync + 'static, S>
+        where F: Fn(A) -> Option<B> + Send + Sync + 'static,
+              P: Fn(&B) -> bool + Send + Sync + 'static {
+        let f = self.operator.f;
+        LiftObservable {operator: FilterMapOperator {f: Arc::new(move |val| f(val).filter(&predicate))}, source: self.source}
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::observable::Observable;
+    use crate::operators::filter_map::FilterMapObservable;
+    use crate::sources::{from_iter, range, RangeObservable};
+    use crate::testing::observer::TestObserver;
+
+    fn values<S>(source: S) -> Vec<S::Item>
+        where S: Observable,
+              S::Item: Clone + Send + 'static {
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        observer.assert_completed();
+        observer.values()
+    }
+
+    #[test]
+    fn filter_map_drops_the_nones() {
+        let source = from_iter(vec!["1", "x", "3"]).filter_map(|val| val.parse::<i32>().ok());
+        assert_eq!(values(source), vec![1, 3]);
+    }
+
+    #[test]
+    fn map_and_filter_after_filter_map_are_one_stage() {
+        let half = |val: i32| if val % 2 == 0 { Some(val / 2) } else { None };
+        let fused: FilterMapObservable<_, RangeObservable<i32>> = range(0, 20).filter_map(half).map(|val| val + 1).filter(|val| val % 3 != 0);
+        let unfused = Observable::filter(Observable::map(Observable::filter_map(range(0, 20), half), |val| val + 1), |val| val % 3 != 0);
+        assert_eq!(values(fused), values(unfused));
+    }
+
+    #[test]
+    fn filter_map_stops_with_the_downstream() {
+        let observer = TestObserver::new();
+        range(0, i32::MAX).filter_map(|val| Some(val).filter(|val| val % 2 == 0)).take(2).subscribe(observer.clone());
+        observer.assert_values(&[0, 2]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,148 @@
+use alloc::sync::Arc;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+
+pub struct FinallyObservable<S, F> {
+    pub(crate) f: Arc<F>,
+    pub(crate) source: S
+}
+
+impl<S, F> Observable for FinallyObservable<S, F>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          F: Fn() + Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(FinallyObserver {f: self.f.clone(), ran: false, observer: observer});
+    }
+}
+
+// Runs after the downstream sees completion or an error, as soon as the
+// downstream asks to stop, or failing all of those when the subscription is
+// dropped
+pub(crate) struct FinallyObserver<F, N>
+    where F: Fn() {
+    pub(crate) f: Arc<F>,
+    pub(crate) ran: bool,
+    pub(crate) observer: N
+}
+
+impl<F, N> FinallyObserver<F, N>
+    where F: Fn() {
+    fn run(&mut self) {
+        if !self.ran {
+            self.ran = true;
+            (self.f)();
+        }
+    }
+}
+
+impl<F, N> Observer for FinallyObserver<F, N>
+    where F: Fn(),
+          N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let result = self.observer.next(val);
+        if let Stop = result {
+            self.run();
+        }
+        result
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+        self.run();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.error(err);
+        self.run();
+    }
+}
+
+impl<F, N> Drop for FinallyObserver<F, N>
+    where F: Fn() {
+    fn drop(&mut self) {
+        self.run();
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::observable::Observable;
+    use crate::sources::{create, range, throw, Emitter};
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::TestObserver;
+
+    fn counter() -> (Arc<AtomicUsize>, impl Fn() + Send + Sync + 'static) {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counted = runs.clone();
+        (runs, move || { counted.fetch_add(1, Ordering::SeqCst); })
+    }
+
+    #[test]
+    fn finally_runs_once_after_completion() {
+        let (runs, f) = counter();
+        let observer = TestObserver::new();
+        range(0, 3).finally(f).subscribe(observer.clone());
+        observer.assert_completed();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn finally_runs_once_after_an_error() {
+        let (runs, f) = counter();
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(MarbleError)).finally(f).subscribe(observer.clone());
+        observer.assert_error();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn finally_runs_once_when_the_downstream_stops() {
+        let (runs, f) = counter();
+        let observer = TestObserver::new();
+        range(0, 100).finally(f).take(2).subscribe(observer.clone());
+        observer.assert_values(&[0, 1]);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn finally_runs_after_the_downstream_sees_the_end() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counted = runs.clone();
+        let observer = TestObserver::new();
+        let seen = observer.clone();
+        range(0, 1)
+            .finally(move || {
+                assert_eq!(seen.completion_count(), 1);
+                counted.fetch_add(1, Ordering::SeqCst);
+            })
+            .subscribe(observer.clone());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    // The emitter isn't kept, so the subscription is dropped without the
+    // stream ever ending
+    #[test]
+    fn finally_runs_once_the_subscription_is_dropped() {
+        let (runs, f) = counter();
+        create(|_: Emitter<i32>| {}).finally(f).subscribe(TestObserver::new());
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,414 @@
+use std::sync::{Arc, Mutex};
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use crate::flow::{FlowControl, FlowSubscription, Flowable, Subscriber, add_demand};
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::sync::MutexExt;
+
+// How many items each inner flowable is asked for ahead of demand
+pub(crate) const FLOW_PREFETCH: u64 = 16;
+
+// Merges the inner flowables while keeping every queue bounded: at most
+// max_concurrency inners run at once, each is asked for FLOW_PREFETCH items
+// and topped up one at a time as the downstream takes them.
+pub struct FlatMapFlowable<S, F> {
+    pub(crate) f: Arc<F>,
+    pub(crate) max_concurrency: usize,
+    pub(crate) source: S
+}
+
+impl<S, F, U> Flowable for FlatMapFlowable<S, F>
+    where S: Flowable,
+          S::Item: Send + Sync + 'static,
+          F: Fn(S::Item) -> U + Send + Sync + 'static,
+          U: Flowable,
+          U::Item: Send + 'static {
+    type Item = U::Item;
+
+    fn subscribe_flow<N>(&self, subscriber: N)
+        where N: Subscriber<Item=Self::Item> + Send + Sync + 'static {
+        // Marked as draining until the outer subscription is in place, so
+        // requests made from on_subscribe are held rather than drained early
+        let merge = Arc::new(MergeFlow {
+            state: Mutex::new(MergeFlowState {
+                requested: 0,
+                slots: Vec::new(),
+                next_id: 0,
+                cursor: 0,
+                outer: None,
+                outer_done: false,
+                error: None,
+                cancelled: false,
+                draining: true,
+                missed: false
+            }),
+            subscriber: Mutex::new(subscriber)
+        });
+
+        merge.subscriber.acquire().on_subscribe(FlowSubscription {control: merge.clone()});
+        merge.state.acquire().draining = false;
+
+        self.source.subscribe_flow(OuterFlowSubscriber {
+            f: self.f.clone(),
+            max_concurrency: self.max_concurrency as u64,
+            merge: merge.clone(),
+            _marker: PhantomData
+        });
+        merge.drain();
+    }
+}
+
+pub(crate) struct InnerSlot<A> {
+    pub(crate) id: u64,
+    pub(crate) queue: VecDeque<A>,
+    pub(crate) subscription: Option<FlowSubscription>,
+    pub(crate) done: bool
+}
+
+pub(crate) struct MergeFlowState<A> {
+    pub(crate) requested: u64,
+    pub(crate) slots: Vec<InnerSlot<A>>,
+    pub(crate) next_id: u64,
+    pub(crate) cursor: usize,
+    pub(crate) outer: Option<FlowSubscription>,
+    pub(crate) outer_done: bool,
+    pub(crate) error: Option<Error>,
+    pub(crate) cancelled: bool,
+    pub(crate) draining: bool,
+    pub(crate) missed: bool
+}
+
+impl<A> MergeFlowState<A> {
+    fn slot(&mut self, id: u64) -> Option<&mut InnerSlot<A>> {
+        self.slots.iter_mut().find(|slot| slot.id == id)
+    }
+
+    // Everything still upstream, for cancelling once the lock is released
+    fn cancel(&mut self) -> Vec<FlowSubscription> {
+        self.cancelled = true;
+        self.slots.drain(..).filter_map(|slot| slot.subscription).chain(self.outer.take()).collect()
+    }
+}
+
+pub(crate) enum MergeStep<A> {
+    Next(A),
+    Completed,
+    Failed(Error, Vec<FlowSubscription>),
+    Again,
+    Idle
+}
+
+// Only one thread drains at a time. Anything that changes the state while a
+// drain is running flags it as missed and leaves it to the running drain, so
+// requests and pushes made from inside the downstream never re-enter it.
+pub(crate) struct MergeFlow<A, N> {
+    pub(crate) state: Mutex<MergeFlowState<A>>,
+    pub(crate) subscriber: Mutex<N>
+}
+
+impl<A, N> MergeFlow<A, N>
+    where N: Subscriber<Item=A> {
+    fn drain(&self) {
+        {
+            let mut state = self.state.acquire();
+            if state.draining {
+                state.missed = true;
+                return;
+            }
+            state.draining = true;
+        }
+
+        let mut subscriber = self.subscriber.acquire();
+        loop {
+            let mut requests = Vec::new();
+            let step = {
+                let mut state = self.state.acquire();
+                self.next_step(&mut state, &mut requests)
+            };
+
+            // Requests go out with the lock released since a synchronous
+            // upstream answers them straight away
+            for (subscription, n) in requests {
+                subscription.request(n);
+            }
+
+            match step {
+                MergeStep::Next(val) => {
+                    if let Stop = subscriber.next(val) {
+                        let cancelled = self.state.acquire().cancel();
+                        for subscription in cancelled {
+                            subscription.cancel();
+                        }
+                        return subscriber.completed();
+                    }
+                },
+                MergeStep::Completed => return subscriber.completed(),
+                MergeStep::Failed(err, cancelled) => {
+                    for subscription in cancelled {
+                        subscription.cancel();
+                    }
+                    return subscriber.error(err);
+                },
+                MergeStep::Again => {},
+                MergeStep::Idle => return
+            }
+        }
+    }
+
+    fn next_step(&self, state: &mut MergeFlowState<A>, requests: &mut Vec<(FlowSubscription, u64)>) -> MergeStep<A> {
+        if state.cancelled {
+            state.draining = false;
+            return MergeStep::Idle;
+        }
+
+        if let Some(err) = state.error.take() {
+            let cancelled = state.cancel();
+            return MergeStep::Failed(err, cancelled);
+        }
+
+        // Each finished inner makes room for another from the outer source
+        let before = state.slots.len();
+        state.slots.retain(|slot| !(slot.done && slot.queue.is_empty()));
+        let retired = (before - state.slots.len()) as u64;
+        if retired > 0 && !state.outer_done {
+            if let Some(ref outer) = state.outer {
+                requests.push((outer.clone(), retired));
+            }
+        }
+
+        if state.slots.is_empty() && state.outer_done {
+            state.cancelled = true;
+            return MergeStep::Completed;
+        }
+
+        if state.requested > 0 {
+            let len = state.slots.len();
+            let ready = (0..len).map(|i| (state.cursor + i) % len).find(|&i| !state.slots[i].queue.is_empty());
+            if let Some(i) = ready {
+                state.cursor = i + 1;
+                if state.requested != u64::MAX {
+                    state.requested -= 1;
+                }
+
+                let slot = &mut state.slots[i];
+                if let (false, Some(subscription)) = (slot.done, slot.subscription.as_ref()) {
+                    requests.push((subscription.clone(), 1));
+                }
+                return MergeStep::Next(slot.queue.pop_front().unwrap());
+            }
+        }
+
+        if state.missed || !requests.is_empty() {
+            state.missed = false;
+            return MergeStep::Again;
+        }
+
+        state.draining = false;
+        MergeStep::Idle
+    }
+
+    fn fail(&self, err: Error) {
+        {
+            let mut state = self.state.acquire();
+            if state.error.is_none() {
+                state.error = Some(err);
+            }
+        }
+        self.drain();
+    }
+}
+
+impl<A, N> FlowControl for MergeFlow<A, N>
+    where A: Send,
+          N: Subscriber<Item=A> + Send {
+    fn request(&self, n: u64) {
+        {
+            let mut state = self.state.acquire();
+            if state.cancelled || n == 0 {
+                return;
+            }
+            state.requested = add_demand(state.requested, n);
+        }
+        self.drain();
+    }
+
+    fn cancel(&self) {
+        let cancelled = self.state.acquire().cancel();
+        for subscription in cancelled {
+            subscription.cancel();
+        }
+    }
+}
+
+pub(crate) struct OuterFlowSubscriber<F, B, A, N> {
+    pub(crate) f: Arc<F>,
+    pub(crate) max_concurrency: u64,
+    pub(crate) merge: Arc<MergeFlow<A, N>>,
+    pub(crate) _marker: PhantomData<B>
+}
+
+impl<F, B, U, N> Observer for OuterFlowSubscriber<F, B, U::Item, N>
+    where F: Fn(B) -> U,
+          U: Flowable,
+          U::Item: Send + 'static,
+          N: Subscriber<Item=U::Item> + Send + Sync + 'static {
+    type Item = B;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let id = {
+            let mut state = self.merge.state.acquire();
+            if state.cancelled {
+                return Stop;
+            }
+            let id = state.next_id;
+            state.next_id += 1;
+            state.slots.push(InnerSlot {id: id, queue: VecDeque::new(), subscription: None, done: false});
+            id
+        };
+
+        (self.f)(val).subscribe_flow(InnerFlowSubscriber {id: id, merge: self.merge.clone()});
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.merge.state.acquire().outer_done = true;
+        self.merge.drain();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.merge.fail(err);
+    }
+}
+
+impl<F, B, U, N> Subscriber for OuterFlowSubscriber<F, B, U::Item, N>
+    where F: Fn(B) -> U,
+          U: Flowable,
+          U::Item: Send + 'static,
+          N: Subscriber<Item=U::Item> + Send + Sync + 'static {
+    fn on_subscribe(&mut self, subscription: FlowSubscription) {
+        self.merge.state.acquire().outer = Some(subscription.clone());
+        subscription.request(self.max_concurrency);
+    }
+}
+
+pub(crate) struct InnerFlowSubscriber<A, N> {
+    pub(crate) id: u64,
+    pub(crate) merge: Arc<MergeFlow<A, N>>
+}
+
+impl<A, N> Observer for InnerFlowSubscriber<A, N>
+    where N: Subscriber<Item=A> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        {
+            let mut state = self.merge.state.acquire();
+            match state.slot(self.id) {
+                Some(slot) => slot.queue.push_back(val),
+                None => return Stop
+            }
+        }
+        self.merge.drain();
+        Continue
+    }
+
+    fn completed(&mut self) {
+        if let Some(slot) = self.merge.state.acquire().slot(self.id) {
+            slot.done = true;
+        }
+        self.merge.drain();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.merge.fail(err);
+    }
+}
+
+impl<A, N> Subscriber for InnerFlowSubscriber<A, N>
+    where N: Subscriber<Item=A> {
+    fn on_subscribe(&mut self, subscription: FlowSubscription) {
+        let attached = match self.merge.state.acquire().slot(self.id) {
+            Some(slot) => {
+                slot.subscription = Some(subscription.clone());
+                true
+            },
+            None => false
+        };
+
+        if attached {
+            subscription.request(FLOW_PREFETCH);
+        } else {
+            subscription.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::flow::Flowable;
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::operators::backpressure::Overflow;
+    use crate::sources::range;
+    use crate::subjects::Subject;
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::TestSubscriber;
+
+    #[test]
+    fn flat_map_flow_merges_every_inner() {
+        let subscriber = TestSubscriber::new(u64::MAX);
+        range(0, 3).flat_map_flow(|i| range(i * 10, i * 10 + 3), 2).subscribe_flow(subscriber.clone());
+
+        let mut values = subscriber.observer().values();
+        values.sort();
+        assert_eq!(values, vec![0, 1, 2, 10, 11, 12, 20, 21, 22]);
+        subscriber.observer().assert_completed();
+    }
+
+    #[test]
+    fn flat_map_flow_sends_no_more_than_requested() {
+        let subscriber = TestSubscriber::new(4);
+        range(0, 3).flat_map_flow(|i| range(i * 10, i * 10 + 3), 2).subscribe_flow(subscriber.clone());
+        assert_eq!(subscriber.observer().value_count(), 4);
+        subscriber.observer().assert_not_completed();
+
+        subscriber.request(4);
+        assert_eq!(subscriber.observer().value_count(), 8);
+        subscriber.observer().assert_not_completed();
+
+        subscriber.request(1);
+        assert_eq!(subscriber.observer().value_count(), 9);
+        subscriber.observer().assert_completed();
+    }
+
+    #[test]
+    fn flat_map_flow_runs_no_more_than_max_concurrency() {
+        let subscriber = TestSubscriber::new(3);
+        range(0, 3).flat_map_flow(|i| range(i * 1000, i32::MAX), 1).subscribe_flow(subscriber.clone());
+        subscriber.observer().assert_values(&[0, 1, 2]);
+
+        subscriber.request(2);
+        subscriber.observer().assert_values(&[0, 1, 2, 3, 4]);
+        subscriber.cancel();
+    }
+
+    #[test]
+    fn flat_map_flow_fails_on_an_inner_error() {
+        let subject = Subject::new();
+        let subscriber = TestSubscriber::new(u64::MAX);
+        let inner = subject.clone();
+        range(0, 2)
+            .flat_map_flow(move |_| inner.clone().on_backpressure_buffer(16, Overflow::Error), 2)
+            .subscribe_flow(subscriber.clone());
+
+        let mut source = subject.clone();
+        source.next(7);
+        source.error(Arc::new(MarbleError));
+        subscriber.observer().assert_values(&[7, 7]);
+        subscriber.observer().assert_error();
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::sources::{NeverObservable, never};
+use crate::subjects::Subject;
+use crate::sync::MutexExt;
+
+// The items of one key. It's hot: the group is handed downstream before its
+// first item, so subscribe to it from next() to see everything.
+pub struct GroupedObservable<K, A> {
+    pub(crate) key: K,
+    pub(crate) subject: Subject<A>
+}
+
+impl<K, A> GroupedObservable<K, A> {
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<K, A> Clone for GroupedObservable<K, A>
+    where K: Clone {
+    fn clone(&self) -> GroupedObservable<K, A> {
+        GroupedObservable {key: self.key.clone(), subject: self.subject.clone()}
+    }
+}
+
+impl<K, A> Observable for GroupedObservable<K, A>
+    where A: Clone {
+    type Item = A;
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.subject.subscribe(observer);
+    }
+}
+
+// What group_by closes its groups with
+pub(crate) fn never_closes<K, A>(_: &GroupedObservable<K, A>) -> NeverObservable<()> {
+    never()
+}
+
+pub(crate) type Groups<K, A> = Arc<Mutex<HashMap<K, Subject<A>>>>;
+
+// Each group lasts until the observable made for it by the duration selector
+// emits or terminates. An item for a key whose group has closed opens a new
+// one, so a group's lifetime is bounded rather than the pipeline's.
+pub struct GroupByObservable<S, F, G> {
+    pub(crate) key: Arc<F>,
+    pub(crate) duration: Arc<G>,
+    pub(crate) source: S
+}
+
+impl<S, F, G, K, U> Observable for GroupByObservable<S, F, G>
+    where S: Observable,
+          S::Item: Clone + Send + Sync + 'static,
+          F: Fn(&S::Item) -> K + Send + Sync + 'static,
+          G: Fn(&GroupedObservable<K, S::Item>) -> U + Send + Sync + 'static,
+          K: Hash + Eq + Clone + Send + Sync + 'static,
+          U: Observable,
+          U::Item: 'static {
+    type Item = GroupedObservable<K, S::Item>;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(GroupByObserver {
+            key: self.key.clone(),
+            duration: self.duration.clone(),
+            groups: Arc::new(Mutex::new(HashMap::new())),
+            stopped: false,
+            observer: observer
+        });
+    }
+}
+
+// Once the downstream stops taking groups the open ones still get their
+// items, and the source is only stopped when none are left
+pub(crate) struct GroupByObserver<F, G, K, A, N> {
+    pub(crate) key: Arc<F>,
+    pub(crate) duration: Arc<G>,
+    pub(crate) groups: Groups<K, A>,
+    pub(crate) stopped: bool,
+    pub(crate) observer: N
+}
+
+impl<F, G, K, A, N, U> Observer for GroupByObserver<F, G, K, A, N>
+    where F: Fn(&A) -> K,
+          G: Fn(&GroupedObservable<K, A>) -> U,
+          K: Hash + Eq + Clone + Send + Sync + 'static,
+          A: Clone + Send + 'static,
+          N: Observer<Item=GroupedObservable<K, A>>,
+          U: Observable,
+          U::Item: 'static {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let key = (self.key)(&val);
+        let existing = self.groups.acquire().get(&key).cloned();
+
+        let subject = match existing {
+            Some(subject) => subject,
+            None if self.stopped => {
+                return if self.groups.acquire().is_empty() { Stop } else { Continue };
+            },
+            None => {
+                let group = GroupedObservable {key: key.clone(), subject: Subject::new()};
+                self.groups.acquire().insert(key.clone(), group.subject.clone());
+                if let Stop = self.observer.next(group.clone()) {
+                    self.stopped = true;
+                }
+
+                group.subject.dispatch(val);
+                (self.duration)(&group).subscribe(DurationObserver {
+                    key: key,
+                    subject: group.subject,
+                    groups: self.groups.clone(),
+                    _marker: PhantomData
+                });
+                return Continue;
+            }
+        };
+
+        subject.dispatch(val);
+        Continue
+    }
+
+    fn completed(&mut self) {
+        let groups: Vec<_> = self.groups.acquire().drain().collect();
+        for (_, mut subject) in groups {
+            subject.completed();
+        }
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        let groups: Vec<_> = self.groups.acquire().drain().collect();
+        for (_, mut subject) in groups {
+            subject.error(err.clone());
+        }
+        self.observer.error(err);
+    }
+}
+
+// Closes one group. The key may have been reopened by the time this fires,
+// so only a group that's still this one is removed.
+pub(crate) struct DurationObserver<K, A, B> {
+    pub(crate) key: K,
+    pub(crate) subject: Subject<A>,
+    pub(crate) groups: Groups<K, A>,
+    pub(crate) _marker: PhantomData<fn(B)>
+}
+
+impl<K, A, B> DurationObserver<K, A, B>
+    where K: Hash + Eq {
+    fn close(&mut self) -> bool {
+        let mut groups = self.groups.acquire();
+        let current = groups.get(&self.key).is_some_and(|subject| Arc::ptr_eq(&subject.state, &self.subject.state));
+        if current {
+            groups.remove(&self.key);
+        }
+        current
+    }
+}
+
+impl<K, A, B> Observer for DurationObserver<K, A, B>
+    where K: Hash + Eq,
+          A: Clone {
+    type Item = B;
+
+    fn next(&mut self, _: Self::Item) -> IterationResult {
+        self.completed();
+        Stop
+    }
+
+    fn completed(&mut self) {
+        if self.close() {
+            self.subject.completed();
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        if self.close() {
+            self.subject.error(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::sources::range;
+    use crate::testing::marbles::cold;
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn group_by_splits_items_by_key() {
+        let groups = Arc::new(Mutex::new(Vec::new()));
+        let opened = groups.clone();
+        let keys = TestObserver::new();
+        range(0, 10)
+            .group_by(|x| x % 3)
+            .map(move |group| {
+                let observer = TestObserver::new();
+                group.subscribe(observer.clone());
+                opened.lock().unwrap().push(observer);
+                *group.key()
+            })
+            .subscribe(keys.clone());
+
+        keys.assert_values(&[0, 1, 2]);
+        keys.assert_completed();
+        let groups = groups.lock().unwrap();
+        groups[0].assert_values(&[0, 3, 6, 9]);
+        groups[1].assert_values(&[1, 4, 7]);
+        groups[2].assert_values(&[2, 5, 8]);
+        for group in groups.iter() {
+            group.assert_completed();
+        }
+    }
+
+    #[test]
+    fn group_by_until_reopens_a_closed_group() {
+        let scheduler = TestScheduler::new();
+        let closer = scheduler.clone();
+        let groups = Arc::new(Mutex::new(Vec::new()));
+        let opened = groups.clone();
+        let keys = TestObserver::new();
+        cold(&scheduler, "aa--a|")
+            .group_by_until(|c| *c, move |_| cold(&closer, "--x"))
+            .map(move |group| {
+                let observer = TestObserver::new();
+                group.subscribe(observer.clone());
+                opened.lock().unwrap().push(observer);
+                *group.key()
+            })
+            .subscribe(keys.clone());
+        scheduler.advance_by(Duration::from_millis(100));
+
+        keys.assert_values(&['a', 'a']);
+        let groups = groups.lock().unwrap();
+        groups[0].assert_values(&['a', 'a']);
+        groups[0].assert_completed();
+        groups[1].assert_values(&['a']);
+        groups[1].assert_completed();
+    }
+}
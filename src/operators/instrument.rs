@@ -0,0 +1,219 @@
+use std::cell::Cell;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+
+// Instrumented stages between the one subscribing and the subscriber. It's
+// counted on the way up the chain while subscribing, so a stage subscribed
+// from another thread (after subscribe_on, say) starts again from 0.
+thread_local! {
+    static POSITION: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Event<'a> {
+    Subscribe,
+    Next(u64),
+    Error(&'a Error),
+    Completed,
+    Unsubscribe
+}
+
+// Everything goes to the "rx" target: items at trace, errors at warn and the
+// rest of the lifecycle at debug. With both features on, events go to both.
+pub(crate) fn emit(name: &'static str, position: usize, event: Event) {
+    #[cfg(feature = "log")]
+    match event {
+        Event::Subscribe => log::debug!(target: "rx", "{}[{}]: subscribe", name, position),
+        Event::Next(index) => log::trace!(target: "rx", "{}[{}]: next #{}", name, position, index),
+        Event::Error(err) => log::warn!(target: "rx", "{}[{}]: error: {}", name, position, err),
+        Event::Completed => log::debug!(target: "rx", "{}[{}]: completed", name, position),
+        Event::Unsubscribe => log::debug!(target: "rx", "{}[{}]: unsubscribe", name, position)
+    }
+
+    #[cfg(feature = "tracing")]
+    match event {
+        Event::Subscribe => tracing::debug!(target: "rx", name, position, "subscribe"),
+        Event::Next(index) => tracing::trace!(target: "rx", name, position, index, "next"),
+        Event::Error(err) => tracing::warn!(target: "rx", name, position, error = %err, "error"),
+        Event::Completed => tracing::debug!(target: "rx", name, position, "completed"),
+        Event::Unsubscribe => tracing::debug!(target: "rx", name, position, "unsubscribe")
+    }
+}
+
+pub struct InstrumentObservable<S> {
+    pub(crate) name: &'static str,
+    pub(crate) source: S
+}
+
+impl<S> Observable for InstrumentObservable<S>
+    where S: Observable,
+          S::Item: Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let position = POSITION.with(|cell| cell.replace(cell.get() + 1));
+        emit(self.name, position, Event::Subscribe);
+        self.source.subscribe(InstrumentObserver {name: self.name, position: position, count: 0, done: false, observer: observer});
+        POSITION.with(|cell| cell.set(position));
+    }
+}
+
+// Unsubscribe is the downstream returning Stop, or the observer being let go
+// before the stream ended. Only the first of those and the terminal events is
+// reported.
+pub(crate) struct InstrumentObserver<N> {
+    pub(crate) name: &'static str,
+    pub(crate) position: usize,
+    pub(crate) count: u64,
+    pub(crate) done: bool,
+    pub(crate) observer: N
+}
+
+impl<N> InstrumentObserver<N> {
+    fn finish(&mut self, event: Event) {
+        if !self.done {
+            self.done = true;
+            emit(self.name, self.position, event);
+        }
+    }
+}
+
+impl<N> Observer for InstrumentObserver<N>
+    where N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        emit(self.name, self.position, Event::Next(self.count));
+        self.count += 1;
+
+        let result = self.observer.next(val);
+        if let Stop = result {
+            self.finish(Event::Unsubscribe);
+        }
+        result
+    }
+
+    fn completed(&mut self) {
+        self.finish(Event::Completed);
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.finish(Event::Error(&err));
+        self.observer.error(err);
+    }
+}
+
+impl<N> Drop for InstrumentObserver<N> {
+    fn drop(&mut self) {
+        self.finish(Event::Unsubscribe);
+    }
+}
+
+#[cfg(all(test, feature = "log"))]
+mod tests {
+    use std::cell::RefCell;
+    use std::sync::{Arc, Once};
+
+    use log::{Level, Log, Metadata, Record};
+
+    use crate::observable::Observable;
+    use crate::sources::{create, range, throw, Emitter};
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::TestObserver;
+
+    // The logger's global, so each test thread keeps what it logged to itself
+    thread_local! {
+        static LINES: RefCell<Vec<(Level, String)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    struct Capture;
+
+    impl Log for Capture {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.target() == "rx"
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                LINES.with(|lines| lines.borrow_mut().push((record.level(), record.args().to_string())));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn captured<F>(f: F) -> Vec<(Level, String)>
+        where F: FnOnce() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            log::set_logger(&Capture).unwrap();
+            log::set_max_level(log::LevelFilter::Trace);
+        });
+        LINES.with(|lines| lines.borrow_mut().clear());
+        f();
+        LINES.with(|lines| lines.borrow_mut().split_off(0))
+    }
+
+    fn messages(lines: &[(Level, String)]) -> Vec<&str> {
+        lines.iter().map(|(_, line)| line.as_str()).collect()
+    }
+
+    #[test]
+    fn instrument_reports_the_lifecycle() {
+        let lines = captured(|| range(0, 2).instrument("numbers").subscribe(TestObserver::new()));
+        assert_eq!(messages(&lines), vec![
+            "numbers[0]: subscribe",
+            "numbers[0]: next #0",
+            "numbers[0]: next #1",
+            "numbers[0]: completed"
+        ]);
+        assert_eq!(lines.iter().map(|&(level, _)| level).collect::<Vec<_>>(), vec![Level::Debug, Level::Trace, Level::Trace, Level::Debug]);
+    }
+
+    #[test]
+    fn instrument_reports_errors_at_warn() {
+        let lines = captured(|| throw::<i32>(Arc::new(MarbleError)).instrument("failing").subscribe(TestObserver::new()));
+        assert_eq!(lines.last(), Some(&(Level::Warn, "failing[0]: error: marble error".to_string())));
+    }
+
+    #[test]
+    fn instrument_reports_a_stop_as_one_unsubscribe() {
+        let lines = captured(|| range(0, 10).instrument("numbers").take(1).subscribe(TestObserver::new()));
+        assert_eq!(messages(&lines), vec!["numbers[0]: subscribe", "numbers[0]: next #0", "numbers[0]: unsubscribe"]);
+    }
+
+    #[test]
+    fn instrument_reports_an_observer_let_go_as_an_unsubscribe() {
+        let lines = captured(|| create(|_: Emitter<i32>| {}).instrument("abandoned").subscribe(TestObserver::new()));
+        assert_eq!(messages(&lines), vec!["abandoned[0]: subscribe", "abandoned[0]: unsubscribe"]);
+    }
+
+    #[test]
+    fn instrumented_stages_are_numbered_up_the_chain() {
+        let lines = captured(|| {
+            range(0, 1).instrument("inner").map(|val| val + 1).instrument("outer").subscribe(TestObserver::new());
+            range(0, 1).instrument("again").subscribe(TestObserver::new());
+        });
+        assert_eq!(messages(&lines), vec![
+            "outer[0]: subscribe",
+            "inner[1]: subscribe",
+            "inner[1]: next #0",
+            "outer[0]: next #0",
+            "inner[1]: completed",
+            "outer[0]: completed",
+            "again[0]: subscribe",
+            "again[0]: next #0",
+            "again[0]: completed"
+        ]);
+    }
+}
@@ -0,0 +1,335 @@
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::subjects::Terminal;
+use crate::sync::MutexExt;
+
+// A slot holding at most one item, swapped in by the producer and out by the
+// consumer without either waiting on the other. Putting into a full slot
+// drops what was there.
+pub(crate) struct LatestCell<A> {
+    pub(crate) ptr: AtomicPtr<A>
+}
+
+impl<A> LatestCell<A> {
+    pub(crate) fn new() -> LatestCell<A> {
+        LatestCell {ptr: AtomicPtr::new(ptr::null_mut())}
+    }
+
+    pub(crate) fn put(&self, val: A) {
+        let old = self.ptr.swap(Box::into_raw(Box::new(val)), Ordering::AcqRel);
+        if !old.is_null() {
+            // Swapped out, so nothing else can reach it
+            drop(unsafe { Box::from_raw(old) });
+        }
+    }
+
+    pub(crate) fn take(&self) -> Option<A> {
+        let val = self.ptr.swap(ptr::null_mut(), Ordering::AcqRel);
+        if val.is_null() {
+            None
+        } else {
+            Some(*unsafe { Box::from_raw(val) })
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.ptr.load(Ordering::Acquire).is_null()
+    }
+}
+
+impl<A> Drop for LatestCell<A> {
+    fn drop(&mut self) {
+        self.take();
+    }
+}
+
+// The cell owns the item behind the pointer, so it's as Send as the item, and
+// it only ever hands out whole items, never references
+unsafe impl<A> Send for LatestCell<A>
+    where A: Send {}
+
+unsafe impl<A> Sync for LatestCell<A>
+    where A: Send {}
+
+// The source never waits on the downstream: each item replaces the one still
+// waiting to go out, and the downstream is given the newest on the scheduler
+// whenever it's done with the last. Completion comes after the last item.
+pub struct LatestOnlyObservable<S, D> {
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, D> Observable for LatestOnlyObservable<S, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(LatestOnlyObserver {
+            scheduler: self.scheduler.clone(),
+            mailbox: Arc::new(Mailbox {
+                latest: LatestCell::new(),
+                terminal: Mutex::new(None),
+                draining: AtomicBool::new(false),
+                stopped: AtomicBool::new(false),
+                observer: Mutex::new(observer)
+            })
+        });
+    }
+}
+
+// Only one drain runs at a time, so the downstream never sees two threads at
+// once, and it's the drain that clears the draining flag
+pub(crate) struct Mailbox<A, N> {
+    pub(crate) latest: LatestCell<A>,
+    pub(crate) terminal: Mutex<Option<Terminal>>,
+    pub(crate) draining: AtomicBool,
+    pub(crate) stopped: AtomicBool,
+    pub(crate) observer: Mutex<N>
+}
+
+impl<A, N> Mailbox<A, N>
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static {
+    fn pending(&self) -> bool {
+        !self.latest.is_empty() || self.terminal.acquire().is_some()
+    }
+
+    fn schedule<D>(self: &Arc<Self>, scheduler: &D)
+        where D: Scheduler {
+        if self.draining.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+            let mailbox = self.clone();
+            scheduler.schedule(Box::new(move || mailbox.drain()));
+        }
+    }
+
+    fn drain(&self) {
+        let mut observer = self.observer.acquire();
+
+        loop {
+            while let Some(val) = self.latest.take() {
+                if !self.deliver(&mut *observer, val) {
+                    return;
+                }
+            }
+
+            let terminal = self.terminal.acquire().take();
+            if let Some(terminal) = terminal {
+                // The source's last item went in before its terminal did
+                if let Some(val) = self.latest.take() {
+                    if !self.deliver(&mut *observer, val) {
+                        return;
+                    }
+                }
+                self.stopped.store(true, Ordering::Release);
+                return terminal.deliver(&mut *observer);
+            }
+
+            self.draining.store(false, Ordering::Release);
+            // Whatever came in after the checks above saw the flag still set
+            // and left it to this drain
+            if !self.pending() || self.draining.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).is_err() {
+                return;
+            }
+        }
+    }
+
+    // False once the downstream has stopped, which completes it
+    fn deliver(&self, observer: &mut N, val: A) -> bool {
+        match observer.next(val) {
+            Continue => true,
+            Stop => {
+                self.stopped.store(true, Ordering::Release);
+                self.latest.take();
+                observer.completed();
+                false
+            }
+        }
+    }
+}
+
+pub(crate) struct LatestOnlyObserver<A, N, D> {
+    pub(crate) scheduler: D,
+    pub(crate) mailbox: Arc<Mailbox<A, N>>
+}
+
+impl<A, N, D> LatestOnlyObserver<A, N, D>
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler {
+    fn finish(&mut self, terminal: Terminal) {
+        if !self.mailbox.stopped.load(Ordering::Acquire) {
+            *self.mailbox.terminal.acquire() = Some(terminal);
+            self.mailbox.schedule(&self.scheduler);
+        }
+    }
+}
+
+impl<A, N, D> Observer for LatestOnlyObserver<A, N, D>
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        if self.mailbox.stopped.load(Ordering::Acquire) {
+            return Stop;
+        }
+
+        self.mailbox.latest.put(val);
+        self.mailbox.schedule(&self.scheduler);
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.finish(Terminal::Completed);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.finish(Terminal::Failed(err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::operators::latest_only::LatestCell;
+    use crate::schedulers::thread_pool::ThreadPoolScheduler;
+    use crate::sources::range;
+    use crate::subjects::Subject;
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn latest_cell_keeps_only_the_newest() {
+        let cell = LatestCell::new();
+        assert!(cell.is_empty());
+        assert_eq!(cell.take(), None);
+
+        let first = Arc::new(1);
+        cell.put(first.clone());
+        cell.put(Arc::new(2));
+        assert_eq!(Arc::strong_count(&first), 1);
+        assert_eq!(cell.take().map(|val| *val), Some(2));
+        assert!(cell.is_empty());
+
+        cell.put(first.clone());
+        drop(cell);
+        assert_eq!(Arc::strong_count(&first), 1);
+    }
+
+    #[test]
+    fn latest_cell_hands_out_items_in_order_across_threads() {
+        let cell = Arc::new(LatestCell::new());
+        let producer = {
+            let cell = cell.clone();
+            thread::spawn(move || {
+                for i in 0..100_000 {
+                    cell.put(i);
+                }
+            })
+        };
+
+        let mut last = None;
+        while !producer.is_finished() || !cell.is_empty() {
+            if let Some(val) = cell.take() {
+                assert!(last < Some(val));
+                last = Some(val);
+            }
+        }
+        producer.join().unwrap();
+        assert_eq!(last, Some(99_999));
+    }
+
+    #[test]
+    fn latest_only_conflates_what_arrives_between_drains() {
+        let scheduler = TestScheduler::new();
+        let subject = Subject::new();
+        let observer = TestObserver::new();
+        subject.clone().latest_only_on(scheduler.clone()).subscribe(observer.clone());
+
+        let mut source = subject.clone();
+        source.next(1);
+        source.next(2);
+        source.next(3);
+        observer.assert_values(&[]);
+        scheduler.trigger_actions();
+        observer.assert_values(&[3]);
+
+        source.next(4);
+        source.completed();
+        scheduler.trigger_actions();
+        observer.assert_values(&[3, 4]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn latest_only_delivers_the_last_item_before_an_error() {
+        let scheduler = TestScheduler::new();
+        let subject = Subject::new();
+        let observer = TestObserver::new();
+        subject.clone().latest_only_on(scheduler.clone()).subscribe(observer.clone());
+
+        let mut source = subject.clone();
+        source.next(1);
+        source.error(Arc::new(MarbleError));
+        scheduler.trigger_actions();
+        observer.assert_values(&[1]);
+        observer.assert_error();
+    }
+
+    #[test]
+    fn latest_only_stops_the_source_once_the_downstream_stops() {
+        let scheduler = TestScheduler::new();
+        let subject = Subject::new();
+        let observer = TestObserver::new();
+        subject.clone().latest_only_on(scheduler.clone()).take(1).subscribe(observer.clone());
+
+        let mut source = subject.clone();
+        source.next(1);
+        scheduler.trigger_actions();
+        observer.assert_values(&[1]);
+        observer.assert_completed();
+
+        source.next(2);
+        scheduler.trigger_actions();
+        observer.assert_values(&[1]);
+        assert_eq!(observer.completion_count(), 1);
+    }
+
+    #[test]
+    fn latest_only_ends_on_the_newest_item_on_a_thread_pool() {
+        let scheduler = ThreadPoolScheduler::new(4);
+        for _ in 0..20 {
+            let observer = TestObserver::new();
+            range(0, 10_000).latest_only_on(scheduler.clone()).subscribe(observer.clone());
+            assert!(observer.await_terminal_event(Duration::from_secs(5)));
+
+            let values = observer.values();
+            assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+            assert_eq!(values.last(), Some(&9_999));
+            observer.assert_completed();
+        }
+        scheduler.shutdown();
+    }
+}
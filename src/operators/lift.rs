@@ -0,0 +1,56 @@
+use alloc::format;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, Observer};
+
+// Builds the observer subscribed upstream out of the one downstream, which
+// is all most operators need to define. Observable::lift turns one into an
+// observable.
+pub trait Operator<A, E = Error> {
+    type Item;
+    type Upstream<N>: Observer<E, Item=A> + Send + Sync + 'static
+        where N: Observer<E, Item=Self::Item> + Send + Sync + 'static;
+
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=Self::Item> + Send + Sync + 'static;
+
+    // For Observable::subscribe_owned. Operators that share state with the
+    // observers they build can move it into the one observer instead.
+    #[inline]
+    fn call_owned<N>(self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=Self::Item> + Send + Sync + 'static,
+              Self: Sized {
+        self.call(observer)
+    }
+}
+
+pub struct LiftObservable<S, O> {
+    pub(crate) operator: O,
+    pub(crate) source: S
+}
+
+impl<S, O, E> Observable<E> for LiftObservable<S, O>
+    where S: Observable<E>,
+          O: Operator<S::Item, E> {
+    type Item = O::Item;
+
+    // Named for the operator, so a MapOperator shows as MapObservable
+    fn node(&self) -> Node {
+        let operator = short_name::<O>();
+        let name = format!("{}Observable", operator.strip_suffix("Operator").unwrap_or(operator));
+        Node::new(&name, [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<E, Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(self.operator.call(observer));
+    }
+
+    #[inline]
+    fn subscribe_owned<N>(self, observer: N)
+        where N: Observer<E, Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe_owned(self.operator.call_owned(observer));
+    }
+}
@@ -0,0 +1,116 @@
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+
+use crate::observer::{IterationResult, Observer};
+use crate::operators::filter_map::{FilterMapObservable, FilterMapOperator};
+use crate::operators::lift::{LiftObservable, Operator};
+
+pub type MapObservable<F, S> = LiftObservable<S, MapOperator<F>>;
+
+pub struct MapOperator<F> {
+    pub(crate) f: Arc<F>
+}
+
+impl<A, B, F, E> Operator<A, E> for MapOperator<F>
+    where A: Send + Sync + 'static,
+          F: Fn(A) -> B + Send + Sync + 'static {
+    type Item = B;
+    type Upstream<N> = MapObserver<F, N, A>
+        where N: Observer<E, Item=B> + Send + Sync + 'static;
+
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=B> + Send + Sync + 'static {
+        MapObserver {f: self.f.clone(), observer: observer, _marker: PhantomData}
+    }
+
+    fn call_owned<N>(self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=B> + Send + Sync + 'static {
+        MapObserver {f: self.f, observer: observer, _marker: PhantomData}
+    }
+}
+
+pub struct MapObserver<F, N, B> {
+    pub(crate) f: Arc<F>,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<B>
+}
+
+impl<B, N, F, E> Observer<E> for MapObserver<F, N, B>
+    where N: Observer<E>,
+          F: Fn(B) -> <N as Observer<E>>::Item {
+    type Item = B;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.observer.next((self.f)(val))
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
+
+// Fusion: a map or filter straight after a map is composed into it, so a
+// chain of them is one stage with one observer rather than one per operator.
+// These are inherent so they're found before Observable's map and filter;
+// going through the trait, or through pipe, doesn't fuse.
+impl<S, F> LiftObservable<S, MapOperator<F>> {
+    #[inline]
+    pub fn map<A, B, C, G>(self, g: G) -> MapObservable<impl Fn(A) -> C + Send + Sync + 'static, S>
+        where F: Fn(A) -> B + Send + Sync + 'static,
+              G: Fn(B) -> C + Send + Sync + 'static {
+        let f = self.operator.f;
+        LiftObservable {operator: MapOperator {f: Arc::new(move |val| g(f(val)))}, source: self.source}
+    }
+
+    #[inline]
+    pub fn filter<A, B, P>(self, predicate: P) -> FilterMapObservable<impl Fn(A) -> Option<B> + Send + Sync + 'static, S>
+        where F: Fn(A) -> B + Send + Sync + 'static,
+              P: Fn(&B) -> bool + Send + Sync + 'static {
+        let f = self.operator.f;
+        LiftObservable {operator: FilterMapOperator {f: Arc::new(move |val| Some(f(val)).filter(&predicate))}, source: self.source}
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::observable::Observable;
+    use crate::operators::filter_map::FilterMapObservable;
+    use crate::operators::map::MapObservable;
+    use crate::sources::{range, RangeObservable};
+    use crate::testing::observer::TestObserver;
+
+    fn values<S>(source: S) -> Vec<S::Item>
+        where S: Observable,
+              S::Item: Clone + Send + 'static {
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        observer.assert_completed();
+        observer.values()
+    }
+
+    #[test]
+    fn map_after_map_is_one_stage() {
+        let fused: MapObservable<_, RangeObservable<i32>> = range(0, 5).map(|val| val + 1).map(|val| val * 10);
+        let unfused = Observable::map(Observable::map(range(0, 5), |val| val + 1), |val| val * 10);
+        assert_eq!(values(fused), values(unfused));
+    }
+
+    #[test]
+    fn filter_after_map_is_one_stage() {
+        let fused: FilterMapObservable<_, RangeObservable<i32>> = range(0, 10).map(|val| val * 3).filter(|val| val % 2 == 0);
+        let unfused = Observable::filter(Observable::map(range(0, 10), |val| val * 3), |val| val % 2 == 0);
+        assert_eq!(values(fused), values(unfused));
+    }
+
+    #[test]
+    fn fused_maps_still_stop_with_the_downstream() {
+        let observer = TestObserver::new();
+        range(0, i32::MAX).map(|val| val + 1).map(|val| val * 2).take(3).subscribe(observer.clone());
+        observer.assert_values(&[2, 4, 6]);
+        observer.assert_completed();
+    }
+}
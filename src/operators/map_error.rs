@@ -0,0 +1,163 @@
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{IterationResult, Observer};
+
+// Converts a chain's errors, e.g. from a library's own error enum into the
+// shared Error the built-in operators use, or back out again
+pub struct MapErrorObservable<F, S, E> {
+    pub(crate) f: Arc<F>,
+    pub(crate) source: S,
+    pub(crate) _marker: PhantomData<fn(E)>
+}
+
+impl<F, S, E, X> Observable<X> for MapErrorObservable<F, S, E>
+    where S: Observable<E>,
+          S::Item: Send + Sync + 'static,
+          F: Fn(E) -> X + Send + Sync + 'static,
+          E: 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<X, Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(MapErrorObserver {f: self.f.clone(), observer: observer, _marker: PhantomData});
+    }
+
+    fn subscribe_owned<N>(self, observer: N)
+        where N: Observer<X, Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe_owned(MapErrorObserver {f: self.f, observer: observer, _marker: PhantomData});
+    }
+}
+
+pub(crate) struct MapErrorObserver<F, N, E> {
+    pub(crate) f: Arc<F>,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<fn(E)>
+}
+
+impl<F, N, E, X> Observer<E> for MapErrorObserver<F, N, E>
+    where N: Observer<X>,
+          F: Fn(E) -> X {
+    type Item = N::Item;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.observer.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error((self.f)(err));
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::observable::Observable;
+    use crate::observer::{IterationResult, Observer};
+    use crate::observer::IterationResult::Continue;
+    use crate::sources::{range, throw};
+    use crate::testing::marbles::MarbleError;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct LibError(String);
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum AppError {
+        Lib(String)
+    }
+
+    impl From<LibError> for AppError {
+        fn from(err: LibError) -> AppError {
+            AppError::Lib(err.0)
+        }
+    }
+
+    // TestObserver only takes the shared Error, so this records any other
+    #[derive(Clone)]
+    struct Recorder<X> {
+        events: Arc<Mutex<Vec<Result<i32, Option<X>>>>>
+    }
+
+    impl<X> Recorder<X> {
+        fn new() -> Recorder<X> {
+            Recorder {events: Arc::new(Mutex::new(Vec::new()))}
+        }
+
+        fn events(&self) -> Vec<Result<i32, Option<X>>>
+            where X: Clone {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    impl<X> Observer<X> for Recorder<X> {
+        type Item = i32;
+
+        fn next(&mut self, val: i32) -> IterationResult {
+            self.events.lock().unwrap().push(Ok(val));
+            Continue
+        }
+
+        fn completed(&mut self) {
+            self.events.lock().unwrap().push(Err(None));
+        }
+
+        fn error(&mut self, err: X) {
+            self.events.lock().unwrap().push(Err(Some(err)));
+        }
+    }
+
+    #[test]
+    fn map_error_converts_the_error_and_leaves_the_rest() {
+        let recorder = Recorder::new();
+        range(0, 2).map_error(|err| LibError(err.to_string())).subscribe(recorder.clone());
+        assert_eq!(recorder.events(), vec![Ok(0), Ok(1), Err(None)]);
+
+        let recorder = Recorder::new();
+        throw::<i32>(Arc::new(MarbleError)).map_error(|err| LibError(err.to_string())).subscribe(recorder.clone());
+        assert_eq!(recorder.events(), vec![Err(Some(LibError("marble error".to_string())))]);
+    }
+
+    #[test]
+    fn err_into_converts_with_from() {
+        let recorder = Recorder::<AppError>::new();
+        throw::<i32>(Arc::new(MarbleError))
+            .map_error(|err| LibError(err.to_string()))
+            .err_into()
+            .subscribe(recorder.clone());
+        assert_eq!(recorder.events(), vec![Err(Some(AppError::Lib("marble error".to_string())))]);
+    }
+
+    #[test]
+    fn inspect_err_sees_the_error_and_passes_it_on() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let into = seen.clone();
+        let recorder = Recorder::new();
+        throw::<i32>(Arc::new(MarbleError))
+            .map_error(|err| LibError(err.to_string()))
+            .inspect_err(move |err: &LibError| into.lock().unwrap().push(err.clone()))
+            .subscribe(recorder.clone());
+        assert_eq!(*seen.lock().unwrap(), vec![LibError("marble error".to_string())]);
+        assert_eq!(recorder.events(), vec![Err(Some(LibError("marble error".to_string())))]);
+
+        let seen = Arc::new(Mutex::new(Vec::<LibError>::new()));
+        let into = seen.clone();
+        let recorder = Recorder::new();
+        range(0, 1)
+            .map_error(|err| LibError(err.to_string()))
+            .inspect_err(move |err| into.lock().unwrap().push(err.clone()))
+            .subscribe(recorder.clone());
+        assert!(seen.lock().unwrap().is_empty());
+        assert_eq!(recorder.events(), vec![Ok(0), Err(None)]);
+    }
+}
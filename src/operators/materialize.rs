@@ -0,0 +1,171 @@
+use core::marker::PhantomData;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Notification<A, E> {
+    Next(A),
+    Error(E),
+    Completed
+}
+
+pub struct MaterializeObservable<S> {
+    pub(crate) source: S
+}
+
+impl<S> Observable for MaterializeObservable<S>
+    where S: Observable,
+          S::Item: Send + Sync + 'static {
+    type Item = Notification<S::Item, Error>;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(MaterializeObserver {observer: observer, _marker: PhantomData});
+    }
+}
+
+pub(crate) struct MaterializeObserver<A, N> {
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, N> Observer for MaterializeObserver<A, N>
+    where N: Observer<Item=Notification<A, Error>> {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.observer.next(Notification::Next(val))
+    }
+
+    fn completed(&mut self) {
+        self.observer.next(Notification::Completed);
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.next(Notification::Error(err));
+        self.observer.completed();
+    }
+}
+
+pub struct DematerializeObservable<S> {
+    pub(crate) source: S
+}
+
+impl<S, A> Observable for DematerializeObservable<S>
+    where S: Observable<Item=Notification<A, Error>>,
+          A: Send + Sync + 'static {
+    type Item = A;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(DematerializeObserver {done: false, observer: observer});
+    }
+}
+
+// A terminal notification ends the stream; the source's own completion that
+// follows is swallowed
+pub(crate) struct DematerializeObserver<N> {
+    pub(crate) done: bool,
+    pub(crate) observer: N
+}
+
+impl<N> Observer for DematerializeObserver<N>
+    where N: Observer {
+    type Item = Notification<N::Item, Error>;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        if self.done {
+            return Stop;
+        }
+
+        match val {
+            Notification::Next(val) => self.observer.next(val),
+            Notification::Error(err) => {
+                self.error(err);
+                Stop
+            },
+            Notification::Completed => {
+                self.completed();
+                Stop
+            }
+        }
+    }
+
+    fn completed(&mut self) {
+        if !self.done {
+            self.done = true;
+            self.observer.completed();
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        if !self.done {
+            self.done = true;
+            self.observer.error(err);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::observable::Observable;
+    use crate::operators::materialize::Notification;
+    use crate::sources::{from_iter, range, throw};
+    use crate::testing::marbles::{assert_marbles, cold, MarbleError};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn materialize_turns_completion_into_an_item() {
+        let observer = TestObserver::new();
+        range(0, 2).materialize().subscribe(observer.clone());
+        let values = observer.values();
+        assert!(matches!(values[..], [Notification::Next(0), Notification::Next(1), Notification::Completed]));
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn materialize_turns_an_error_into_an_item_and_completes() {
+        let observer = TestObserver::new();
+        throw::<i32>(Arc::new(MarbleError)).materialize().subscribe(observer.clone());
+        let values = observer.values();
+        assert!(matches!(values[..], [Notification::Error(_)]));
+        observer.assert_completed();
+        observer.assert_no_errors();
+    }
+
+    #[test]
+    fn dematerialize_undoes_materialize() {
+        let scheduler = TestScheduler::new();
+        assert_marbles(&scheduler, cold(&scheduler, "a-b-#").materialize().dematerialize(), "a-b-#");
+        assert_marbles(&scheduler, cold(&scheduler, "a-b|").materialize().dematerialize(), "a-b|");
+    }
+
+    #[test]
+    fn dematerialize_ends_at_the_first_terminal_notification() {
+        let observer = TestObserver::new();
+        from_iter(vec![Notification::Next(1), Notification::Completed, Notification::Next(2)])
+            .dematerialize()
+            .subscribe(observer.clone());
+        observer.assert_values(&[1]);
+        assert_eq!(observer.completion_count(), 1);
+    }
+}
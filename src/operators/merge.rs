@@ -0,0 +1,315 @@
+#[cfg(feature = "std")]
+use std::sync::Arc;
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::sync::mpsc::{channel, Receiver, Sender};
+#[cfg(feature = "std")]
+use std::cell::UnsafeCell;
+#[cfg(feature = "std")]
+use std::marker::PhantomData;
+
+#[cfg(feature = "std")]
+use crate::describe::{Node, short_name};
+#[cfg(feature = "std")]
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+#[cfg(feature = "std")]
+use crate::observer::IterationResult::Continue;
+
+// The outer source counts as one active subscription, so the merged stream
+// completes once the outer source and every inner observable have completed
+pub(crate) struct MergeState<N> {
+    pub(crate) observer: N,
+    pub(crate) active: usize,
+    pub(crate) stopped: bool,
+    pub(crate) terminated: bool
+}
+
+impl<N> MergeState<N>
+    where N: Observer {
+    pub(crate) fn next(&mut self, val: N::Item) -> IterationResult {
+        if self.stopped || self.terminated {
+            return Stop;
+        }
+
+        let result = self.observer.next(val);
+        if let Stop = result {
+            self.stopped = true;
+        }
+        result
+    }
+
+    pub(crate) fn completed(&mut self) {
+        self.active -= 1;
+        if self.active == 0 && !self.terminated {
+            self.terminated = true;
+            self.observer.completed();
+        }
+    }
+
+    pub(crate) fn error(&mut self, err: Error) {
+        if !self.terminated {
+            self.terminated = true;
+            self.observer.error(err);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) enum MergeSignal<A> {
+    Next(A),
+    // Another inner observable is about to be subscribed
+    Subscribed,
+    Completed,
+    Error(Error)
+}
+
+#[cfg(feature = "std")]
+pub(crate) struct MergeDrain<N>
+    where N: Observer {
+    pub(crate) state: MergeState<N>,
+    pub(crate) receiver: Receiver<MergeSignal<N::Item>>
+}
+
+// Sources emitting from several threads at once don't wait on each other.
+// Whoever finds the merge idle delivers straight to the observer; anyone who
+// turns up meanwhile queues their signal and carries on, and the thread
+// that's delivering drains the queue before letting go. wip counts the
+// signals not yet handled, and only the thread that takes it off zero touches
+// the observer, so nothing ever waits on a lock.
+#[cfg(feature = "std")]
+pub(crate) struct MergeShared<N>
+    where N: Observer {
+    pub(crate) drain: UnsafeCell<MergeDrain<N>>,
+    pub(crate) sender: Sender<MergeSignal<N::Item>>,
+    pub(crate) wip: AtomicUsize,
+    pub(crate) done: AtomicBool
+}
+
+#[cfg(feature = "std")]
+impl<N> MergeShared<N>
+    where N: Observer {
+    pub(crate) fn new(observer: N, active: usize) -> Arc<MergeShared<N>> {
+        let (sender, receiver) = channel();
+        Arc::new(MergeShared {
+            drain: UnsafeCell::new(MergeDrain {
+                state: MergeState {observer: observer, active: active, stopped: false, terminated: false},
+                receiver: receiver
+            }),
+            sender: sender,
+            wip: AtomicUsize::new(0),
+            done: AtomicBool::new(false)
+        })
+    }
+
+    pub(crate) fn signal(&self, signal: MergeSignal<N::Item>) -> IterationResult {
+        if self.done.load(Ordering::Acquire) {
+            return Stop;
+        }
+
+        if self.wip.compare_exchange(0, 1, Ordering::AcqRel, Ordering::Relaxed).is_ok() {
+            // This thread now holds wip, see the Sync impl below
+            let drain = unsafe { &mut *self.drain.get() };
+            self.apply(drain, signal);
+            // The queue is only looked at if someone else turned up
+            let missed = self.wip.fetch_sub(1, Ordering::AcqRel) - 1;
+            if missed > 0 {
+                self.drain_loop(drain, missed);
+            }
+        } else {
+            // The receiver lives as long as this does, so sending can't fail
+            let _ = self.sender.send(signal);
+            if self.wip.fetch_add(1, Ordering::AcqRel) == 0 {
+                self.drain_loop(unsafe { &mut *self.drain.get() }, 1);
+            }
+        }
+
+        if self.done.load(Ordering::Acquire) {
+            Stop
+        } else {
+            Continue
+        }
+    }
+
+    fn apply(&self, drain: &mut MergeDrain<N>, signal: MergeSignal<N::Item>) {
+        let state = &mut drain.state;
+        match signal {
+            MergeSignal::Next(val) => { state.next(val); },
+            MergeSignal::Subscribed => state.active += 1,
+            MergeSignal::Completed => state.completed(),
+            MergeSignal::Error(err) => state.error(err)
+        }
+        if state.stopped || state.terminated {
+            self.done.store(true, Ordering::Release);
+        }
+    }
+
+    // Entered holding missed units of wip
+    fn drain_loop(&self, drain: &mut MergeDrain<N>, mut missed: usize) {
+        loop {
+            while let Ok(signal) = drain.receiver.try_recv() {
+                self.apply(drain, signal);
+            }
+
+            missed = self.wip.fetch_sub(missed, Ordering::AcqRel) - missed;
+            if missed == 0 {
+                return;
+            }
+        }
+    }
+}
+
+// The drain is only reached through signal, by whichever thread moved wip off
+// zero, and is let go of by moving wip back to zero. That's never more than
+// one thread at a time, so sharing only needs what moving it would.
+#[cfg(feature = "std")]
+unsafe impl<N> Sync for MergeShared<N>
+    where N: Observer + Send,
+          N::Item: Send {}
+
+#[cfg(feature = "std")]
+pub(crate) struct SharedObserver<N>
+    where N: Observer {
+    pub(crate) shared: Arc<MergeShared<N>>
+}
+
+#[cfg(feature = "std")]
+impl<N> Observer for SharedObserver<N>
+    where N: Observer {
+    type Item = N::Item;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.shared.signal(MergeSignal::Next(val))
+    }
+
+    fn completed(&mut self) {
+        self.shared.signal(MergeSignal::Completed);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.shared.signal(MergeSignal::Error(err));
+    }
+}
+
+#[cfg(feature = "std")]
+pub struct MergeAllObservable<S, U> {
+    pub(crate) source: S,
+    pub(crate) _marker: PhantomData<U>
+}
+
+#[cfg(feature = "std")]
+impl<U, S> Observable for MergeAllObservable<S, U>
+    where S::Item: Observable + Send + Sync,
+          S: Observable<Item=U> + Send + Sync,
+          U: Observable + Send + Sync + 'static,
+          U::Item: Send + Sync + 'static {
+    type Item = U::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(MergeAllObserver {shared: MergeShared::new(observer, 1), _marker: PhantomData});
+    }
+
+    fn subscribe_owned<N>(self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe_owned(MergeAllObserver {shared: MergeShared::new(observer, 1), _marker: PhantomData});
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) struct MergeAllObserver<N, U>
+    where N: Observer {
+    pub(crate) shared: Arc<MergeShared<N>>,
+    pub(crate) _marker: PhantomData<U>
+}
+
+#[cfg(feature = "std")]
+impl<N, U> Observer for MergeAllObserver<N, U>
+    where U: Observable + Send + Sync,
+          U::Item: Send + Sync + 'static,
+          N: Observer<Item=U::Item> + Send + Sync + 'static {
+    type Item = U;
+
+    fn next(&mut self, val: U) -> IterationResult {
+        if let Stop = self.shared.signal(MergeSignal::Subscribed) {
+            return Stop;
+        }
+
+        val.subscribe(SharedObserver {shared: self.shared.clone()});
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.shared.signal(MergeSignal::Completed);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.shared.signal(MergeSignal::Error(err));
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::schedulers::thread_pool::ThreadPoolScheduler;
+    use crate::sources::{from_iter, range};
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn merge_all_interleaves_by_time() {
+        let scheduler = TestScheduler::new();
+        let source = from_iter(vec![cold(&scheduler, "a-b|"), cold(&scheduler, "-c-d|")]).merge_all();
+        assert_marbles(&scheduler, source, "acbd|");
+    }
+
+    #[test]
+    fn flat_map_completes_after_the_outer_source() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b--|").flat_map(|c| from_iter(vec![c, c.to_ascii_uppercase()]));
+        assert_marbles(&scheduler, source, "(aA)-(bB)--|");
+    }
+
+    #[test]
+    fn merge_all_fails_on_the_first_error() {
+        let scheduler = TestScheduler::new();
+        let source = from_iter(vec![cold(&scheduler, "a-#"), cold(&scheduler, "-b-c|")]).merge_all();
+        assert_marbles(&scheduler, source, "ab#");
+    }
+
+    #[test]
+    fn merge_all_stops_every_inner_source() {
+        let observer = TestObserver::new();
+        range(0, 4).flat_map(|_| range(0, 100)).take(5).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2, 3, 4]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn concurrent_inner_sources_all_arrive() {
+        let scheduler = ThreadPoolScheduler::new(4);
+        for _ in 0..10 {
+            let observer = TestObserver::new();
+            let pool = scheduler.clone();
+            range(0, 8)
+                .flat_map(move |i| range(i * 1000, (i + 1) * 1000).subscribe_on(pool.clone()))
+                .subscribe(observer.clone());
+            assert!(observer.await_terminal_event(Duration::from_secs(5)));
+
+            let mut values = observer.values();
+            values.sort();
+            assert_eq!(values, (0..8000).collect::<Vec<i32>>());
+            assert_eq!(observer.completion_count(), 1);
+        }
+        scheduler.shutdown();
+    }
+}
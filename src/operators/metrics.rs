@@ -0,0 +1,267 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::schedulers::Scheduler;
+use crate::sync::MutexExt;
+
+// The time constant of the throughput average. Items are weighted by how
+// recently they arrived, so a change in rate shows within a few of these.
+const THROUGHPUT_WINDOW: f64 = 1.0;
+const LATENCY_BUCKETS: usize = 40;
+
+//////////////Histogram
+// Gaps between items, in power of two buckets of microseconds. The first is
+// everything under a microsecond and the last everything over 2^38.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LatencyHistogram {
+    pub(crate) counts: [u64; LATENCY_BUCKETS]
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, gap: Duration) {
+        let micros = gap.as_micros();
+        let bucket = if micros == 0 {
+            0
+        } else {
+            (128 - micros.leading_zeros() as usize).min(LATENCY_BUCKETS - 1)
+        };
+        self.counts[bucket] += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    // Each bucket's upper bound and how many gaps fell in it
+    pub fn buckets(&self) -> impl Iterator<Item=(Duration, u64)> + '_ {
+        self.counts.iter().enumerate().map(|(i, &count)| (Duration::from_micros(1 << i), count))
+    }
+
+    // The upper bound of the bucket holding the given fraction of gaps (0.5
+    // for the median), or None before there are any
+    pub fn percentile(&self, fraction: f64) -> Option<Duration> {
+        let total = self.count();
+        if total == 0 {
+            return None;
+        }
+
+        let target = ((total as f64 * fraction).ceil() as u64).clamp(1, total);
+        let mut seen = 0;
+        self.buckets().find(|&(_, count)| {
+            seen += count;
+            seen >= target
+        }).map(|(bound, _)| bound)
+    }
+}
+//Histogram//////////////
+
+//////////////Metrics
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetricsSnapshot {
+    pub items: u64,
+    pub errors: u64,
+    pub completions: u64,
+    // Items per second, averaged over about the last second
+    pub throughput: f64,
+    pub latency: LatencyHistogram
+}
+
+pub(crate) struct MetricsState {
+    pub(crate) items: u64,
+    pub(crate) errors: u64,
+    pub(crate) completions: u64,
+    pub(crate) rate: f64,
+    pub(crate) last: Option<Instant>,
+    pub(crate) latency: LatencyHistogram
+}
+
+impl MetricsState {
+    // The average decays with the time since the last item
+    fn rate_at(&self, now: Instant) -> f64 {
+        match self.last {
+            Some(last) => self.rate * (-now.saturating_duration_since(last).as_secs_f64() / THROUGHPUT_WINDOW).exp(),
+            None => 0.0
+        }
+    }
+}
+
+// A handle on the numbers, shared by every subscription to the pipeline it
+// came from
+#[derive(Clone)]
+pub struct Metrics<D> {
+    pub(crate) state: Arc<Mutex<MetricsState>>,
+    pub(crate) scheduler: D
+}
+
+impl<D> Metrics<D> {
+    pub(crate) fn new(scheduler: D) -> Metrics<D> {
+        Metrics {
+            state: Arc::new(Mutex::new(MetricsState {
+                items: 0,
+                errors: 0,
+                completions: 0,
+                rate: 0.0,
+                last: None,
+                latency: LatencyHistogram {counts: [0; LATENCY_BUCKETS]}
+            })),
+            scheduler: scheduler
+        }
+    }
+}
+
+impl<D> Metrics<D>
+    where D: Scheduler {
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let state = self.state.acquire();
+
+        MetricsSnapshot {
+            items: state.items,
+            errors: state.errors,
+            completions: state.completions,
+            throughput: state.rate_at(self.scheduler.now()),
+            latency: state.latency.clone()
+        }
+    }
+}
+
+pub struct MetricsObservable<S, D> {
+    pub(crate) metrics: Metrics<D>,
+    pub(crate) source: S
+}
+
+impl<S, D> MetricsObservable<S, D>
+    where D: Clone {
+    #[inline]
+    pub fn handle(&self) -> Metrics<D> {
+        self.metrics.clone()
+    }
+}
+
+impl<S, D> Observable for MetricsObservable<S, D>
+    where S: Observable,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(MetricsObserver {
+            last: self.metrics.scheduler.now(),
+            metrics: self.metrics.clone(),
+            observer: observer
+        });
+    }
+}
+
+// Gaps are measured per subscription, the first from the moment of
+// subscription
+pub(crate) struct MetricsObserver<N, D> {
+    pub(crate) last: Instant,
+    pub(crate) metrics: Metrics<D>,
+    pub(crate) observer: N
+}
+
+impl<N, D> Observer for MetricsObserver<N, D>
+    where N: Observer,
+          D: Scheduler {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let now = self.metrics.scheduler.now();
+        {
+            let mut state = self.metrics.state.acquire();
+            state.items += 1;
+            state.rate = state.rate_at(now) + 1.0 / THROUGHPUT_WINDOW;
+            state.last = Some(now);
+            state.latency.record(now.saturating_duration_since(self.last));
+        }
+        self.last = now;
+        self.observer.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.metrics.state.acquire().completions += 1;
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.metrics.state.acquire().errors += 1;
+        self.observer.error(err);
+    }
+}
+//Metrics//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn metrics_pass_the_stream_through() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").metrics_on(scheduler.clone());
+        assert_marbles(&scheduler, source, "a-b|");
+    }
+
+    #[test]
+    fn snapshot_counts_across_every_subscription() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "ab-c|").metrics_on(scheduler.clone());
+        let metrics = source.handle();
+        source.subscribe(TestObserver::new());
+        source.subscribe(TestObserver::new());
+        scheduler.advance_by(Duration::from_millis(100));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!((snapshot.items, snapshot.completions, snapshot.errors), (6, 2, 0));
+
+        let source = cold(&scheduler, "a#").metrics_on(scheduler.clone());
+        let metrics = source.handle();
+        source.subscribe(TestObserver::new());
+        scheduler.advance_by(Duration::from_millis(100));
+        let snapshot = metrics.snapshot();
+        assert_eq!((snapshot.items, snapshot.completions, snapshot.errors), (1, 0, 1));
+    }
+
+    #[test]
+    fn latency_buckets_the_gaps_between_items() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").metrics_on(scheduler.clone());
+        let metrics = source.handle();
+        assert_eq!(metrics.snapshot().latency.percentile(0.5), None);
+
+        source.subscribe(TestObserver::new());
+        scheduler.advance_by(Duration::from_millis(100));
+        let latency = metrics.snapshot().latency;
+        // The first gap is from subscribing, so nothing; the second 20ms
+        assert_eq!(latency.count(), 2);
+        assert_eq!(latency.percentile(0.5), Some(Duration::from_micros(1)));
+        assert_eq!(latency.percentile(1.0), Some(Duration::from_micros(1 << 15)));
+    }
+
+    #[test]
+    fn throughput_decays_once_items_stop() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "(abc)").metrics_on(scheduler.clone());
+        let metrics = source.handle();
+        assert_eq!(metrics.snapshot().throughput, 0.0);
+
+        source.subscribe(TestObserver::new());
+        scheduler.advance_by(Duration::ZERO);
+        assert!((metrics.snapshot().throughput - 3.0).abs() < 1e-9);
+        scheduler.advance_by(Duration::from_secs(1));
+        assert!((metrics.snapshot().throughput - 3.0 / std::f64::consts::E).abs() < 1e-9);
+    }
+}
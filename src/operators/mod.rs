@@ -0,0 +1,87 @@
+#[cfg(feature = "std")]
+pub mod auto_connect;
+#[cfg(feature = "std")]
+pub mod backpressure;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod buffer_toggle;
+pub mod catch;
+#[cfg(feature = "std")]
+pub mod catch_panics;
+#[cfg(feature = "std")]
+pub mod channelize;
+pub mod chunk_by;
+#[cfg(feature = "std")]
+pub mod concat;
+#[cfg(feature = "std")]
+pub mod connectable;
+#[cfg(feature = "std")]
+pub mod debounce;
+#[cfg(feature = "std")]
+pub mod delay;
+#[cfg(feature = "std")]
+pub mod delay_subscription;
+pub mod enumerate;
+pub mod filter;
+pub mod filter_map;
+pub mod finally;
+#[cfg(feature = "std")]
+pub mod flat_map_flow;
+#[cfg(feature = "std")]
+pub mod group_by;
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub mod instrument;
+#[cfg(feature = "std")]
+pub mod latest_only;
+pub mod lift;
+pub mod map;
+pub mod map_error;
+pub mod materialize;
+pub mod merge;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod multicast;
+#[cfg(feature = "std")]
+pub mod observe_on;
+pub mod on_error_resume_next;
+pub mod on_error_return;
+#[cfg(feature = "rayon")]
+pub mod par_map;
+#[cfg(feature = "std")]
+pub mod parallel;
+#[cfg(feature = "std")]
+pub mod prefetch;
+#[cfg(feature = "std")]
+pub mod rate_limit;
+#[cfg(feature = "std")]
+pub mod ref_count;
+#[cfg(feature = "std")]
+pub mod reorder;
+#[cfg(feature = "std")]
+pub mod repeat;
+pub mod result;
+#[cfg(feature = "std")]
+pub mod retry;
+#[cfg(feature = "std")]
+pub mod retry_when;
+#[cfg(feature = "std")]
+pub mod sample;
+#[cfg(feature = "std")]
+pub mod scan_shared;
+#[cfg(feature = "std")]
+pub mod subscribe_on;
+pub mod take;
+#[cfg(feature = "std")]
+pub mod take_until;
+pub mod tap;
+#[cfg(feature = "std")]
+pub mod throttle;
+#[cfg(feature = "std")]
+pub mod time_interval;
+#[cfg(feature = "std")]
+pub mod timeout;
+#[cfg(feature = "std")]
+pub mod timestamp;
+pub mod zip_iter;
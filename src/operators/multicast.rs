@@ -0,0 +1,73 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use crate::describe::{Node, short_name};
+use crate::observable::{Observable, Subscription};
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::connectable::ConnectObserver;
+
+pub struct MulticastObservable<S, F, G> {
+    pub(crate) factory: F,
+    pub(crate) selector: G,
+    pub(crate) source: S
+}
+
+impl<S, F, G, J, U> Observable for MulticastObservable<S, F, G>
+    where S: Observable,
+          F: Fn() -> J,
+          G: Fn(J) -> U,
+          J: Observer<Item=S::Item> + Clone + Send + Sync + 'static,
+          U: Observable {
+    type Item = U::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let subject = (self.factory)();
+        let connection = Subscription::new();
+        (self.selector)(subject.clone()).subscribe(ScopedObserver {connection: connection.clone(), observer: observer});
+        self.source.subscribe(ConnectObserver {
+            subject: subject,
+            connection: connection,
+            terminated: Arc::new(AtomicBool::new(false))
+        });
+    }
+}
+
+// Disconnects the shared source once the selected pipeline is done with it
+pub(crate) struct ScopedObserver<N> {
+    pub(crate) connection: Subscription,
+    pub(crate) observer: N
+}
+
+impl<N> Observer for ScopedObserver<N>
+    where N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        match self.observer.next(val) {
+            Continue => Continue,
+            Stop => {
+                self.connection.unsubscribe();
+                Stop
+            }
+        }
+    }
+
+    #[inline]
+    fn completed(&mut self) {
+        self.connection.unsubscribe();
+        self.observer.completed();
+    }
+
+    #[inline]
+    fn error(&mut self, err: Error) {
+        self.connection.unsubscribe();
+        self.observer.error(err);
+    }
+}
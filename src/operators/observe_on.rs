@@ -0,0 +1,182 @@
+use std::sync::{Arc, Mutex};
+use std::collections::VecDeque;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::operators::materialize::Notification;
+use crate::sync::MutexExt;
+
+pub struct ObserveOnObservable<S, D> {
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, D> Observable for ObserveOnObservable<S, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(ObserveOnObserver {
+            scheduler: self.scheduler.clone(),
+            state: Arc::new(Mutex::new(ObserveOnState {queue: VecDeque::new(), draining: false, stopped: false})),
+            observer: Arc::new(Mutex::new(observer))
+        });
+    }
+}
+
+// Only one drain is ever scheduled at a time, so the downstream sees items in
+// order and never from two threads at once. The queue has its own lock so the
+// source can keep pushing while a drain is delivering.
+pub(crate) struct ObserveOnState<A> {
+    pub(crate) queue: VecDeque<Notification<A, Error>>,
+    pub(crate) draining: bool,
+    pub(crate) stopped: bool
+}
+
+pub(crate) struct ObserveOnObserver<A, N, D> {
+    pub(crate) scheduler: D,
+    pub(crate) state: Arc<Mutex<ObserveOnState<A>>>,
+    pub(crate) observer: Arc<Mutex<N>>
+}
+
+impl<A, N, D> ObserveOnObserver<A, N, D>
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler {
+    fn push(&mut self, notification: Notification<A, Error>) -> IterationResult {
+        {
+            let mut state = self.state.acquire();
+            if state.stopped {
+                return Stop;
+            }
+            state.queue.push_back(notification);
+            if state.draining {
+                return Continue;
+            }
+            state.draining = true;
+        }
+
+        let state = self.state.clone();
+        let observer = self.observer.clone();
+        self.scheduler.schedule(Box::new(move || drain_observed(state, observer)));
+        Continue
+    }
+}
+
+pub(crate) fn drain_observed<A, N>(state: Arc<Mutex<ObserveOnState<A>>>, observer: Arc<Mutex<N>>)
+    where N: Observer<Item=A> {
+    let mut observer = observer.acquire();
+
+    loop {
+        let notification = {
+            let mut state = state.acquire();
+            match state.queue.pop_front() {
+                Some(notification) => notification,
+                None => {
+                    state.draining = false;
+                    return;
+                }
+            }
+        };
+
+        match notification {
+            Notification::Next(val) => {
+                if let Stop = observer.next(val) {
+                    let mut state = state.acquire();
+                    state.stopped = true;
+                    state.queue.clear();
+                    drop(state);
+                    return observer.completed();
+                }
+            },
+            Notification::Completed => return observer.completed(),
+            Notification::Error(err) => return observer.error(err)
+        }
+    }
+}
+
+impl<A, N, D> Observer for ObserveOnObserver<A, N, D>
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.push(Notification::Next(val))
+    }
+
+    fn completed(&mut self) {
+        self.push(Notification::Completed);
+        self.state.acquire().stopped = true;
+    }
+
+    fn error(&mut self, err: Error) {
+        self.push(Notification::Error(err));
+        self.state.acquire().stopped = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::schedulers::thread_pool::ThreadPoolScheduler;
+    use crate::sources::range;
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn observe_on_delivers_on_the_scheduler() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        range(0, 3).observe_on(scheduler.clone()).subscribe(observer.clone());
+        observer.assert_values(&[]);
+
+        scheduler.trigger_actions();
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn observe_on_keeps_order_on_a_thread_pool() {
+        let scheduler = ThreadPoolScheduler::new(4);
+        for _ in 0..20 {
+            let observer = TestObserver::new();
+            let threads = Arc::new(Mutex::new(Vec::new()));
+            let seen = threads.clone();
+            range(0, 1000)
+                .observe_on(scheduler.clone())
+                .tap(move |_: &i32| seen.lock().unwrap().push(thread::current().id()))
+                .subscribe(observer.clone());
+            assert!(observer.await_terminal_event(Duration::from_secs(5)));
+            observer.assert_values(&(0..1000).collect::<Vec<i32>>());
+            observer.assert_completed();
+            assert!(!threads.lock().unwrap().contains(&thread::current().id()));
+        }
+        scheduler.shutdown();
+    }
+
+    #[test]
+    fn observe_on_stops_the_source_once_the_downstream_stops() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        range(0, 10).observe_on(scheduler.clone()).take(2).subscribe(observer.clone());
+        scheduler.trigger_actions();
+        observer.assert_values(&[0, 1]);
+        observer.assert_completed();
+    }
+}
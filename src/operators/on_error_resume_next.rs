@@ -0,0 +1,99 @@
+use alloc::sync::Arc;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+
+pub struct OnErrorResumeNextObservable<S, U> {
+    pub(crate) next: Arc<U>,
+    pub(crate) source: S
+}
+
+impl<S, U> Observable for OnErrorResumeNextObservable<S, U>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          U: Observable<Item=S::Item> + Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node(), self.next.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(ResumeNextObserver {next: self.next.clone(), observer: Some(observer)});
+    }
+}
+
+pub(crate) struct ResumeNextObserver<U, N> {
+    pub(crate) next: Arc<U>,
+    pub(crate) observer: Option<N>
+}
+
+impl<U, N> Observer for ResumeNextObserver<U, N>
+    where U: Observable<Item=N::Item>,
+          N: Observer + Send + Sync + 'static {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        match self.observer {
+            Some(ref mut observer) => observer.next(val),
+            None => Stop
+        }
+    }
+
+    fn completed(&mut self) {
+        if let Some(ref mut observer) = self.observer {
+            observer.completed();
+        }
+    }
+
+    fn error(&mut self, _: Error) {
+        if let Some(observer) = self.observer.take() {
+            self.next.subscribe(observer);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::observable::Observable;
+    use crate::sources::{range, throw};
+    use crate::testing::marbles::{assert_marbles, cold, MarbleError};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn on_error_resume_next_carries_on_with_the_next_source() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-#").on_error_resume_next(cold(&scheduler, "x-y|"));
+        assert_marbles(&scheduler, source, "a-x-y|");
+    }
+
+    #[test]
+    fn on_error_resume_next_leaves_completion_alone() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a|").on_error_resume_next(cold(&scheduler, "x|"));
+        assert_marbles(&scheduler, source, "a|");
+    }
+
+    #[test]
+    fn on_error_resume_next_ends_with_the_next_sources_error() {
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(MarbleError)).on_error_resume_next(throw(Arc::new(MarbleError))).subscribe(observer.clone());
+        observer.assert_error();
+        assert_eq!(observer.errors().len(), 1);
+    }
+
+    #[test]
+    fn on_error_resume_next_stops_the_next_source_with_the_downstream() {
+        let observer = TestObserver::new();
+        throw(Arc::new(MarbleError)).on_error_resume_next(range(0, i32::MAX)).take(2).subscribe(observer.clone());
+        observer.assert_values(&[0, 1]);
+        observer.assert_completed();
+    }
+}
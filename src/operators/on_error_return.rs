@@ -0,0 +1,89 @@
+use alloc::sync::Arc;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+
+pub struct OnErrorReturnObservable<S, F> {
+    pub(crate) f: Arc<F>,
+    pub(crate) source: S
+}
+
+impl<S, F> Observable for OnErrorReturnObservable<S, F>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          F: Fn(Error) -> S::Item + Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(OnErrorReturnObserver {f: self.f.clone(), observer: observer});
+    }
+}
+
+pub(crate) struct OnErrorReturnObserver<F, N> {
+    pub(crate) f: Arc<F>,
+    pub(crate) observer: N
+}
+
+impl<F, N> Observer for OnErrorReturnObserver<F, N>
+    where F: Fn(Error) -> N::Item,
+          N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.observer.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.next((self.f)(err));
+        self.observer.completed();
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::subjects::Subject;
+    use crate::testing::marbles::{assert_marbles, cold, MarbleError};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn on_error_return_ends_with_the_fallback_value() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-#").on_error_return(|_| 'z');
+        assert_marbles(&scheduler, source, "a-(z|)");
+    }
+
+    #[test]
+    fn on_error_return_leaves_completion_alone() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").on_error_return(|_| 'z');
+        assert_marbles(&scheduler, source, "a-b|");
+    }
+
+    #[test]
+    fn on_error_return_completes_once_when_the_downstream_stops_on_the_fallback() {
+        let subject = Subject::new();
+        let observer = TestObserver::new();
+        subject.clone().on_error_return(|_| 0).take(2).subscribe(observer.clone());
+        subject.clone().next(1);
+        subject.clone().error(Arc::new(MarbleError));
+        observer.assert_values(&[1, 0]);
+        assert_eq!(observer.completion_count(), 1);
+        observer.assert_no_errors();
+    }
+}
@@ -0,0 +1,310 @@
+use std::sync::{Arc, Mutex};
+use std::collections::{BTreeMap, VecDeque};
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::catch_panics::PanicError;
+use crate::sync::MutexExt;
+
+#[derive(Clone, Copy)]
+pub enum ParOrder {
+    // Results come out in the order their items went in, each held back
+    // until those before it are done
+    Ordered,
+    // Results come out as soon as they're done
+    Unordered
+}
+
+// Runs f for each item as a job on rayon's global pool. Items aren't held
+// back while earlier ones are mapped, so the source runs ahead of f and every
+// item it's produced is queued as a job. An error is passed on without
+// waiting for the jobs in flight, whose results are then dropped.
+pub struct ParMapObservable<S, F> {
+    pub(crate) f: Arc<F>,
+    pub(crate) order: ParOrder,
+    pub(crate) source: S
+}
+
+impl<S, F, B> Observable for ParMapObservable<S, F>
+    where S: Observable,
+          S::Item: Send + 'static,
+          F: Fn(S::Item) -> B + Send + Sync + 'static,
+          B: Send + 'static {
+    type Item = B;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let shared = Arc::new(ParMapShared {
+            state: Mutex::new(ParMapState {
+                ordered: BTreeMap::new(),
+                unordered: VecDeque::new(),
+                issued: 0,
+                emitted: 0,
+                in_flight: 0,
+                completed: false,
+                error: None,
+                stopped: false,
+                draining: false,
+                missed: false
+            }),
+            order: self.order,
+            observer: Mutex::new(Some(observer))
+        });
+
+        self.source.subscribe(ParMapObserver {f: self.f.clone(), shared: shared, _marker: PhantomData});
+    }
+}
+
+pub(crate) struct ParMapState<B> {
+    pub(crate) ordered: BTreeMap<u64, B>,
+    pub(crate) unordered: VecDeque<B>,
+    pub(crate) issued: u64,
+    pub(crate) emitted: u64,
+    pub(crate) in_flight: usize,
+    pub(crate) completed: bool,
+    pub(crate) error: Option<Error>,
+    pub(crate) stopped: bool,
+    pub(crate) draining: bool,
+    pub(crate) missed: bool
+}
+
+pub(crate) enum ParMapStep<B> {
+    Next(B),
+    Error(Error),
+    Completed,
+    Idle
+}
+
+pub(crate) struct ParMapShared<N, B> {
+    pub(crate) state: Mutex<ParMapState<B>>,
+    pub(crate) order: ParOrder,
+    // Only ever locked by whoever is draining
+    pub(crate) observer: Mutex<Option<N>>
+}
+
+impl<N, B> ParMapShared<N, B>
+    where N: Observer<Item=B> {
+    fn finish(&self, seq: u64, val: B) {
+        {
+            let mut state = self.state.acquire();
+            state.in_flight -= 1;
+            if state.stopped {
+                return;
+            }
+            match self.order {
+                ParOrder::Ordered => { state.ordered.insert(seq, val); },
+                ParOrder::Unordered => state.unordered.push_back(val)
+            }
+        }
+        self.drain();
+    }
+
+    // A job whose f panicked fails the stream, as an error from the source
+    // would
+    fn fail(&self, err: Error) {
+        {
+            let mut state = self.state.acquire();
+            state.in_flight -= 1;
+            if state.stopped {
+                return;
+            }
+            if state.error.is_none() {
+                state.error = Some(err);
+            }
+        }
+        self.drain();
+    }
+
+    fn step(&self, state: &mut ParMapState<B>) -> ParMapStep<B> {
+        if state.stopped {
+            return ParMapStep::Idle;
+        }
+        if let Some(err) = state.error.take() {
+            state.stopped = true;
+            return ParMapStep::Error(err);
+        }
+
+        let ready = match self.order {
+            ParOrder::Ordered => {
+                let seq = state.emitted;
+                state.ordered.remove(&seq)
+            },
+            ParOrder::Unordered => state.unordered.pop_front()
+        };
+        match ready {
+            Some(val) => {
+                state.emitted += 1;
+                ParMapStep::Next(val)
+            },
+            None if state.completed && state.in_flight == 0 => {
+                state.stopped = true;
+                ParMapStep::Completed
+            },
+            None => ParMapStep::Idle
+        }
+    }
+
+    // Whoever finds the drain idle takes it over and delivers on behalf of
+    // everyone else, so jobs finishing on different threads never call
+    // downstream at the same time
+    fn drain(&self) {
+        {
+            let mut state = self.state.acquire();
+            if state.draining {
+                state.missed = true;
+                return;
+            }
+            state.draining = true;
+        }
+
+        loop {
+            let step = {
+                let mut state = self.state.acquire();
+                match self.step(&mut state) {
+                    ParMapStep::Idle if state.missed => {
+                        state.missed = false;
+                        continue;
+                    },
+                    ParMapStep::Idle => {
+                        state.draining = false;
+                        return;
+                    },
+                    step => step
+                }
+            };
+
+            let mut observer = self.observer.acquire();
+            match step {
+                ParMapStep::Next(val) => {
+                    let stop = match *observer {
+                        Some(ref mut observer) => matches!(observer.next(val), Stop),
+                        None => true
+                    };
+                    if stop {
+                        let mut state = self.state.acquire();
+                        state.stopped = true;
+                        state.ordered.clear();
+                        state.unordered.clear();
+                        observer.take();
+                    }
+                },
+                ParMapStep::Error(err) => {
+                    if let Some(mut observer) = observer.take() {
+                        observer.error(err);
+                    }
+                },
+                ParMapStep::Completed => {
+                    if let Some(mut observer) = observer.take() {
+                        observer.completed();
+                    }
+                },
+                ParMapStep::Idle => {}
+            }
+        }
+    }
+}
+
+pub(crate) struct ParMapObserver<F, N, A, B> {
+    pub(crate) f: Arc<F>,
+    pub(crate) shared: Arc<ParMapShared<N, B>>,
+    pub(crate) _marker: PhantomData<fn(A)>
+}
+
+impl<A, B, F, N> Observer for ParMapObserver<F, N, A, B>
+    where A: Send + 'static,
+          B: Send + 'static,
+          F: Fn(A) -> B + Send + Sync + 'static,
+          N: Observer<Item=B> + Send + 'static {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let seq = {
+            let mut state = self.shared.state.acquire();
+            if state.stopped {
+                return Stop;
+            }
+            let seq = state.issued;
+            state.issued += 1;
+            state.in_flight += 1;
+            seq
+        };
+
+        let f = self.f.clone();
+        let shared = self.shared.clone();
+        // A panic left to unwind out of a rayon job aborts the process
+        rayon::spawn(move || match panic::catch_unwind(AssertUnwindSafe(|| f(val))) {
+            Ok(mapped) => shared.finish(seq, mapped),
+            Err(payload) => shared.fail(Arc::new(PanicError::from_payload(payload)))
+        });
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.shared.state.acquire().completed = true;
+        self.shared.drain();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.shared.state.acquire().error = Some(err);
+        self.shared.drain();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::operators::catch_panics::PanicError;
+    use crate::operators::par_map::ParOrder;
+    use crate::sources::range;
+    use crate::testing::observer::TestObserver;
+
+    #[test]
+    fn ordered_par_map_keeps_source_order() {
+        let observer = TestObserver::new();
+        range(0, 500).par_map(|x| x * 2).subscribe(observer.clone());
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_values(&(0..500).map(|x| x * 2).collect::<Vec<i32>>());
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn unordered_par_map_delivers_everything() {
+        let observer = TestObserver::new();
+        range(0, 500).par_map_with(|x| x + 1, ParOrder::Unordered).subscribe(observer.clone());
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+
+        let mut values = observer.values();
+        values.sort();
+        assert_eq!(values, (1..501).collect::<Vec<i32>>());
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn a_panic_in_f_fails_the_stream() {
+        let observer = TestObserver::new();
+        range(0, 100)
+            .par_map(|x| {
+                if x == 50 {
+                    panic!("bad item");
+                }
+                x
+            })
+            .subscribe(observer.clone());
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_error();
+
+        let errors = observer.errors();
+        assert_eq!(errors[0].downcast_ref::<PanicError>(), Some(&PanicError("bad item".to_string())));
+        assert!(observer.values().iter().all(|&x| x != 50));
+    }
+}
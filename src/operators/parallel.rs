@@ -0,0 +1,239 @@
+use std::sync::{Arc, Mutex};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{BoxObserver, Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::merge::{MergeShared, SharedObserver};
+use crate::sync::MutexExt;
+
+// A source split into rails, e.g.
+// source.parallel(4).rails(|rail| rail.observe_on(pool.clone()).map(f)).sequential().
+// Items are dealt out to the rails in turn, and each rail is an ordinary
+// observable to build on; moving a rail onto a scheduler is what makes the
+// rails run concurrently. The source isn't subscribed until every rail has
+// been, so a rail that is never subscribed holds the others up.
+pub struct Parallel<S> {
+    pub(crate) rails: usize,
+    pub(crate) source: Arc<S>
+}
+
+impl<S> Parallel<S>
+    where S: Observable {
+    #[inline]
+    pub fn rails<U, F>(self, f: F) -> ParallelRails<S, F>
+        where F: Fn(Rail<S>) -> U {
+        ParallelRails {rails: self.rails, f: f, source: self.source}
+    }
+
+    #[inline]
+    pub fn sequential(self) -> SequentialObservable<S, fn(Rail<S>) -> Rail<S>> {
+        self.rails(identity as fn(Rail<S>) -> Rail<S>).sequential()
+    }
+}
+
+fn identity<A>(val: A) -> A {
+    val
+}
+
+pub struct ParallelRails<S, F> {
+    pub(crate) rails: usize,
+    pub(crate) f: F,
+    pub(crate) source: Arc<S>
+}
+
+impl<S, F> ParallelRails<S, F> {
+    // Merges the rails back into one stream, in whatever order their items
+    // turn up
+    #[inline]
+    pub fn sequential(self) -> SequentialObservable<S, F> {
+        SequentialObservable {rails: self.rails, f: self.f, source: self.source}
+    }
+}
+
+pub struct SequentialObservable<S, F> {
+    pub(crate) rails: usize,
+    pub(crate) f: F,
+    pub(crate) source: Arc<S>
+}
+
+impl<S, F, U> Observable for SequentialObservable<S, F>
+    where S: Observable + Send + Sync + 'static,
+          S::Item: Send + Sync + 'static,
+          F: Fn(Rail<S>) -> U,
+          U: Observable,
+          U::Item: Send + Sync + 'static {
+    type Item = U::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let gate = Arc::new(RailGate {
+            slots: Mutex::new(RailSlots {observers: (0..self.rails).map(|_| None).collect(), remaining: self.rails}),
+            source: self.source.clone()
+        });
+        let shared = MergeShared::new(observer, self.rails);
+
+        for index in 0..self.rails {
+            let rail = (self.f)(Rail {index: index, gate: gate.clone()});
+            rail.subscribe(SharedObserver {shared: shared.clone()});
+        }
+    }
+}
+
+pub(crate) struct RailSlots<A> {
+    pub(crate) observers: Vec<Option<BoxObserver<A>>>,
+    pub(crate) remaining: usize
+}
+
+pub(crate) struct RailGate<S>
+    where S: Observable {
+    pub(crate) slots: Mutex<RailSlots<S::Item>>,
+    pub(crate) source: Arc<S>
+}
+
+// One of the rails of a Parallel. Each can only be subscribed once; a second
+// subscriber just sees it complete.
+pub struct Rail<S>
+    where S: Observable {
+    pub(crate) index: usize,
+    pub(crate) gate: Arc<RailGate<S>>
+}
+
+impl<S> Observable for Rail<S>
+    where S: Observable,
+          S::Item: 'static {
+    type Item = S::Item;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let ready = {
+            let mut slots = self.gate.slots.acquire();
+            if slots.observers[self.index].is_some() || slots.remaining == 0 {
+                drop(slots);
+                return observer.completed();
+            }
+
+            slots.observers[self.index] = Some(Box::new(observer));
+            slots.remaining -= 1;
+            if slots.remaining == 0 {
+                Some(::std::mem::take(&mut slots.observers))
+            } else {
+                None
+            }
+        };
+
+        if let Some(observers) = ready {
+            self.gate.source.subscribe(SplitObserver {rails: observers, next: 0});
+        }
+    }
+}
+
+// The source is serial, so the rails' observers can be owned outright here
+// and handed items without any locking
+pub(crate) struct SplitObserver<A> {
+    pub(crate) rails: Vec<Option<BoxObserver<A>>>,
+    pub(crate) next: usize
+}
+
+impl<A> Observer for SplitObserver<A> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        // Rails that have stopped are skipped over
+        let count = self.rails.len();
+        for offset in 0..count {
+            let index = (self.next + offset) % count;
+            if let Some(ref mut rail) = self.rails[index] {
+                if let Stop = rail.next(val) {
+                    self.rails[index] = None;
+                }
+                self.next = (index + 1) % count;
+                return if self.rails.iter().any(Option::is_some) { Continue } else { Stop };
+            }
+        }
+        Stop
+    }
+
+    fn completed(&mut self) {
+        for mut rail in self.rails.drain(..).flatten() {
+            rail.completed();
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        for mut rail in self.rails.drain(..).flatten() {
+            rail.error(err.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::schedulers::thread_pool::ThreadPoolScheduler;
+    use crate::sources::range;
+    use crate::testing::observer::TestObserver;
+
+    #[test]
+    fn parallel_deals_items_out_to_the_rails_in_turn() {
+        let observer = TestObserver::new();
+        range(0, 6)
+            .parallel(3)
+            .rails(|rail| {
+                let index = rail.index;
+                rail.map(move |val| (index, val))
+            })
+            .sequential()
+            .subscribe(observer.clone());
+        observer.assert_values(&[(0, 0), (1, 1), (2, 2), (0, 3), (1, 4), (2, 5)]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn parallel_skips_rails_that_have_stopped() {
+        let observer = TestObserver::new();
+        range(0, 5)
+            .parallel(2)
+            .rails(|rail| {
+                let index = rail.index;
+                rail.take(if index == 0 { 1 } else { usize::MAX }).map(move |val| (index, val))
+            })
+            .sequential()
+            .subscribe(observer.clone());
+        observer.assert_values(&[(0, 0), (1, 1), (1, 2), (1, 3), (1, 4)]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn sequential_merges_rails_run_on_a_thread_pool() {
+        let scheduler = ThreadPoolScheduler::new(4);
+        let observer = TestObserver::new();
+        let pool = scheduler.clone();
+        range(0, 200)
+            .parallel(4)
+            .rails(move |rail| rail.observe_on(pool.clone()).map(|val| val * 2))
+            .sequential()
+            .subscribe(observer.clone());
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+
+        let mut values = observer.values();
+        values.sort();
+        assert_eq!(values, (0..200).map(|val| val * 2).collect::<Vec<i32>>());
+        assert_eq!(observer.completion_count(), 1);
+        scheduler.shutdown();
+    }
+
+    #[test]
+    fn sequential_without_rails_hands_everything_on() {
+        let observer = TestObserver::new();
+        range(0, 4).parallel(2).sequential().subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2, 3]);
+        observer.assert_completed();
+    }
+}
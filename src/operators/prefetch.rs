@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use crate::describe::{Node, short_name};
+use crate::blocking::receive_blocking;
+use crate::observable::Observable;
+use crate::observer::Observer;
+use crate::observer::IterationResult::Stop;
+use crate::schedulers::Scheduler;
+
+// Runs the source on the scheduler, reading up to count items ahead into a
+// bounded channel, while the subscribing thread hands them downstream at its
+// own pace. The source is held up once the channel is full, so the scheduler
+// should be one that tolerates blocking. Subscribing blocks until the stream
+// ends. On a scheduler that runs the source in place, there's no other thread
+// to read ahead on, so the whole stream is read before any of it goes
+// downstream.
+pub struct PrefetchObservable<S, D> {
+    pub(crate) count: usize,
+    pub(crate) scheduler: D,
+    pub(crate) source: Arc<S>
+}
+
+impl<S, D> Observable for PrefetchObservable<S, D>
+    where S: Observable + Send + Sync + 'static,
+          S::Item: Send + 'static,
+          D: Scheduler {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let source = self.source.clone();
+        let receiver = receive_blocking(&self.scheduler, self.count, move |observer| source.subscribe(observer));
+
+        // Dropping the receiver on Stop makes the source's next send fail,
+        // which stops it in turn
+        loop {
+            match receiver.recv() {
+                Ok(Ok(val)) => {
+                    if let Stop = observer.next(val) {
+                        return observer.completed();
+                    }
+                },
+                Ok(Err(err)) => return observer.error(err),
+                Err(_) => return observer.completed()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::observable::Observable;
+    use crate::schedulers::current_thread::CurrentThreadScheduler;
+    use crate::sources::{range, throw};
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::TestObserver;
+
+    #[test]
+    fn prefetch_hands_on_everything() {
+        let observer = TestObserver::new();
+        range(0, 100).prefetch(4).subscribe(observer.clone());
+        observer.assert_values(&(0..100).collect::<Vec<i32>>());
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn prefetch_stops_the_source_once_the_downstream_stops() {
+        let observer = TestObserver::new();
+        range(0, i32::MAX).prefetch(4).take(3).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn prefetch_passes_errors_on() {
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(MarbleError)).prefetch(4).subscribe(observer.clone());
+        observer.assert_error();
+    }
+
+    #[test]
+    fn prefetch_runs_on_a_scheduler_that_runs_the_source_in_place() {
+        let observer = TestObserver::new();
+        range(0, 10).prefetch_on(2, CurrentThreadScheduler).subscribe(observer.clone());
+        observer.assert_values(&(0..10).collect::<Vec<i32>>());
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::sync::MutexExt;
+
+// What happens to an item that arrives with no permit left
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RateLimitMode {
+    // Queue it until one frees up. The queue isn't bounded, so a source that's
+    // faster for good needs something like on_backpressure_buffer upstream.
+    Delay,
+    Drop
+}
+
+// A token bucket: permits come back steadily at permits per interval, and up
+// to burst of them can be saved up while it's quiet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    pub(crate) permits: usize,
+    pub(crate) interval: Duration,
+    pub(crate) burst: usize,
+    pub(crate) mode: RateLimitMode
+}
+
+impl RateLimit {
+    // Starts with a full bucket as big as one interval's worth, delaying
+    pub fn new(permits: usize, interval: Duration) -> RateLimit {
+        RateLimit {permits: permits.max(1), interval: interval, burst: permits.max(1), mode: RateLimitMode::Delay}
+    }
+
+    pub fn burst(mut self, burst: usize) -> RateLimit {
+        self.burst = burst.max(1);
+        self
+    }
+
+    pub fn mode(mut self, mode: RateLimitMode) -> RateLimit {
+        self.mode = mode;
+        self
+    }
+
+    // Permits per second
+    fn rate(&self) -> f64 {
+        self.permits as f64 / self.interval.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+pub struct RateLimitObservable<S, D> {
+    pub(crate) limit: RateLimit,
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, D> Observable for RateLimitObservable<S, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(RateLimitObserver {
+            scheduler: self.scheduler.clone(),
+            state: Arc::new(Mutex::new(RateLimitState {
+                observer: observer,
+                limit: self.limit,
+                tokens: self.limit.burst as f64,
+                last: self.scheduler.now(),
+                queue: VecDeque::new(),
+                draining: false,
+                completed: false,
+                stopped: false
+            }))
+        });
+    }
+}
+
+pub(crate) struct RateLimitState<A, N> {
+    pub(crate) observer: N,
+    pub(crate) limit: RateLimit,
+    pub(crate) tokens: f64,
+    pub(crate) last: Instant,
+    pub(crate) queue: VecDeque<A>,
+    pub(crate) draining: bool,
+    // Completion waits on the queue
+    pub(crate) completed: bool,
+    pub(crate) stopped: bool
+}
+
+impl<A, N> RateLimitState<A, N>
+    where N: Observer<Item=A> {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.rate()).min(self.limit.burst as f64);
+        self.last = now;
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn emit(&mut self, val: A) {
+        if !self.stopped {
+            if let Stop = self.observer.next(val) {
+                self.stopped = true;
+                self.queue.clear();
+            }
+        }
+    }
+
+    // How long until the next permit comes back
+    fn wait(&self) -> Duration {
+        Duration::from_secs_f64((1.0 - self.tokens).max(0.0) / self.limit.rate())
+    }
+}
+
+// Releases what the permits allow and comes back for the rest. The guard is
+// let go before the timer is armed, as a scheduler that runs it in place
+// would otherwise find the lock still held.
+pub(crate) fn drain_rate_limit<A, N, D>(shared: &Arc<Mutex<RateLimitState<A, N>>>, scheduler: &D, mut state: MutexGuard<'_, RateLimitState<A, N>>)
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler + Clone + 'static {
+    state.draining = true;
+    let wait = state.wait();
+    drop(state);
+
+    let shared = shared.clone();
+    let next_scheduler = scheduler.clone();
+    scheduler.schedule_after(wait, Box::new(move || {
+        let mut state = shared.acquire();
+        let now = next_scheduler.now();
+        state.refill(now);
+        while !state.stopped && !state.queue.is_empty() && state.try_acquire() {
+            if let Some(val) = state.queue.pop_front() {
+                state.emit(val);
+            }
+        }
+
+        if state.stopped {
+            state.draining = false;
+        } else if !state.queue.is_empty() {
+            drain_rate_limit(&shared, &next_scheduler, state);
+        } else {
+            state.draining = false;
+            if state.completed {
+                state.stopped = true;
+                state.observer.completed();
+            }
+        }
+    }));
+}
+
+pub(crate) struct RateLimitObserver<A, N, D> {
+    pub(crate) scheduler: D,
+    pub(crate) state: Arc<Mutex<RateLimitState<A, N>>>
+}
+
+impl<A, N, D> Observer for RateLimitObserver<A, N, D>
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut state = self.state.acquire();
+        if state.stopped {
+            return Stop;
+        }
+
+        let now = self.scheduler.now();
+        state.refill(now);
+        if state.queue.is_empty() && state.try_acquire() {
+            state.emit(val);
+        } else if state.limit.mode == RateLimitMode::Delay {
+            state.queue.push_back(val);
+            if !state.draining {
+                drain_rate_limit(&self.state, &self.scheduler, state);
+                return Continue;
+            }
+        }
+
+        if state.stopped {
+            Stop
+        } else {
+            Continue
+        }
+    }
+
+    fn completed(&mut self) {
+        let mut state = self.state.acquire();
+        if state.stopped {
+            return;
+        }
+
+        if state.queue.is_empty() {
+            state.stopped = true;
+            state.observer.completed();
+        } else {
+            state.completed = true;
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        let mut state = self.state.acquire();
+        if !state.stopped {
+            state.stopped = true;
+            state.queue.clear();
+            state.observer.error(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::operators::rate_limit::{RateLimit, RateLimitMode};
+    use crate::schedulers::current_thread::CurrentThreadScheduler;
+    use crate::sources::range;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn rate_limit_spends_the_burst_then_paces() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        range(0, 5).rate_limit_with_on(RateLimit::new(2, Duration::from_millis(20)), scheduler.clone()).subscribe(observer.clone());
+        observer.assert_values(&[0, 1]);
+        observer.assert_not_completed();
+
+        scheduler.advance_by(Duration::from_millis(10));
+        observer.assert_values(&[0, 1, 2]);
+        scheduler.advance_by(Duration::from_millis(20));
+        observer.assert_values(&[0, 1, 2, 3, 4]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn rate_limit_can_drop_instead() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        let limit = RateLimit::new(2, Duration::from_millis(20)).mode(RateLimitMode::Drop);
+        range(0, 5).rate_limit_with_on(limit, scheduler.clone()).subscribe(observer.clone());
+        observer.assert_values(&[0, 1]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn rate_limit_saves_up_permits_while_quiet() {
+        let scheduler = TestScheduler::new();
+        let limit = RateLimit::new(1, Duration::from_millis(20)).burst(2);
+        let source = cold(&scheduler, "a-----(bcd)|").rate_limit_with_on(limit, scheduler.clone());
+        assert_marbles(&scheduler, source, "a-----(bc)-(d|)");
+    }
+
+    #[test]
+    fn rate_limit_drops_the_queue_on_an_error() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "abc#").rate_limit_with_on(RateLimit::new(1, Duration::from_millis(100)), scheduler.clone());
+        assert_marbles(&scheduler, source, "a--#------------");
+    }
+
+    // The wait for each permit is slept out in place, so nothing is queued
+    // for long
+    #[test]
+    fn rate_limit_runs_on_a_scheduler_that_runs_timers_in_place() {
+        let observer = TestObserver::new();
+        range(0, 3).rate_limit_with_on(RateLimit::new(1, Duration::from_millis(5)), CurrentThreadScheduler).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,195 @@
+use std::sync::{Arc, Mutex};
+
+use crate::describe::{Node, short_name};
+use crate::observable::{Observable, Subscription};
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::connectable::ConnectableObservable;
+use crate::sync::MutexExt;
+
+pub(crate) struct RefCountState {
+    pub(crate) count: usize,
+    pub(crate) connection: Option<Subscription>
+}
+
+// Connects when the first subscriber arrives and disconnects once the last
+// one stops or terminates. The subscriber after that starts over with a fresh
+// subject, so nothing buffered for the last connection is replayed.
+pub struct RefCountObservable<S, F, J> {
+    pub(crate) connectable: Arc<ConnectableObservable<S, F, J>>,
+    pub(crate) state: Arc<Mutex<RefCountState>>
+}
+
+impl<S, F, J> Clone for RefCountObservable<S, F, J> {
+    fn clone(&self) -> RefCountObservable<S, F, J> {
+        RefCountObservable {connectable: self.connectable.clone(), state: self.state.clone()}
+    }
+}
+
+impl<S, F, J> Observable for RefCountObservable<S, F, J>
+    where S: Observable,
+          F: Fn() -> J,
+          J: Observer<Item=S::Item> + Observable<Item=S::Item> + Clone + Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.connectable.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let connection = {
+            let mut state = self.state.acquire();
+            state.count += 1;
+            if state.count == 1 {
+                let connection = Subscription::new();
+                state.connection = Some(connection.clone());
+                Some(connection)
+            } else {
+                None
+            }
+        };
+
+        if connection.is_some() {
+            self.connectable.renew();
+        }
+        self.connectable.subscribe(RefCountObserver {state: self.state.clone(), released: false, observer: observer});
+        if let Some(connection) = connection {
+            self.connectable.connect_with(connection);
+        }
+    }
+}
+
+pub(crate) struct RefCountObserver<N> {
+    pub(crate) state: Arc<Mutex<RefCountState>>,
+    pub(crate) released: bool,
+    pub(crate) observer: N
+}
+
+impl<N> RefCountObserver<N> {
+    fn release(&mut self) {
+        if self.released {
+            return;
+        }
+        self.released = true;
+
+        let mut state = self.state.acquire();
+        state.count -= 1;
+        if state.count == 0 {
+            if let Some(connection) = state.connection.take() {
+                connection.unsubscribe();
+            }
+        }
+    }
+}
+
+impl<N> Observer for RefCountObserver<N>
+    where N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        match self.observer.next(val) {
+            Continue => Continue,
+            Stop => {
+                self.release();
+                Stop
+            }
+        }
+    }
+
+    #[inline]
+    fn completed(&mut self) {
+        self.release();
+        self.observer.completed();
+    }
+
+    #[inline]
+    fn error(&mut self, err: Error) {
+        self.release();
+        self.observer.error(err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::sources::range;
+    use crate::subjects::Subject;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn share_multicasts_one_subscription() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b-c|").share();
+        let early = TestObserver::new();
+        source.subscribe(early.clone());
+        assert_marbles(&scheduler, source, "a-b-c|");
+        early.assert_values(&['a', 'b', 'c']);
+        early.assert_completed();
+    }
+
+    #[test]
+    fn share_disconnects_once_the_last_subscriber_stops() {
+        let subject = Subject::new();
+        let shared = subject.clone().share();
+        let first = TestObserver::new();
+        let second = TestObserver::new();
+        shared.clone().take(1).subscribe(first.clone());
+        shared.clone().take(2).subscribe(second.clone());
+
+        subject.clone().next(1);
+        assert_eq!(subject.observer_count(), 1);
+        subject.clone().next(2);
+        subject.clone().next(3);
+        first.assert_values(&[1]);
+        second.assert_values(&[1, 2]);
+        assert_eq!(subject.observer_count(), 0);
+
+        let late = TestObserver::new();
+        shared.subscribe(late.clone());
+        subject.clone().next(4);
+        late.assert_values(&[4]);
+        late.assert_not_completed();
+    }
+
+    #[test]
+    fn share_starts_over_once_the_source_terminates() {
+        let shared = range(0, 3).share();
+        let first = TestObserver::new();
+        let second = TestObserver::new();
+        shared.subscribe(first.clone());
+        shared.subscribe(second.clone());
+        first.assert_values(&[0, 1, 2]);
+        first.assert_completed();
+        second.assert_values(&[0, 1, 2]);
+        second.assert_completed();
+    }
+
+    #[test]
+    fn share_replay_replays_while_connected_and_starts_over_after() {
+        let subject = Subject::new();
+        let shared = subject.clone().share_replay(2);
+        let first = TestObserver::new();
+        shared.clone().take(3).subscribe(first.clone());
+        subject.clone().next(1);
+        subject.clone().next(2);
+
+        let second = TestObserver::new();
+        shared.clone().take(2).subscribe(second.clone());
+        second.assert_values(&[1, 2]);
+        second.assert_completed();
+        subject.clone().next(3);
+        first.assert_values(&[1, 2, 3]);
+        first.assert_completed();
+
+        let late = TestObserver::new();
+        shared.subscribe(late.clone());
+        late.assert_values(&[]);
+        subject.clone().next(4);
+        late.assert_values(&[4]);
+    }
+}
@@ -0,0 +1,275 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::sync::MutexExt;
+
+// What to do with an item whose key is behind one already emitted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LatePolicy {
+    Drop,
+    // Pass it straight through, out of order
+    Emit,
+    // Fail the stream with a LateItemError
+    Error
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LateItemError {
+    pub(crate) lateness: Duration
+}
+
+impl fmt::Display for LateItemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "item arrived out of order by more than {:?}", self.lateness)
+    }
+}
+
+impl StdError for LateItemError {}
+
+// Holds each item for the allowed lateness, then lets items out in key order.
+// One can be held back a little longer by a smaller key that turned up after
+// it, which has to go first. Equal keys keep their arrival order.
+pub struct ReorderObservable<S, F, D> {
+    pub(crate) key: Arc<F>,
+    pub(crate) lateness: Duration,
+    pub(crate) policy: LatePolicy,
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, F, K, D> Observable for ReorderObservable<S, F, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          F: Fn(&S::Item) -> K + Send + Sync + 'static,
+          K: Ord + Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(ReorderObserver {
+            key: self.key.clone(),
+            lateness: self.lateness,
+            policy: self.policy,
+            scheduler: self.scheduler.clone(),
+            state: Arc::new(Mutex::new(ReorderState {
+                observer: observer,
+                held: BinaryHeap::new(),
+                arrivals: 0,
+                emitted: None,
+                stopped: false
+            }))
+        });
+    }
+}
+
+pub(crate) struct Held<K, A> {
+    pub(crate) key: K,
+    pub(crate) arrival: u64,
+    pub(crate) due: Instant,
+    pub(crate) val: A
+}
+
+impl<K, A> PartialEq for Held<K, A>
+    where K: Ord {
+    fn eq(&self, other: &Held<K, A>) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K, A> Eq for Held<K, A>
+    where K: Ord {}
+
+impl<K, A> PartialOrd for Held<K, A>
+    where K: Ord {
+    fn partial_cmp(&self, other: &Held<K, A>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K, A> Ord for Held<K, A>
+    where K: Ord {
+    fn cmp(&self, other: &Held<K, A>) -> Ordering {
+        self.key.cmp(&other.key).then(self.arrival.cmp(&other.arrival))
+    }
+}
+
+pub(crate) struct ReorderState<K, A, N> {
+    pub(crate) observer: N,
+    pub(crate) held: BinaryHeap<Reverse<Held<K, A>>>,
+    pub(crate) arrivals: u64,
+    // The key of the last item let out
+    pub(crate) emitted: Option<K>,
+    pub(crate) stopped: bool
+}
+
+impl<K, A, N> ReorderState<K, A, N>
+    where K: Ord,
+          N: Observer<Item=A> {
+    fn emit(&mut self, val: A) {
+        if !self.stopped {
+            if let Stop = self.observer.next(val) {
+                self.stopped = true;
+                self.held.clear();
+            }
+        }
+    }
+
+    // Lets out the smallest keys for as long as they're due by the given
+    // time, or everything when the stream's ending
+    fn release(&mut self, until: Option<Instant>) {
+        while !self.stopped {
+            let ready = match self.held.peek() {
+                Some(Reverse(held)) => until.is_none_or(|until| held.due <= until),
+                None => false
+            };
+            if !ready {
+                break;
+            }
+
+            if let Some(Reverse(held)) = self.held.pop() {
+                self.emitted = Some(held.key);
+                self.emit(held.val);
+            }
+        }
+    }
+}
+
+pub(crate) struct ReorderObserver<F, K, A, N, D> {
+    pub(crate) key: Arc<F>,
+    pub(crate) lateness: Duration,
+    pub(crate) policy: LatePolicy,
+    pub(crate) scheduler: D,
+    pub(crate) state: Arc<Mutex<ReorderState<K, A, N>>>
+}
+
+impl<F, K, A, N, D> Observer for ReorderObserver<F, K, A, N, D>
+    where F: Fn(&A) -> K,
+          K: Ord + Send + 'static,
+          A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let key = (self.key)(&val);
+        let mut state = self.state.acquire();
+        if state.stopped {
+            return Stop;
+        }
+
+        if state.emitted.as_ref().is_some_and(|emitted| key < *emitted) {
+            match self.policy {
+                LatePolicy::Drop => {},
+                LatePolicy::Emit => state.emit(val),
+                LatePolicy::Error => {
+                    state.stopped = true;
+                    state.held.clear();
+                    state.observer.error(Arc::new(LateItemError {lateness: self.lateness}));
+                }
+            }
+        } else {
+            let due = self.scheduler.now() + self.lateness;
+            let arrival = state.arrivals;
+            state.arrivals += 1;
+            state.held.push(Reverse(Held {key: key, arrival: arrival, due: due, val: val}));
+
+            // Goes by the deadline rather than the clock, so a timer that
+            // fires a touch early still lets this item out. The lock is let
+            // go first, as a scheduler that runs the timer in place would
+            // otherwise find it still held.
+            drop(state);
+            let shared = self.state.clone();
+            self.scheduler.schedule_after(self.lateness, Box::new(move || {
+                shared.acquire().release(Some(due));
+            }));
+            return Continue;
+        }
+
+        if state.stopped {
+            Stop
+        } else {
+            Continue
+        }
+    }
+
+    fn completed(&mut self) {
+        let mut state = self.state.acquire();
+        if !state.stopped {
+            state.release(None);
+            state.stopped = true;
+            state.observer.completed();
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        let mut state = self.state.acquire();
+        if !state.stopped {
+            state.stopped = true;
+            state.held.clear();
+            state.observer.error(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::operators::reorder::LatePolicy;
+    use crate::schedulers::current_thread::CurrentThreadScheduler;
+    use crate::sources::from_iter;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    fn reordered(marbles: &str, lateness: u64, policy: LatePolicy, expected: &str) {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, marbles).reorder_by_with_on(|c| *c, Duration::from_millis(lateness), policy, scheduler.clone());
+        assert_marbles(&scheduler, source, expected);
+    }
+
+    #[test]
+    fn reorder_lets_items_out_in_key_order() {
+        // c is due first, but waits for a, which has the smaller key
+        reordered("ca-b---|", 25, LatePolicy::Drop, "---a-(bc)-|");
+    }
+
+    #[test]
+    fn reorder_flushes_on_completion() {
+        reordered("cab|", 100, LatePolicy::Drop, "---(abc|)");
+    }
+
+    #[test]
+    fn reorder_handles_late_items_by_policy() {
+        reordered("b----a|", 15, LatePolicy::Drop, "-b----|");
+        reordered("b----a|", 15, LatePolicy::Emit, "-b---a|");
+        reordered("b----a|", 15, LatePolicy::Error, "-b---#-");
+    }
+
+    // Each item's lateness is slept out before the next arrives, so anything
+    // with a smaller key after it counts as late
+    #[test]
+    fn reorder_runs_on_a_scheduler_that_runs_timers_in_place() {
+        let observer = TestObserver::new();
+        from_iter(vec![3, 1, 4, 2])
+            .reorder_by_with_on(|val| *val, Duration::from_millis(5), LatePolicy::Drop, CurrentThreadScheduler)
+            .subscribe(observer.clone());
+        observer.assert_values(&[3, 4]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,316 @@
+use std::sync::{Arc, Mutex};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+use crate::sync::MutexExt;
+
+// Resubscribing from inside completed() would grow the stack with every round
+// for a synchronous source, so the round is handed back to the loop that is
+// already subscribing if there is one. Asynchronous sources complete after
+// that loop has finished, and start a new one.
+pub(crate) struct Resubscribe<T> {
+    pub(crate) pending: Option<T>,
+    pub(crate) active: bool
+}
+
+pub(crate) fn trampoline<T, F>(shared: &Arc<Mutex<Resubscribe<T>>>, round: T, mut f: F)
+    where F: FnMut(T) {
+    {
+        let mut state = shared.acquire();
+        if state.active {
+            state.pending = Some(round);
+            return;
+        }
+        state.active = true;
+    }
+
+    let mut next = Some(round);
+    while let Some(round) = next {
+        f(round);
+
+        let mut state = shared.acquire();
+        next = state.pending.take();
+        if next.is_none() {
+            state.active = false;
+        }
+    }
+}
+
+pub struct RepeatObservable<S> {
+    pub(crate) count: Option<usize>,
+    pub(crate) source: Arc<S>
+}
+
+impl<S> Observable for RepeatObservable<S>
+    where S: Observable + Send + Sync + 'static,
+          S::Item: Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        if self.count == Some(0) {
+            return observer.completed();
+        }
+
+        let rounds = Arc::new(Mutex::new(Resubscribe {pending: None, active: false}));
+        subscribe_round(&self.source, &rounds, (observer, self.count));
+    }
+}
+
+pub(crate) fn subscribe_round<S, N>(source: &Arc<S>, rounds: &Arc<Mutex<Resubscribe<(N, Option<usize>)>>>, round: (N, Option<usize>))
+    where S: Observable + Send + Sync + 'static,
+          N: Observer<Item=S::Item> + Send + Sync + 'static {
+    trampoline(rounds, round, |(observer, remaining)| {
+        source.subscribe(RepeatObserver {
+            remaining: remaining,
+            stopped: false,
+            source: source.clone(),
+            rounds: rounds.clone(),
+            observer: Some(observer)
+        });
+    });
+}
+
+// remaining counts the subscriptions left including the current one, or is
+// None to repeat forever
+pub(crate) struct RepeatObserver<S, N> {
+    pub(crate) remaining: Option<usize>,
+    pub(crate) stopped: bool,
+    pub(crate) source: Arc<S>,
+    pub(crate) rounds: Arc<Mutex<Resubscribe<(N, Option<usize>)>>>,
+    pub(crate) observer: Option<N>
+}
+
+impl<S, N> Observer for RepeatObserver<S, N>
+    where S: Observable + Send + Sync + 'static,
+          N: Observer<Item=S::Item> + Send + Sync + 'static {
+    type Item = S::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let result = match self.observer {
+            Some(ref mut observer) => observer.next(val),
+            None => Stop
+        };
+
+        if let Stop = result {
+            self.stopped = true;
+        }
+        result
+    }
+
+    fn completed(&mut self) {
+        let mut observer = match self.observer.take() {
+            Some(observer) => observer,
+            None => return
+        };
+
+        let remaining = match self.remaining {
+            _ if self.stopped => return observer.completed(),
+            Some(remaining) if remaining <= 1 => return observer.completed(),
+            Some(remaining) => Some(remaining - 1),
+            None => None
+        };
+
+        subscribe_round(&self.source, &self.rounds, (observer, remaining));
+    }
+
+    fn error(&mut self, err: Error) {
+        if let Some(mut observer) = self.observer.take() {
+            observer.error(err);
+        }
+    }
+}
+
+//////////////RepeatWhile
+// Resubscribes depending on the last item of each round. The predicate sees
+// every item as it passes and the verdict on the last one counts, so items
+// needn't be cloned to be kept. A round without items has nothing to go on:
+// repeat_until carries on and do_while stops.
+pub struct RepeatWhileObservable<S, P> {
+    pub(crate) predicate: Arc<P>,
+    // Whether a match ends the loop (repeat_until) or continues it (do_while)
+    pub(crate) until: bool,
+    pub(crate) source: Arc<S>
+}
+
+impl<S, P> Observable for RepeatWhileObservable<S, P>
+    where S: Observable + Send + Sync + 'static,
+          S::Item: Send + Sync + 'static,
+          P: Fn(&S::Item) -> bool + Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let rounds = Arc::new(Mutex::new(Resubscribe {pending: None, active: false}));
+        subscribe_while_round(&self.source, &self.predicate, self.until, &rounds, observer);
+    }
+}
+
+pub(crate) fn subscribe_while_round<S, P, N>(source: &Arc<S>, predicate: &Arc<P>, until: bool, rounds: &Arc<Mutex<Resubscribe<N>>>, observer: N)
+    where S: Observable + Send + Sync + 'static,
+          P: Fn(&S::Item) -> bool + Send + Sync + 'static,
+          N: Observer<Item=S::Item> + Send + Sync + 'static {
+    trampoline(rounds, observer, |observer| {
+        source.subscribe(RepeatWhileObserver {
+            predicate: predicate.clone(),
+            until: until,
+            matched: None,
+            stopped: false,
+            source: source.clone(),
+            rounds: rounds.clone(),
+            observer: Some(observer)
+        });
+    });
+}
+
+pub(crate) struct RepeatWhileObserver<S, P, N> {
+    pub(crate) predicate: Arc<P>,
+    pub(crate) until: bool,
+    // The verdict on the round's latest item
+    pub(crate) matched: Option<bool>,
+    pub(crate) stopped: bool,
+    pub(crate) source: Arc<S>,
+    pub(crate) rounds: Arc<Mutex<Resubscribe<N>>>,
+    pub(crate) observer: Option<N>
+}
+
+impl<S, P, N> Observer for RepeatWhileObserver<S, P, N>
+    where S: Observable + Send + Sync + 'static,
+          P: Fn(&S::Item) -> bool + Send + Sync + 'static,
+          N: Observer<Item=S::Item> + Send + Sync + 'static {
+    type Item = S::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.matched = Some((self.predicate)(&val));
+        let result = match self.observer {
+            Some(ref mut observer) => observer.next(val),
+            None => Stop
+        };
+
+        if let Stop = result {
+            self.stopped = true;
+        }
+        result
+    }
+
+    fn completed(&mut self) {
+        let mut observer = match self.observer.take() {
+            Some(observer) => observer,
+            None => return
+        };
+
+        let again = match self.matched {
+            Some(matched) => matched != self.until,
+            None => self.until
+        };
+        if self.stopped || !again {
+            return observer.completed();
+        }
+
+        subscribe_while_round(&self.source, &self.predicate, self.until, &self.rounds, observer);
+    }
+
+    fn error(&mut self, err: Error) {
+        if let Some(mut observer) = self.observer.take() {
+            observer.error(err);
+        }
+    }
+}
+//RepeatWhile//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::observable::Observable;
+    use crate::sources::{create, range, throw, Emitter};
+    use crate::testing::marbles::{assert_marbles, cold, MarbleError};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn repeat_n_subscribes_count_times() {
+        let observer = TestObserver::new();
+        range(0, 2).repeat_n(3).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 0, 1, 0, 1]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn repeat_stops_once_the_downstream_stops() {
+        let observer = TestObserver::new();
+        range(0, 3).repeat().take(7).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2, 0, 1, 2, 0]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn repeat_passes_errors_on() {
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(MarbleError)).repeat().subscribe(observer.clone());
+        observer.assert_error();
+    }
+
+    #[test]
+    fn synchronous_repeats_dont_grow_the_stack() {
+        let observer = TestObserver::new();
+        range(0, 1).repeat_n(1_000_000).subscribe(observer.clone());
+        assert_eq!(observer.value_count(), 1_000_000);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn repeat_waits_for_an_asynchronous_round() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").repeat_n(2);
+        assert_marbles(&scheduler, source, "a-ba-b|");
+    }
+
+    #[test]
+    fn repeat_until_stops_on_a_matching_last_item() {
+        let polls = Arc::new(AtomicUsize::new(0));
+        let counted = polls.clone();
+        let observer = TestObserver::new();
+        create(move |emitter: Emitter<usize>| {
+            emitter.next(counted.fetch_add(1, Ordering::SeqCst));
+            emitter.completed();
+        })
+        .repeat_until(|&poll| poll == 3)
+        .subscribe(observer.clone());
+
+        observer.assert_values(&[0, 1, 2, 3]);
+        observer.assert_completed();
+        assert_eq!(polls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn do_while_stops_once_the_last_item_doesnt_match() {
+        let observer = TestObserver::new();
+        let rounds = Arc::new(AtomicUsize::new(0));
+        let counted = rounds.clone();
+        create(move |emitter: Emitter<usize>| {
+            let round = counted.fetch_add(1, Ordering::SeqCst);
+            emitter.next(round);
+            emitter.completed();
+        })
+        .do_while(|&round| round < 2)
+        .subscribe(observer.clone());
+
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+        assert_eq!(rounds.load(Ordering::SeqCst), 3);
+    }
+}
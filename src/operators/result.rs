@@ -0,0 +1,180 @@
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+#[cfg(feature = "std")]
+use crate::observable::Observable;
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::Continue;
+use crate::operators::lift::{LiftObservable, Operator};
+#[cfg(feature = "std")]
+use crate::operators::merge::MergeAllObservable;
+#[cfg(feature = "std")]
+use crate::sync::MutexExt;
+
+// What one of the Result combinators does to each item, None dropping it
+pub trait ResultStep<A> {
+    type Output;
+
+    fn step(&self, val: A) -> Option<Self::Output>;
+}
+
+pub struct MapOk<F> {
+    pub(crate) f: F
+}
+
+impl<T, X, B, F> ResultStep<Result<T, X>> for MapOk<F>
+    where F: Fn(T) -> B {
+    type Output = Result<B, X>;
+
+    #[inline]
+    fn step(&self, val: Result<T, X>) -> Option<Self::Output> {
+        Some(val.map(&self.f))
+    }
+}
+
+pub struct MapErr<F> {
+    pub(crate) f: F
+}
+
+impl<T, X, Y, F> ResultStep<Result<T, X>> for MapErr<F>
+    where F: Fn(X) -> Y {
+    type Output = Result<T, Y>;
+
+    #[inline]
+    fn step(&self, val: Result<T, X>) -> Option<Self::Output> {
+        Some(val.map_err(&self.f))
+    }
+}
+
+pub struct AndThen<F> {
+    pub(crate) f: F
+}
+
+impl<T, X, B, F> ResultStep<Result<T, X>> for AndThen<F>
+    where F: Fn(T) -> Result<B, X> {
+    type Output = Result<B, X>;
+
+    #[inline]
+    fn step(&self, val: Result<T, X>) -> Option<Self::Output> {
+        Some(val.and_then(&self.f))
+    }
+}
+
+// Err items always pass
+pub struct FilterOk<P> {
+    pub(crate) predicate: P
+}
+
+impl<T, X, P> ResultStep<Result<T, X>> for FilterOk<P>
+    where P: Fn(&T) -> bool {
+    type Output = Result<T, X>;
+
+    #[inline]
+    fn step(&self, val: Result<T, X>) -> Option<Self::Output> {
+        match val {
+            Ok(ref ok) if !(self.predicate)(ok) => None,
+            _ => Some(val)
+        }
+    }
+}
+
+// Ok values become the inner observable f returns, and an Err becomes an
+// inner observable of just that Err
+#[cfg(feature = "std")]
+pub struct TryFlatMap<F> {
+    pub(crate) f: F
+}
+
+#[cfg(feature = "std")]
+impl<T, X, U, F> ResultStep<Result<T, X>> for TryFlatMap<F>
+    where F: Fn(T) -> U {
+    type Output = TryInner<U, X>;
+
+    #[inline]
+    fn step(&self, val: Result<T, X>) -> Option<Self::Output> {
+        Some(match val {
+            Ok(ok) => TryInner::Inner((self.f)(ok)),
+            Err(err) => TryInner::Failed(Mutex::new(Some(err)))
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+pub enum TryInner<U, X> {
+    Inner(U),
+    Failed(Mutex<Option<X>>)
+}
+
+#[cfg(feature = "std")]
+impl<U, B, X> Observable for TryInner<U, X>
+    where U: Observable<Item=Result<B, X>> {
+    type Item = Result<B, X>;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        match *self {
+            TryInner::Inner(ref inner) => inner.subscribe(observer),
+            TryInner::Failed(ref err) => {
+                if let Some(err) = err.acquire().take() {
+                    observer.next(Err(err));
+                }
+                observer.completed();
+            }
+        }
+    }
+}
+
+pub type TryObservableOf<G, S> = LiftObservable<S, TryOperator<G>>;
+#[cfg(feature = "std")]
+pub type TryFlatMapObservable<F, S, U, X> = MergeAllObservable<TryObservableOf<TryFlatMap<F>, S>, TryInner<U, X>>;
+
+pub struct TryOperator<G> {
+    pub(crate) step: Arc<G>
+}
+
+impl<A, G, E> Operator<A, E> for TryOperator<G>
+    where A: Send + Sync + 'static,
+          G: ResultStep<A> + Send + Sync + 'static {
+    type Item = G::Output;
+    type Upstream<N> = TryObserver<G, N, A>
+        where N: Observer<E, Item=G::Output> + Send + Sync + 'static;
+
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=G::Output> + Send + Sync + 'static {
+        TryObserver {step: self.step.clone(), observer: observer, _marker: PhantomData}
+    }
+
+    fn call_owned<N>(self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=G::Output> + Send + Sync + 'static {
+        TryObserver {step: self.step, observer: observer, _marker: PhantomData}
+    }
+}
+
+pub struct TryObserver<G, N, A> {
+    pub(crate) step: Arc<G>,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, G, N, E> Observer<E> for TryObserver<G, N, A>
+    where G: ResultStep<A>,
+          N: Observer<E, Item=G::Output> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        match self.step.step(val) {
+            Some(val) => self.observer.next(val),
+            None => Continue
+        }
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: E) {
+        self.observer.error(err);
+    }
+}
@@ -0,0 +1,343 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+use crate::operators::repeat::{Resubscribe, trampoline};
+use crate::schedulers::Scheduler;
+
+// Decides, after each failure, how long to wait before resubscribing. Returning
+// None gives up and forwards the error. A policy is cloned for every
+// subscription, so it is free to keep per-subscription state. now comes from
+// the scheduler the retries wait on, so a virtual clock is honoured.
+pub trait BackoffPolicy {
+    fn next_delay(&mut self, attempt: usize, err: &Error, now: Instant) -> Option<Duration>;
+}
+
+#[derive(Clone)]
+pub struct FixedBackoff {
+    pub(crate) delay: Duration,
+    pub(crate) max_retries: usize
+}
+
+impl FixedBackoff {
+    pub fn new(delay: Duration, max_retries: usize) -> FixedBackoff {
+        FixedBackoff {delay: delay, max_retries: max_retries}
+    }
+}
+
+impl BackoffPolicy for FixedBackoff {
+    fn next_delay(&mut self, attempt: usize, _: &Error, _: Instant) -> Option<Duration> {
+        if attempt <= self.max_retries {
+            Some(self.delay)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ExponentialBackoff {
+    pub(crate) initial: Duration,
+    pub(crate) multiplier: f64,
+    pub(crate) max_delay: Duration,
+    pub(crate) jitter: f64,
+    pub(crate) max_retries: Option<usize>,
+    pub(crate) max_elapsed: Option<Duration>,
+    pub(crate) started: Option<Instant>,
+    pub(crate) seed: u64
+}
+
+impl ExponentialBackoff {
+    pub fn new(initial: Duration, max_delay: Duration) -> ExponentialBackoff {
+        ExponentialBackoff {
+            initial: initial,
+            multiplier: 2.0,
+            max_delay: max_delay,
+            jitter: 0.0,
+            max_retries: None,
+            max_elapsed: None,
+            started: None,
+            seed: 0
+        }
+    }
+
+    // Anything below 1, NaN included, is taken as 1, so delays never shrink
+    // and never go negative
+    pub fn multiplier(mut self, multiplier: f64) -> ExponentialBackoff {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    // A fraction in [0, 1] of each delay that is randomized away
+    pub fn jitter(mut self, jitter: f64) -> ExponentialBackoff {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: usize) -> ExponentialBackoff {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    // Measured from the first failure
+    pub fn max_elapsed(mut self, max_elapsed: Duration) -> ExponentialBackoff {
+        self.max_elapsed = Some(max_elapsed);
+        self
+    }
+
+    // xorshift is plenty for spreading retries out, and avoids a dependency
+    fn random(&mut self) -> f64 {
+        if self.seed == 0 {
+            let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+            self.seed = (nanos as u64) | 1;
+        }
+
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl BackoffPolicy for ExponentialBackoff {
+    fn next_delay(&mut self, attempt: usize, _: &Error, now: Instant) -> Option<Duration> {
+        let started = *self.started.get_or_insert(now);
+
+        if self.max_retries.is_some_and(|max| attempt > max) {
+            return None;
+        }
+
+        let delay = self.initial.as_secs_f64() * self.multiplier.powi(attempt as i32 - 1);
+        let delay = delay.min(self.max_delay.as_secs_f64());
+        let delay = delay * (1.0 - self.jitter * self.random());
+        let delay = Duration::from_secs_f64(delay);
+
+        match self.max_elapsed {
+            Some(max) if now + delay - started > max => None,
+            _ => Some(delay)
+        }
+    }
+}
+
+pub struct RetryObservable<S, P, D> {
+    pub(crate) policy: P,
+    pub(crate) scheduler: D,
+    pub(crate) source: Arc<S>
+}
+
+impl<S, P, D> Observable for RetryObservable<S, P, D>
+    where S: Observable + Send + Sync + 'static,
+          S::Item: Send + Sync + 'static,
+          P: BackoffPolicy + Clone + Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let rounds = Arc::new(Mutex::new(Resubscribe {pending: None, active: false}));
+        let first = RetryObserver {
+            attempt: 0,
+            policy: self.policy.clone(),
+            scheduler: self.scheduler.clone(),
+            source: self.source.clone(),
+            rounds: rounds.clone(),
+            observer: Some(observer)
+        };
+        subscribe_attempt(&self.source, &rounds, first);
+    }
+}
+
+// An attempt that fails straight away resubscribes from inside the one
+// before, so each is queued for the outermost subscribe to run rather than
+// nested, which would overflow the stack over enough immediate retries
+pub(crate) fn subscribe_attempt<S, N, P, D>(source: &Arc<S>, rounds: &Arc<Mutex<Resubscribe<RetryObserver<S, N, P, D>>>>, retry: RetryObserver<S, N, P, D>)
+    where S: Observable + Send + Sync + 'static,
+          N: Observer<Item=S::Item> + Send + Sync + 'static,
+          P: BackoffPolicy + Clone + Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    trampoline(rounds, retry, |retry| source.subscribe(retry));
+}
+
+// Every attempt gets a fresh subscription. The downstream observer is moved
+// into the next attempt's observer, so a failed attempt is left with nothing
+// to forward to.
+pub(crate) struct RetryObserver<S, N, P, D> {
+    pub(crate) attempt: usize,
+    pub(crate) policy: P,
+    pub(crate) scheduler: D,
+    pub(crate) source: Arc<S>,
+    pub(crate) rounds: Arc<Mutex<Resubscribe<RetryObserver<S, N, P, D>>>>,
+    pub(crate) observer: Option<N>
+}
+
+impl<S, N, P, D> Observer for RetryObserver<S, N, P, D>
+    where S: Observable + Send + Sync + 'static,
+          N: Observer<Item=S::Item> + Send + Sync + 'static,
+          P: BackoffPolicy + Clone + Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        match self.observer {
+            Some(ref mut observer) => observer.next(val),
+            None => Stop
+        }
+    }
+
+    fn completed(&mut self) {
+        if let Some(ref mut observer) = self.observer {
+            observer.completed();
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        let mut observer = match self.observer.take() {
+            Some(observer) => observer,
+            None => return
+        };
+
+        let attempt = self.attempt + 1;
+        let delay = match self.policy.next_delay(attempt, &err, self.scheduler.now()) {
+            Some(delay) => delay,
+            None => return observer.error(err)
+        };
+
+        let source = self.source.clone();
+        let retry = RetryObserver {
+            attempt: attempt,
+            policy: self.policy.clone(),
+            scheduler: self.scheduler.clone(),
+            source: self.source.clone(),
+            rounds: self.rounds.clone(),
+            observer: Some(observer)
+        };
+
+        // Immediate retries stay on the current thread
+        if delay == Duration::from_secs(0) {
+            subscribe_attempt(&source, &self.rounds, retry);
+        } else {
+            let rounds = self.rounds.clone();
+            self.scheduler.schedule_after(delay, Box::new(move || subscribe_attempt(&source, &rounds, retry)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::operators::retry::{BackoffPolicy, ExponentialBackoff, FixedBackoff};
+    use crate::schedulers::Scheduler;
+    use crate::sources::{create, throw};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[derive(Debug)]
+    struct Failed;
+
+    impl fmt::Display for Failed {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "failed")
+        }
+    }
+
+    impl std::error::Error for Failed {}
+
+    #[test]
+    fn retries_until_the_source_succeeds() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counted = attempts.clone();
+        let source = create(move |emitter| {
+            let attempt = counted.fetch_add(1, Ordering::SeqCst);
+            emitter.next(attempt);
+            if attempt < 2 {
+                emitter.error(Arc::new(Failed));
+            } else {
+                emitter.completed();
+            }
+        });
+
+        let observer = TestObserver::new();
+        source.retry(5).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn gives_up_after_the_last_retry() {
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(Failed)).retry(3).subscribe(observer.clone());
+        observer.assert_error();
+    }
+
+    #[test]
+    fn immediate_retries_dont_grow_the_stack() {
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(Failed)).retry(1_000_000).subscribe(observer.clone());
+        observer.assert_error();
+
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(Failed)).retry_with(FixedBackoff::new(Duration::from_secs(0), 1_000_000)).subscribe(observer.clone());
+        observer.assert_error();
+    }
+
+    #[test]
+    fn delayed_retries_wait_on_the_scheduler() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(Failed))
+            .retry_with_on(FixedBackoff::new(Duration::from_secs(1), 2), scheduler.clone())
+            .subscribe(observer.clone());
+
+        scheduler.advance_by(Duration::from_millis(1500));
+        assert!(!observer.is_terminated());
+        scheduler.advance_by(Duration::from_secs(1));
+        observer.assert_error();
+    }
+
+    // On the real clock hardly any time would have passed, and it'd keep going
+    #[test]
+    fn exponential_backoff_gives_up_by_the_scheduler_clock() {
+        let scheduler = TestScheduler::new();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counted = attempts.clone();
+        let observer = TestObserver::<i32>::new();
+        create(move |emitter| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            emitter.error(Arc::new(Failed));
+        })
+        .retry_with_on(ExponentialBackoff::new(Duration::from_millis(10), Duration::from_secs(1)).max_elapsed(Duration::from_millis(50)), scheduler.clone())
+        .subscribe(observer.clone());
+
+        scheduler.advance_by(Duration::from_millis(10));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(!observer.is_terminated());
+        scheduler.advance_by(Duration::from_millis(20));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        observer.assert_error();
+    }
+
+    #[test]
+    fn exponential_backoff_takes_a_bad_multiplier_as_one() {
+        let now = TestScheduler::new().now();
+        for multiplier in [-2.0, 0.5, f64::NAN] {
+            let mut policy = ExponentialBackoff::new(Duration::from_millis(5), Duration::from_secs(1)).multiplier(multiplier).max_retries(3);
+            for attempt in 1..=3 {
+                assert_eq!(policy.next_delay(attempt, &(Arc::new(Failed) as _), now), Some(Duration::from_millis(5)));
+            }
+            assert_eq!(policy.next_delay(4, &(Arc::new(Failed) as _), now), None);
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::sync::{Arc, Mutex};
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::sync::MutexExt;
+
+// Relays the source's errors (retry_when) or completions (repeat_when) into
+// whatever the handler built on top of them. Signals raised while one is
+// already being delivered (a synchronous source terminating again during
+// resubscription) are queued and delivered by the outer call, so the relay
+// never re-enters itself and the stack stays flat.
+pub(crate) struct SignalHub<A> {
+    pub(crate) observer: Option<Box<dyn Observer<Item=A> + Send + Sync>>,
+    pub(crate) queue: VecDeque<A>,
+    pub(crate) draining: bool
+}
+
+pub(crate) fn push_signal<A>(hub: &Arc<Mutex<SignalHub<A>>>, signal: A) {
+    let mut observer = {
+        let mut hub = hub.acquire();
+        if hub.draining {
+            hub.queue.push_back(signal);
+            return;
+        }
+
+        match hub.observer.take() {
+            Some(observer) => {
+                hub.draining = true;
+                hub.queue.push_back(signal);
+                observer
+            },
+            None => return
+        }
+    };
+
+    loop {
+        let signal = {
+            let mut hub = hub.acquire();
+            match hub.queue.pop_front() {
+                Some(signal) => signal,
+                None => {
+                    hub.draining = false;
+                    hub.observer = Some(observer);
+                    return;
+                }
+            }
+        };
+
+        if let Stop = observer.next(signal) {
+            let mut hub = hub.acquire();
+            hub.draining = false;
+            hub.queue.clear();
+            drop(hub);
+            observer.completed();
+            return;
+        }
+    }
+}
+
+pub struct SignalObservable<A> {
+    pub(crate) hub: Arc<Mutex<SignalHub<A>>>
+}
+
+impl<A> Observable for SignalObservable<A> {
+    type Item = A;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.hub.acquire().observer = Some(Box::new(observer));
+    }
+}
+
+// Which terminal event gets relayed to the handler; the other one is forwarded
+// downstream as usual
+pub trait FeedbackSignal: Sized {
+    fn from_completed() -> Option<Self>;
+    fn from_error(err: Error) -> Result<Self, Error>;
+}
+
+impl FeedbackSignal for Error {
+    fn from_completed() -> Option<Error> {
+        None
+    }
+
+    fn from_error(err: Error) -> Result<Error, Error> {
+        Ok(err)
+    }
+}
+
+impl FeedbackSignal for () {
+    fn from_completed() -> Option<()> {
+        Some(())
+    }
+
+    fn from_error(err: Error) -> Result<(), Error> {
+        Err(err)
+    }
+}
+
+pub struct WhenObservable<S, F, A> {
+    pub(crate) handler: F,
+    pub(crate) source: Arc<S>,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<S, F, U, A> Observable for WhenObservable<S, F, A>
+    where S: Observable + Send + Sync + 'static,
+          S::Item: Send + Sync + 'static,
+          F: Fn(SignalObservable<A>) -> U,
+          U: Observable,
+          U::Item: Send + Sync + 'static,
+          A: FeedbackSignal + Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let hub = Arc::new(Mutex::new(SignalHub {observer: None, queue: VecDeque::new(), draining: false}));
+        let state = Arc::new(Mutex::new(WhenState {observer: observer, done: false}));
+
+        let notifier = (self.handler)(SignalObservable {hub: hub.clone()});
+        notifier.subscribe(NotifierObserver {
+            source: self.source.clone(),
+            state: state.clone(),
+            hub: hub.clone(),
+            _marker: PhantomData
+        });
+
+        self.source.subscribe(WhenObserver {state: state, hub: hub});
+    }
+}
+
+pub(crate) struct WhenState<N> {
+    pub(crate) observer: N,
+    pub(crate) done: bool
+}
+
+pub(crate) struct WhenObserver<N, A> {
+    pub(crate) state: Arc<Mutex<WhenState<N>>>,
+    pub(crate) hub: Arc<Mutex<SignalHub<A>>>
+}
+
+impl<N, A> Observer for WhenObserver<N, A>
+    where N: Observer,
+          A: FeedbackSignal {
+    type Item = N::Item;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut state = self.state.acquire();
+        if state.done {
+            return Stop;
+        }
+
+        // Once the downstream has stopped, a terminal event from the source
+        // mustn't be relayed to the handler, or it would resubscribe
+        let result = state.observer.next(val);
+        if let Stop = result {
+            state.done = true;
+        }
+        result
+    }
+
+    fn completed(&mut self) {
+        let mut state = self.state.acquire();
+        if state.done {
+            return;
+        }
+
+        match A::from_completed() {
+            Some(signal) => {
+                drop(state);
+                push_signal(&self.hub, signal);
+            },
+            None => {
+                state.done = true;
+                state.observer.completed();
+            }
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        let mut state = self.state.acquire();
+        if state.done {
+            return;
+        }
+
+        match A::from_error(err) {
+            Ok(signal) => {
+                drop(state);
+                push_signal(&self.hub, signal);
+            },
+            Err(err) => {
+                state.done = true;
+                state.observer.error(err);
+            }
+        }
+    }
+}
+
+// Each notification resubscribes the source; the notifier terminating
+// terminates the stream
+pub(crate) struct NotifierObserver<S, N, B, A> {
+    pub(crate) source: Arc<S>,
+    pub(crate) state: Arc<Mutex<WhenState<N>>>,
+    pub(crate) hub: Arc<Mutex<SignalHub<A>>>,
+    pub(crate) _marker: PhantomData<B>
+}
+
+impl<S, N, B, A> Observer for NotifierObserver<S, N, B, A>
+    where S: Observable,
+          N: Observer<Item=S::Item> + Send + 'static,
+          A: FeedbackSignal + Send + 'static {
+    type Item = B;
+
+    fn next(&mut self, _: Self::Item) -> IterationResult {
+        if self.state.acquire().done {
+            return Stop;
+        }
+
+        self.source.subscribe(WhenObserver {state: self.state.clone(), hub: self.hub.clone()});
+        Continue
+    }
+
+    fn completed(&mut self) {
+        let mut state = self.state.acquire();
+        if !state.done {
+            state.done = true;
+            state.observer.completed();
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        let mut state = self.state.acquire();
+        if !state.done {
+            state.done = true;
+            state.observer.error(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::sources::{create, range, throw, Emitter};
+    use crate::testing::marbles::{assert_marbles, cold, MarbleError};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn retry_when_resubscribes_for_each_signal() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counted = attempts.clone();
+        let observer = TestObserver::new();
+        create(move |emitter: Emitter<usize>| {
+            emitter.next(counted.fetch_add(1, Ordering::SeqCst));
+            emitter.error(Arc::new(MarbleError));
+        })
+        .retry_when(|errors| errors.take(2))
+        .subscribe(observer.clone());
+
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn retry_when_waits_on_the_handler() {
+        let scheduler = TestScheduler::new();
+        let delayer = scheduler.clone();
+        let source = cold(&scheduler, "a#").retry_when(move |errors| errors.take(2).delay_on(Duration::from_millis(20), delayer.clone()));
+        assert_marbles(&scheduler, source, "a--a--|");
+    }
+
+    #[test]
+    fn retry_when_passes_completion_straight_through() {
+        let observer = TestObserver::new();
+        range(0, 3).retry_when(|errors| errors).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn synchronous_retries_dont_grow_the_stack() {
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(MarbleError)).retry_when(|errors| errors.take(200_000)).subscribe(observer.clone());
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn repeat_when_resubscribes_on_completion() {
+        let observer = TestObserver::new();
+        range(0, 2).repeat_when(|completions| completions.take(2)).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 0, 1, 0, 1]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn retry_when_stops_resubscribing_once_the_downstream_stops() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let counted = attempts.clone();
+        let observer = TestObserver::new();
+        create(move |emitter: Emitter<usize>| {
+            emitter.next(counted.fetch_add(1, Ordering::SeqCst));
+            emitter.error(Arc::new(MarbleError));
+        })
+        .retry_when(|errors| errors.take(5))
+        .take(2)
+        .subscribe(observer.clone());
+
+        observer.assert_values(&[0, 1]);
+        observer.assert_completed();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn repeat_when_stops_resubscribing_once_the_downstream_stops() {
+        let subscriptions = Arc::new(AtomicUsize::new(0));
+        let counted = subscriptions.clone();
+        let observer = TestObserver::new();
+        range(0, 3)
+            .do_on_subscribe(move || { counted.fetch_add(1, Ordering::SeqCst); })
+            .repeat_when(|completions| completions)
+            .take(2)
+            .subscribe(observer.clone());
+
+        observer.assert_values(&[0, 1]);
+        observer.assert_completed();
+        assert_eq!(subscriptions.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn repeat_when_passes_errors_straight_through() {
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(MarbleError)).repeat_when(|completions| completions).subscribe(observer.clone());
+        observer.assert_error();
+    }
+}
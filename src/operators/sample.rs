@@ -0,0 +1,184 @@
+use std::sync::{Arc, Mutex};
+use std::marker::PhantomData;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::current_thread::trampolined;
+use crate::sync::MutexExt;
+
+pub struct SampleObservable<S, T> {
+    pub(crate) source: S,
+    pub(crate) sampler: T
+}
+
+impl<S, T> Observable for SampleObservable<S, T>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          T: Observable,
+          T::Item: Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node(), self.sampler.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let state = Arc::new(Mutex::new(SampleState {
+            observer: observer,
+            latest: None,
+            done: false,
+            terminated: false
+        }));
+        // A sampler on CurrentThreadScheduler would otherwise tick away in
+        // place before the source is ever subscribed
+        trampolined(|| {
+            self.sampler.subscribe(SamplerObserver {state: state.clone(), _marker: PhantomData});
+            self.source.subscribe(SampleObserver {state: state});
+        });
+    }
+}
+
+pub(crate) struct SampleState<A, N> {
+    pub(crate) observer: N,
+    pub(crate) latest: Option<A>,
+    pub(crate) done: bool,
+    pub(crate) terminated: bool
+}
+
+impl<A, N> SampleState<A, N>
+    where N: Observer<Item=A> {
+    fn release(&mut self) {
+        if self.done {
+            return;
+        }
+
+        if let Some(val) = self.latest.take() {
+            if let Stop = self.observer.next(val) {
+                self.done = true;
+            }
+        }
+    }
+
+    fn finish(&mut self, err: Option<Error>) {
+        self.done = true;
+        self.latest = None;
+        if !self.terminated {
+            self.terminated = true;
+            match err {
+                Some(err) => self.observer.error(err),
+                None => self.observer.completed()
+            }
+        }
+    }
+}
+
+pub(crate) struct SampleObserver<A, N> {
+    pub(crate) state: Arc<Mutex<SampleState<A, N>>>
+}
+
+impl<A, N> Observer for SampleObserver<A, N>
+    where N: Observer<Item=A> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut state = self.state.acquire();
+        if state.done {
+            return Stop;
+        }
+
+        state.latest = Some(val);
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.state.acquire().finish(None);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.state.acquire().finish(Some(err));
+    }
+}
+
+// Once the sampler completes nothing else can be released, so the stream
+// completes with it
+pub(crate) struct SamplerObserver<A, N, B> {
+    pub(crate) state: Arc<Mutex<SampleState<A, N>>>,
+    pub(crate) _marker: PhantomData<B>
+}
+
+impl<A, N, B> Observer for SamplerObserver<A, N, B>
+    where N: Observer<Item=A> {
+    type Item = B;
+
+    fn next(&mut self, _: Self::Item) -> IterationResult {
+        let mut state = self.state.acquire();
+        state.release();
+        if state.done {
+            Stop
+        } else {
+            Continue
+        }
+    }
+
+    fn completed(&mut self) {
+        self.state.acquire().finish(None);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.state.acquire().finish(Some(err));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::schedulers::current_thread::CurrentThreadScheduler;
+    use crate::sources::interval_on;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn sample_releases_the_latest_on_each_tick() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "ab-c----d|").sample(cold(&scheduler, "--x--xx"));
+        assert_marbles(&scheduler, source, "--b--c---|");
+    }
+
+    #[test]
+    fn sample_completes_with_the_sampler() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "ab-c--d|").sample(cold(&scheduler, "--x--x|"));
+        assert_marbles(&scheduler, source, "--b--c|");
+    }
+
+    #[test]
+    fn sample_completes_with_the_source() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").sample(cold(&scheduler, "-x---x|"));
+        assert_marbles(&scheduler, source, "-a-|");
+    }
+
+    #[test]
+    fn sample_fails_with_the_source() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-#").sample(cold(&scheduler, "-x---x|"));
+        assert_marbles(&scheduler, source, "-a#");
+    }
+
+    #[test]
+    fn sample_time_runs_on_a_scheduler_that_runs_timers_in_place() {
+        let observer = TestObserver::new();
+        interval_on(Duration::from_millis(4), CurrentThreadScheduler)
+            .take(4)
+            .sample_time_on(Duration::from_millis(10), CurrentThreadScheduler)
+            .subscribe(observer.clone());
+        observer.assert_values(&[1]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,146 @@
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+use crate::sync::MutexExt;
+
+// A window onto the state a scan_shared pipeline last emitted, for code that
+// just wants the current value rather than a subscription. It reads the seed
+// until something's been accumulated.
+pub struct StateHandle<B> {
+    pub(crate) latest: Arc<Mutex<B>>
+}
+
+impl<B> StateHandle<B> {
+    pub(crate) fn new(seed: B) -> StateHandle<B> {
+        StateHandle {latest: Arc::new(Mutex::new(seed))}
+    }
+}
+
+impl<B> StateHandle<B>
+    where B: Clone {
+    pub fn latest(&self) -> B {
+        self.latest.acquire().clone()
+    }
+}
+
+impl<B> Clone for StateHandle<B> {
+    fn clone(&self) -> StateHandle<B> {
+        StateHandle {latest: self.latest.clone()}
+    }
+}
+
+// Every subscription folds from its own copy of the seed, and each state it
+// reaches is stored in the handle before going downstream, so a read from
+// the handle is never behind what subscribers have seen. With more than one
+// subscription the handle holds whichever state came last.
+pub struct ScanSharedObservable<S, F, B> {
+    pub(crate) seed: B,
+    pub(crate) f: Arc<F>,
+    pub(crate) handle: StateHandle<B>,
+    pub(crate) source: S
+}
+
+impl<S, F, B> Observable for ScanSharedObservable<S, F, B>
+    where S: Observable,
+          S::Item: 'static,
+          F: Fn(B, S::Item) -> B + Send + Sync + 'static,
+          B: Clone + Send + Sync + 'static {
+    type Item = B;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(ScanSharedObserver {
+            f: self.f.clone(),
+            state: Some(self.seed.clone()),
+            handle: self.handle.clone(),
+            observer: observer,
+            _marker: PhantomData
+        });
+    }
+}
+
+pub(crate) struct ScanSharedObserver<F, A, B, N> {
+    pub(crate) f: Arc<F>,
+    // Only empty while f runs, so a panic in f ends the fold
+    pub(crate) state: Option<B>,
+    pub(crate) handle: StateHandle<B>,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<fn(A)>
+}
+
+impl<F, A, B, N> Observer for ScanSharedObserver<F, A, B, N>
+    where F: Fn(B, A) -> B,
+          B: Clone,
+          N: Observer<Item=B> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let state = match self.state.take() {
+            Some(state) => (self.f)(state, val),
+            None => return Stop
+        };
+
+        *self.handle.latest.acquire() = state.clone();
+        self.state = Some(state.clone());
+        self.observer.next(state)
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.error(err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::observable::Observable;
+    use crate::sources::range;
+    use crate::testing::observer::TestObserver;
+
+    #[test]
+    fn scan_shared_folds_and_publishes_each_state() {
+        let (source, handle) = range(1, 5).scan_shared(0, |sum, x| sum + x);
+        assert_eq!(handle.latest(), 0);
+
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        observer.assert_values(&[1, 3, 6, 10]);
+        observer.assert_completed();
+        assert_eq!(handle.latest(), 10);
+    }
+
+    #[test]
+    fn the_handle_is_never_behind_the_downstream() {
+        let (source, handle) = range(1, 5).scan_shared(0, |sum, x| sum + x);
+        let read = Arc::new(Mutex::new(Vec::new()));
+        let reads = read.clone();
+        let reader = handle.clone();
+        source.tap(move |_| reads.lock().unwrap().push(reader.latest())).subscribe(TestObserver::new());
+        assert_eq!(*read.lock().unwrap(), vec![1, 3, 6, 10]);
+    }
+
+    #[test]
+    fn each_subscription_folds_from_the_seed() {
+        let (source, handle) = range(1, 4).scan_shared(10, |sum, x| sum + x);
+        let first = TestObserver::new();
+        let second = TestObserver::new();
+        source.subscribe(first.clone());
+        source.subscribe(second.clone());
+        first.assert_values(&[11, 13, 16]);
+        second.assert_values(&[11, 13, 16]);
+        assert_eq!(handle.latest(), 16);
+    }
+}
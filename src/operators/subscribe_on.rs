@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::Observer;
+use crate::schedulers::Scheduler;
+
+// Moves the subscription itself, and so any work a synchronous source does
+// while subscribing, onto the scheduler
+pub struct SubscribeOnObservable<S, D> {
+    pub(crate) scheduler: D,
+    pub(crate) source: Arc<S>
+}
+
+impl<S, D> Observable for SubscribeOnObservable<S, D>
+    where S: Observable + Send + Sync + 'static,
+          D: Scheduler {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let source = self.source.clone();
+        self.scheduler.schedule(Box::new(move || source.subscribe(observer)));
+    }
+}
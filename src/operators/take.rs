@@ -0,0 +1,80 @@
+use core::marker::PhantomData;
+
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::lift::{LiftObservable, Operator};
+
+pub type TakeObservable<S> = LiftObservable<S, TakeOperator>;
+
+pub struct TakeOperator {
+    pub(crate) count: usize
+}
+
+impl<A, E> Operator<A, E> for TakeOperator
+    where A: Send + Sync + 'static {
+    type Item = A;
+    type Upstream<N> = TakeObserver<A, N>
+        where N: Observer<E, Item=A> + Send + Sync + 'static;
+
+    #[inline]
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=A> + Send + Sync + 'static {
+        TakeObserver {
+            remaining: self.count,
+            done: false,
+            observer: observer,
+            _marker: PhantomData
+        }
+    }
+}
+
+pub struct TakeObserver<A, N> {
+    pub(crate) remaining: usize,
+    pub(crate) done: bool,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, N, E> Observer<E> for TakeObserver<A, N>
+    where N: Observer<E, Item=A> {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let result = if self.remaining > 0 && !self.done {
+            self.observer.next(val)
+        } else {
+            Stop
+        };
+
+        self.remaining = match result {
+            Stop => 0,
+            Continue => self.remaining - 1
+        };
+
+        if self.remaining > 0 {
+            Continue
+        } else {
+            // Complete as soon as the count is reached instead of waiting on
+            // the source, which may never call back again
+            if let Continue = result {
+                self.completed();
+            }
+            Stop
+        }
+    }
+
+    fn completed(&mut self) {
+        if !self.done {
+            self.done = true;
+            self.observer.completed();
+        }
+    }
+
+    fn error(&mut self, err: E) {
+        if !self.done {
+            self.done = true;
+            self.observer.error(err);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::sync::{Arc, Mutex};
+use std::marker::PhantomData;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::subjects::AsyncSubject;
+use crate::sync::MutexExt;
+
+// Mirrors the source until the notifier emits, then completes. A notifier
+// that completes without emitting changes nothing, and one that fails fails
+// the stream.
+pub struct TakeUntilObservable<S, T> {
+    pub(crate) source: S,
+    pub(crate) notifier: T
+}
+
+impl<S, T> Observable for TakeUntilObservable<S, T>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          T: Observable,
+          T::Item: Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node(), self.notifier.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let state = Arc::new(Mutex::new(TakeUntilState {observer: observer, done: false}));
+        self.notifier.subscribe(NotifierObserver {state: state.clone(), _marker: PhantomData});
+        if !state.acquire().done {
+            self.source.subscribe(TakeUntilObserver {state: state});
+        }
+    }
+}
+
+pub(crate) struct TakeUntilState<N> {
+    pub(crate) observer: N,
+    pub(crate) done: bool
+}
+
+impl<N> TakeUntilState<N>
+    where N: Observer {
+    fn finish(&mut self, err: Option<Error>) {
+        if !self.done {
+            self.done = true;
+            match err {
+                Some(err) => self.observer.error(err),
+                None => self.observer.completed()
+            }
+        }
+    }
+}
+
+pub(crate) struct TakeUntilObserver<N> {
+    pub(crate) state: Arc<Mutex<TakeUntilState<N>>>
+}
+
+impl<N> Observer for TakeUntilObserver<N>
+    where N: Observer {
+    type Item = N::Item;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut state = self.state.acquire();
+        if state.done {
+            return Stop;
+        }
+
+        if let Stop = state.observer.next(val) {
+            state.done = true;
+            return Stop;
+        }
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.state.acquire().finish(None);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.state.acquire().finish(Some(err));
+    }
+}
+
+pub(crate) struct NotifierObserver<N, B> {
+    pub(crate) state: Arc<Mutex<TakeUntilState<N>>>,
+    pub(crate) _marker: PhantomData<B>
+}
+
+impl<N, B> Observer for NotifierObserver<N, B>
+    where N: Observer {
+    type Item = B;
+
+    fn next(&mut self, _: Self::Item) -> IterationResult {
+        self.state.acquire().finish(None);
+        Stop
+    }
+
+    fn completed(&mut self) {}
+
+    fn error(&mut self, err: Error) {
+        self.state.acquire().finish(Some(err));
+    }
+}
+
+// Completes every stream tied to it with take_until_dropped when it goes out
+// of scope, so an RAII binding bounds a subscription: let _guard = ...
+// Streams subscribed after it has gone complete straight away.
+pub struct DropGuard {
+    pub(crate) dropped: AsyncSubject<()>
+}
+
+impl DropGuard {
+    pub fn new() -> DropGuard {
+        DropGuard {dropped: AsyncSubject::new()}
+    }
+}
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        self.dropped.next(());
+        self.dropped.completed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::observable::Observable;
+    use crate::sources::value;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn take_until_completes_when_the_notifier_emits() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b-c-d|").take_until(cold(&scheduler, "---x"));
+        assert_marbles(&scheduler, source, "a-b|----");
+    }
+
+    #[test]
+    fn a_notifier_that_just_completes_changes_nothing() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b-c-d|").take_until(cold(&scheduler, "--|"));
+        assert_marbles(&scheduler, source, "a-b-c-d|");
+    }
+
+    #[test]
+    fn a_failing_notifier_fails_the_stream() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b-c-d|").take_until(cold(&scheduler, "-#"));
+        assert_marbles(&scheduler, source, "a#------");
+    }
+
+    #[test]
+    fn an_immediate_notifier_skips_the_source() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b|").take_until(value('x'));
+        assert_marbles(&scheduler, source, "|---");
+    }
+}
@@ -0,0 +1,220 @@
+use alloc::sync::Arc;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+
+pub struct TapObservable<S, F> {
+    pub(crate) f: Arc<F>,
+    pub(crate) source: S
+}
+
+impl<S, F> Observable for TapObservable<S, F>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          F: Fn(&S::Item) + Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(TapObserver {f: self.f.clone(), observer: observer});
+    }
+}
+
+pub(crate) struct TapObserver<F, N> {
+    pub(crate) f: Arc<F>,
+    pub(crate) observer: N
+}
+
+impl<F, N> Observer for TapObserver<F, N>
+    where F: Fn(&N::Item),
+          N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        (self.f)(&val);
+        self.observer.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.error(err);
+    }
+}
+
+pub struct DoOnCompleteObservable<S, F> {
+    pub(crate) f: Arc<F>,
+    pub(crate) source: S
+}
+
+impl<S, F> Observable for DoOnCompleteObservable<S, F>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          F: Fn() + Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(DoOnCompleteObserver {f: self.f.clone(), observer: observer});
+    }
+}
+
+pub(crate) struct DoOnCompleteObserver<F, N> {
+    pub(crate) f: Arc<F>,
+    pub(crate) observer: N
+}
+
+impl<F, N> Observer for DoOnCompleteObserver<F, N>
+    where F: Fn(),
+          N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.observer.next(val)
+    }
+
+    fn completed(&mut self) {
+        (self.f)();
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.error(err);
+    }
+}
+
+pub struct DoOnErrorObservable<S, F> {
+    pub(crate) f: Arc<F>,
+    pub(crate) source: S
+}
+
+impl<S, F> Observable for DoOnErrorObservable<S, F>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          F: Fn(&Error) + Send + Sync + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(DoOnErrorObserver {f: self.f.clone(), observer: observer});
+    }
+}
+
+pub(crate) struct DoOnErrorObserver<F, N> {
+    pub(crate) f: Arc<F>,
+    pub(crate) observer: N
+}
+
+impl<F, N> Observer for DoOnErrorObserver<F, N>
+    where F: Fn(&Error),
+          N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.observer.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        (self.f)(&err);
+        self.observer.error(err);
+    }
+}
+
+pub struct DoOnSubscribeObservable<S, F> {
+    pub(crate) f: F,
+    pub(crate) source: S
+}
+
+impl<S, F> Observable for DoOnSubscribeObservable<S, F>
+    where S: Observable,
+          F: Fn() {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        (self.f)();
+        self.source.subscribe(observer);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::observable::Observable;
+    use crate::sources::{range, throw};
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::TestObserver;
+
+    #[test]
+    fn tap_sees_each_item_before_the_downstream() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let tapped = seen.clone();
+        let observer = TestObserver::new();
+        range(0, 3).tap(move |val| tapped.lock().unwrap().push(*val)).subscribe(observer.clone());
+        assert_eq!(*seen.lock().unwrap(), vec![0, 1, 2]);
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn tap_stops_with_the_downstream() {
+        let seen = Arc::new(AtomicUsize::new(0));
+        let counted = seen.clone();
+        range(0, 100).tap(move |_| { counted.fetch_add(1, Ordering::SeqCst); }).take(2).subscribe(TestObserver::new());
+        assert_eq!(seen.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn hooks_run_on_their_own_events() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (subscribed, completed, failed) = (log.clone(), log.clone(), log.clone());
+        range(0, 1)
+            .do_on_subscribe(move || subscribed.lock().unwrap().push("subscribe"))
+            .do_on_complete(move || completed.lock().unwrap().push("complete"))
+            .do_on_error(move |_| failed.lock().unwrap().push("error"))
+            .subscribe(TestObserver::new());
+        assert_eq!(*log.lock().unwrap(), vec!["subscribe", "complete"]);
+
+        log.lock().unwrap().clear();
+        let (completed, failed) = (log.clone(), log.clone());
+        let observer = TestObserver::<i32>::new();
+        throw(Arc::new(MarbleError))
+            .do_on_complete(move || completed.lock().unwrap().push("complete"))
+            .do_on_error(move |_| failed.lock().unwrap().push("error"))
+            .subscribe(observer.clone());
+        assert_eq!(*log.lock().unwrap(), vec!["error"]);
+        observer.assert_error();
+    }
+}
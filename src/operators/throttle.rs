@@ -0,0 +1,215 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::sync::MutexExt;
+
+#[derive(Clone, Copy)]
+pub enum ThrottleMode {
+    Leading,
+    Trailing,
+    Both
+}
+
+impl ThrottleMode {
+    #[inline]
+    pub fn leading(&self) -> bool {
+        !matches!(*self, ThrottleMode::Trailing)
+    }
+
+    #[inline]
+    pub fn trailing(&self) -> bool {
+        !matches!(*self, ThrottleMode::Leading)
+    }
+}
+
+pub struct ThrottleObservable<S, D> {
+    pub(crate) window: Duration,
+    pub(crate) mode: ThrottleMode,
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, D> Observable for ThrottleObservable<S, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(ThrottleObserver {
+            window: self.window,
+            mode: self.mode,
+            scheduler: self.scheduler.clone(),
+            state: Arc::new(Mutex::new(ThrottleState {
+                observer: observer,
+                pending: None,
+                open: false,
+                generation: 0,
+                stopped: false
+            }))
+        });
+    }
+}
+
+pub(crate) struct ThrottleState<A, N> {
+    pub(crate) observer: N,
+    pub(crate) pending: Option<A>,
+    pub(crate) open: bool,
+    pub(crate) generation: u64,
+    pub(crate) stopped: bool
+}
+
+impl<A, N> ThrottleState<A, N>
+    where N: Observer<Item=A> {
+    fn emit(&mut self, val: A) {
+        if !self.stopped {
+            if let Stop = self.observer.next(val) {
+                self.stopped = true;
+            }
+        }
+    }
+}
+
+pub(crate) struct ThrottleObserver<A, N, D> {
+    pub(crate) window: Duration,
+    pub(crate) mode: ThrottleMode,
+    pub(crate) scheduler: D,
+    pub(crate) state: Arc<Mutex<ThrottleState<A, N>>>
+}
+
+// Opens a window that, when it closes, releases the trailing item (if any) and
+// immediately opens the next window so trailing emissions stay rate limited.
+// The guard is let go before the timer is armed, as a scheduler that runs it
+// in place would otherwise find the lock still held.
+pub(crate) fn open_throttle_window<A, N, D>(shared: &Arc<Mutex<ThrottleState<A, N>>>, window: Duration, scheduler: &D, mut state: MutexGuard<'_, ThrottleState<A, N>>)
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler + Clone + 'static {
+    state.open = true;
+
+    let generation = state.generation;
+    drop(state);
+    let shared = shared.clone();
+    let next_scheduler = scheduler.clone();
+    scheduler.schedule_after(window, Box::new(move || {
+        let mut state = shared.acquire();
+        if state.generation != generation {
+            return;
+        }
+
+        state.open = false;
+        if let Some(val) = state.pending.take() {
+            state.emit(val);
+            open_throttle_window(&shared, window, &next_scheduler, state);
+        }
+    }));
+}
+
+impl<A, N, D> Observer for ThrottleObserver<A, N, D>
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut state = self.state.acquire();
+        if state.stopped {
+            return Stop;
+        }
+
+        if state.open {
+            if self.mode.trailing() {
+                state.pending = Some(val);
+            }
+            return Continue;
+        }
+
+        if self.mode.leading() {
+            state.emit(val);
+        } else {
+            state.pending = Some(val);
+        }
+        if state.stopped {
+            return Stop;
+        }
+        open_throttle_window(&self.state, self.window, &self.scheduler, state);
+        Continue
+    }
+
+    fn completed(&mut self) {
+        let mut state = self.state.acquire();
+        state.generation += 1;
+        if let Some(val) = state.pending.take() {
+            state.emit(val);
+        }
+        state.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        let mut state = self.state.acquire();
+        state.generation += 1;
+        state.pending = None;
+        state.observer.error(err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::operators::throttle::ThrottleMode;
+    use crate::schedulers::current_thread::CurrentThreadScheduler;
+    use crate::sources::range;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn leading_throttle_takes_the_first_of_each_window() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "abc---d|").throttle_with_on(Duration::from_millis(25), ThrottleMode::Leading, scheduler.clone());
+        assert_marbles(&scheduler, source, "a-----d|");
+    }
+
+    #[test]
+    fn trailing_throttle_takes_the_last_of_each_window() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "abc---d|").throttle_with_on(Duration::from_millis(25), ThrottleMode::Trailing, scheduler.clone());
+        assert_marbles(&scheduler, source, "--c----(d|)");
+    }
+
+    #[test]
+    fn throttle_can_take_both_ends() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "abc---d|").throttle_with_on(Duration::from_millis(25), ThrottleMode::Both, scheduler.clone());
+        assert_marbles(&scheduler, source, "a-c---d|");
+    }
+
+    #[test]
+    fn trailing_items_keep_the_window_open() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "abcdef----|").throttle_with_on(Duration::from_millis(25), ThrottleMode::Both, scheduler.clone());
+        assert_marbles(&scheduler, source, "a-c--f----|");
+    }
+
+    #[test]
+    fn throttle_runs_on_a_scheduler_that_runs_timers_in_place() {
+        let observer = TestObserver::new();
+        range(0, 3)
+            .throttle_with_on(Duration::from_millis(5), ThrottleMode::Both, CurrentThreadScheduler)
+            .subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+use std::marker::PhantomData;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::schedulers::Scheduler;
+
+#[derive(Clone, Debug)]
+pub struct TimeInterval<A> {
+    pub value: A,
+    pub interval: Duration
+}
+
+pub struct TimeIntervalObservable<S, D> {
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, D> Observable for TimeIntervalObservable<S, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = TimeInterval<S::Item>;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(TimeIntervalObserver {
+            last: self.scheduler.now(),
+            scheduler: self.scheduler.clone(),
+            observer: observer,
+            _marker: PhantomData
+        });
+    }
+}
+
+// The first item is measured from the moment of subscription
+pub(crate) struct TimeIntervalObserver<A, N, D> {
+    pub(crate) last: Instant,
+    pub(crate) scheduler: D,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, N, D> Observer for TimeIntervalObserver<A, N, D>
+    where N: Observer<Item=TimeInterval<A>>,
+          D: Scheduler {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let now = self.scheduler.now();
+        let interval = now - self.last;
+        self.last = now;
+        self.observer.next(TimeInterval {value: val, interval: interval})
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.error(err);
+    }
+}
@@ -0,0 +1,245 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::fmt;
+use std::error::Error as StdError;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+use crate::schedulers::Scheduler;
+use crate::schedulers::current_thread::trampolined;
+use crate::sync::MutexExt;
+
+#[derive(Debug)]
+pub struct TimeoutError {
+    pub(crate) duration: Duration
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no item arrived within {:?}", self.duration)
+    }
+}
+
+impl StdError for TimeoutError {}
+
+pub struct TimeoutObservable<S, U, D> {
+    pub(crate) duration: Duration,
+    pub(crate) scheduler: D,
+    pub(crate) source: S,
+    pub(crate) fallback: Arc<U>
+}
+
+impl<S, U, D> Observable for TimeoutObservable<S, U, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          U: Observable<Item=S::Item> + Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node(), self.fallback.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let state = Arc::new(Mutex::new(TimeoutState {
+            observer: observer,
+            generation: 0,
+            stopped: false,
+            terminated: false
+        }));
+        // On CurrentThreadScheduler the first timer would otherwise run out
+        // in place before the source is ever subscribed
+        trampolined(|| {
+            arm_timeout(state.clone(), self.fallback.clone(), self.duration, &self.scheduler, 0);
+            self.source.subscribe(TimeoutObserver {
+                duration: self.duration,
+                scheduler: self.scheduler.clone(),
+                state: state,
+                fallback: self.fallback.clone()
+            });
+        });
+    }
+}
+
+// The timer and the source race for the lock; whichever terminates the stream
+// first wins, and a timer armed for an older generation never fires
+pub(crate) struct TimeoutState<N> {
+    pub(crate) observer: N,
+    pub(crate) generation: u64,
+    pub(crate) stopped: bool,
+    pub(crate) terminated: bool
+}
+
+// On timeout the source is cut off and the downstream is handed to the fallback.
+// The lock is released first since the fallback may emit synchronously.
+pub(crate) fn arm_timeout<N, U, D>(shared: Arc<Mutex<TimeoutState<N>>>, fallback: Arc<U>, duration: Duration, scheduler: &D, generation: u64)
+    where N: Observer + Send + Sync + 'static,
+          N::Item: Send + Sync + 'static,
+          U: Observable<Item=N::Item> + Send + Sync + 'static,
+          D: Scheduler {
+    scheduler.schedule_after(duration, Box::new(move || {
+        {
+            let mut state = shared.acquire();
+            if state.generation != generation || state.terminated {
+                return;
+            }
+            state.terminated = true;
+        }
+
+        fallback.subscribe(FallbackObserver {state: shared});
+    }));
+}
+
+pub(crate) struct FallbackObserver<N> {
+    pub(crate) state: Arc<Mutex<TimeoutState<N>>>
+}
+
+impl<N> Observer for FallbackObserver<N>
+    where N: Observer {
+    type Item = N::Item;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.state.acquire().observer.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.state.acquire().observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.state.acquire().observer.error(err);
+    }
+}
+
+pub(crate) struct TimeoutObserver<N, U, D> {
+    pub(crate) duration: Duration,
+    pub(crate) scheduler: D,
+    pub(crate) state: Arc<Mutex<TimeoutState<N>>>,
+    pub(crate) fallback: Arc<U>
+}
+
+impl<N, U, D> Observer for TimeoutObserver<N, U, D>
+    where N: Observer + Send + Sync + 'static,
+          N::Item: Send + Sync + 'static,
+          U: Observable<Item=N::Item> + Send + Sync + 'static,
+          D: Scheduler {
+    type Item = N::Item;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let mut state = self.state.acquire();
+        if state.stopped || state.terminated {
+            return Stop;
+        }
+
+        state.generation += 1;
+        let result = state.observer.next(val);
+        if let Stop = result {
+            state.stopped = true;
+            return Stop;
+        }
+
+        // Rearmed once the lock's let go, as a scheduler that runs the timer
+        // in place would otherwise find it still held
+        let generation = state.generation;
+        drop(state);
+        arm_timeout(self.state.clone(), self.fallback.clone(), self.duration, &self.scheduler, generation);
+        result
+    }
+
+    fn completed(&mut self) {
+        let mut state = self.state.acquire();
+        state.generation += 1;
+        if !state.terminated {
+            state.terminated = true;
+            state.observer.completed();
+        }
+    }
+
+    fn error(&mut self, err: Error) {
+        let mut state = self.state.acquire();
+        state.generation += 1;
+        if !state.terminated {
+            state.terminated = true;
+            state.observer.error(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::operators::timeout::TimeoutError;
+    use crate::schedulers::current_thread::CurrentThreadScheduler;
+    use crate::sources::range;
+    use crate::subjects::Subject;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn timeout_lets_a_timely_source_through() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b-c|").timeout_on(Duration::from_millis(25), scheduler.clone());
+        assert_marbles(&scheduler, source, "a-b-c|");
+    }
+
+    #[test]
+    fn timeout_fails_after_a_gap() {
+        let scheduler = TestScheduler::new();
+        let observer = TestObserver::new();
+        cold(&scheduler, "a----b|").timeout_on(Duration::from_millis(25), scheduler.clone()).subscribe(observer.clone());
+        scheduler.advance_by(Duration::from_millis(100));
+        observer.assert_values(&['a']);
+
+        let errors = observer.errors();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].downcast_ref::<TimeoutError>().is_some());
+    }
+
+    #[test]
+    fn timeout_counts_from_the_subscription() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "---|").timeout_on(Duration::from_millis(15), scheduler.clone());
+        assert_marbles(&scheduler, source, "-#--");
+    }
+
+    #[test]
+    fn timeout_with_switches_to_the_fallback() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a----b|")
+            .timeout_with_on(Duration::from_millis(25), cold(&scheduler, "xy|"), scheduler.clone());
+        assert_marbles(&scheduler, source, "a-xy|---");
+    }
+
+    #[test]
+    fn each_item_rearms_the_timeout() {
+        let scheduler = TestScheduler::new();
+        let source = cold(&scheduler, "a-b-c-d---|").timeout_on(Duration::from_millis(25), scheduler.clone());
+        assert_marbles(&scheduler, source, "a-b-c-d-#");
+    }
+
+    // Only the timers are left once the synchronous source is done, and they
+    // find it already terminated
+    #[test]
+    fn timeout_subscribes_the_source_before_an_inline_timer_runs() {
+        let observer = TestObserver::new();
+        range(0, 3).timeout_on(Duration::from_millis(5), CurrentThreadScheduler).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+
+    // Nothing's pushed while the first timer is run out, so it fires
+    #[test]
+    fn timeout_fires_on_an_inline_scheduler() {
+        let subject = Subject::<i32>::new();
+        let observer = TestObserver::new();
+        subject.clone().timeout_on(Duration::from_millis(5), CurrentThreadScheduler).subscribe(observer.clone());
+        observer.assert_values(&[]);
+        observer.assert_error();
+    }
+}
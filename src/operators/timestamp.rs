@@ -0,0 +1,88 @@
+use std::time::Instant;
+use std::marker::PhantomData;
+
+use crate::describe::{Node, short_name};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::schedulers::Scheduler;
+
+// Times come from the scheduler's clock, so they can be compared with its
+// deadlines and are virtual under a TestScheduler
+#[derive(Clone, Debug)]
+pub struct Timestamped<A> {
+    pub value: A,
+    pub time: Instant
+}
+
+pub struct TimestampObservable<S, D> {
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, D> Observable for TimestampObservable<S, D>
+    where S: Observable,
+          S::Item: Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = Timestamped<S::Item>;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    #[inline]
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(TimestampObserver {scheduler: self.scheduler.clone(), observer: observer, _marker: PhantomData});
+    }
+}
+
+pub(crate) struct TimestampObserver<A, N, D> {
+    pub(crate) scheduler: D,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, N, D> Observer for TimestampObserver<A, N, D>
+    where N: Observer<Item=Timestamped<A>>,
+          D: Scheduler {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.observer.next(Timestamped {value: val, time: self.scheduler.now()})
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.error(err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::schedulers::Scheduler;
+    use crate::testing::marbles::cold;
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn timestamps_come_from_the_scheduler() {
+        let scheduler = TestScheduler::new();
+        let start = scheduler.now();
+        let observer = TestObserver::new().on(scheduler.clone());
+        cold(&scheduler, "a--b|").timestamp_on(scheduler.clone()).subscribe(observer.clone());
+        scheduler.advance_by(Duration::from_secs(1));
+
+        let times: Vec<Duration> = observer.values().iter().map(|stamped| stamped.time - start).collect();
+        assert_eq!(times, vec![Duration::from_millis(0), Duration::from_millis(30)]);
+
+        let arrivals: Vec<Duration> = observer.events().iter().map(|&(time, _)| time - start).collect();
+        assert_eq!(arrivals, vec![Duration::from_millis(0), Duration::from_millis(30), Duration::from_millis(40)]);
+    }
+}
@@ -0,0 +1,147 @@
+use core::marker::PhantomData;
+
+use crate::observer::{IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::operators::lift::{LiftObservable, Operator};
+
+pub type ZipIterObservable<I, S> = LiftObservable<S, ZipIterOperator<I>>;
+
+// Each subscription pairs against a fresh clone of the iterator, pulling one
+// value per item, and completes as soon as the iterator runs out
+pub struct ZipIterOperator<I> {
+    pub(crate) iter: I
+}
+
+impl<A, I, E> Operator<A, E> for ZipIterOperator<I>
+    where A: Send + Sync + 'static,
+          I: IntoIterator + Clone,
+          I::IntoIter: Send + Sync + 'static,
+          I::Item: Send + Sync + 'static {
+    type Item = (A, I::Item);
+    type Upstream<N> = ZipIterObserver<I::IntoIter, N, A>
+        where N: Observer<E, Item=(A, I::Item)> + Send + Sync + 'static;
+
+    fn call<N>(&self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=(A, I::Item)> + Send + Sync + 'static {
+        ZipIterObserver {iter: self.iter.clone().into_iter(), done: false, observer: observer, _marker: PhantomData}
+    }
+
+    fn call_owned<N>(self, observer: N) -> Self::Upstream<N>
+        where N: Observer<E, Item=(A, I::Item)> + Send + Sync + 'static {
+        ZipIterObserver {iter: self.iter.into_iter(), done: false, observer: observer, _marker: PhantomData}
+    }
+}
+
+pub struct ZipIterObserver<T, N, A> {
+    pub(crate) iter: T,
+    pub(crate) done: bool,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, T, N, E> Observer<E> for ZipIterObserver<T, N, A>
+    where T: Iterator,
+          N: Observer<E, Item=(A, T::Item)> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        if self.done {
+            return Stop;
+        }
+
+        match self.iter.next() {
+            Some(other) => match self.observer.next((val, other)) {
+                Stop => {
+                    self.done = true;
+                    Stop
+                },
+                Continue => Continue
+            },
+            None => {
+                self.completed();
+                Stop
+            }
+        }
+    }
+
+    fn completed(&mut self) {
+        if !self.done {
+            self.done = true;
+            self.observer.completed();
+        }
+    }
+
+    fn error(&mut self, err: E) {
+        if !self.done {
+            self.done = true;
+            self.observer.error(err);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::sources::range;
+    use crate::subjects::Subject;
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn zip_iter_pairs_each_item_with_the_next_value() {
+        let observer = TestObserver::new();
+        range(0, 3).zip_iter(vec!['a', 'b', 'c', 'd']).subscribe(observer.clone());
+        observer.assert_values(&[(0, 'a'), (1, 'b'), (2, 'c')]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn zip_iter_only_pulls_as_items_arrive() {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let counter = pulled.clone();
+        let mut subject = Subject::new();
+        let observer = TestObserver::new();
+        let source = subject.clone().zip_iter((0..).inspect(move |_| { counter.fetch_add(1, Ordering::SeqCst); }));
+        assert_eq!(pulled.load(Ordering::SeqCst), 0);
+
+        source.subscribe(observer.clone());
+        assert_eq!(pulled.load(Ordering::SeqCst), 0);
+        subject.next('a');
+        subject.next('b');
+        assert_eq!(pulled.load(Ordering::SeqCst), 2);
+        observer.assert_values(&[('a', 0), ('b', 1)]);
+    }
+
+    #[test]
+    fn zip_iter_completes_and_stops_the_source_once_the_iterator_runs_out() {
+        let emitted = Arc::new(AtomicUsize::new(0));
+        let counter = emitted.clone();
+        let observer = TestObserver::new();
+        range(0, i32::MAX)
+            .tap(move |_| { counter.fetch_add(1, Ordering::SeqCst); })
+            .zip_iter(vec!['a', 'b'])
+            .subscribe(observer.clone());
+        observer.assert_values(&[(0, 'a'), (1, 'b')]);
+        assert_eq!(observer.completion_count(), 1);
+        assert_eq!(emitted.load(Ordering::SeqCst), 3);
+
+        // Running out is only found by the pull for the next item
+        let scheduler = TestScheduler::new();
+        assert_marbles(&scheduler, cold(&scheduler, "ab-c|").zip_iter(0..2).map(|(val, _)| val), "ab-|");
+    }
+
+    #[test]
+    fn zip_iter_starts_over_for_every_subscription() {
+        let source = range(0, 2).zip_iter(vec!['x', 'y', 'z']);
+        for _ in 0..2 {
+            let observer = TestObserver::new();
+            source.subscribe(observer.clone());
+            observer.assert_values(&[(0, 'x'), (1, 'y')]);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use alloc::sync::Arc;
+
+use crate::operators::filter::{FilterObservable, FilterOperator};
+use crate::operators::lift::LiftObservable;
+use crate::operators::map::{MapObservable, MapOperator};
+use crate::operators::take::{TakeObservable, TakeOperator};
+
+// Operators as values, so a chain can be named once and applied to any number
+// of sources with Observable::pipe. Tuples apply their elements left to right:
+//
+//     let evens = (filter(|x: &i32| x % 2 == 0), take(3));
+//     range(0, 10).pipe(&evens).subscribe_fn(|x| println!("{}", x));
+pub trait Pipeable<S> {
+    type Output;
+
+    fn apply(&self, source: S) -> Self::Output;
+}
+
+impl<S, P> Pipeable<S> for &P
+    where P: Pipeable<S> {
+    type Output = P::Output;
+
+    #[inline]
+    fn apply(&self, source: S) -> Self::Output {
+        (**self).apply(source)
+    }
+}
+
+macro_rules! impl_pipeable_tuple {
+    ($($v:ident: $T:ident => $In:ty),+; $last:ident) => {
+        impl<S, $($T),+> Pipeable<S> for ($($T,)+)
+            where $($T: Pipeable<$In>),+ {
+            type Output = $last::Output;
+
+            #[inline]
+            fn apply(&self, source: S) -> Self::Output {
+                let ($(ref $v,)+) = *self;
+                $(let source = $v.apply(source);)+
+                source
+            }
+        }
+    }
+}
+
+impl_pipeable_tuple!(a: A => S; A);
+impl_pipeable_tuple!(a: A => S, b: B => A::Output; B);
+impl_pipeable_tuple!(a: A => S, b: B => A::Output, c: C => B::Output; C);
+impl_pipeable_tuple!(a: A => S, b: B => A::Output, c: C => B::Output, d: D => C::Output; D);
+impl_pipeable_tuple!(a: A => S, b: B => A::Output, c: C => B::Output, d: D => C::Output, e: E => D::Output; E);
+impl_pipeable_tuple!(a: A => S, b: B => A::Output, c: C => B::Output, d: D => C::Output, e: E => D::Output, f: F => E::Output; F);
+impl_pipeable_tuple!(a: A => S, b: B => A::Output, c: C => B::Output, d: D => C::Output, e: E => D::Output, f: F => E::Output, g: G => F::Output; G);
+impl_pipeable_tuple!(a: A => S, b: B => A::Output, c: C => B::Output, d: D => C::Output, e: E => D::Output, f: F => E::Output, g: G => F::Output, h: H => G::Output; H);
+
+// Wraps any function of an observable, for operators that only exist as
+// methods: from_fn(|s| s.delay(Duration::from_millis(10)))
+pub struct FnPipe<F> {
+    pub(crate) f: F
+}
+
+pub fn from_fn<F>(f: F) -> FnPipe<F> {
+    FnPipe {f: f}
+}
+
+impl<S, U, F> Pipeable<S> for FnPipe<F>
+    where F: Fn(S) -> U {
+    type Output = U;
+
+    #[inline]
+    fn apply(&self, source: S) -> Self::Output {
+        (self.f)(source)
+    }
+}
+
+pub fn map<F>(f: F) -> MapOperator<F> {
+    MapOperator {f: Arc::new(f)}
+}
+
+pub fn take(count: usize) -> TakeOperator {
+    TakeOperator {count: count}
+}
+
+pub fn filter<P>(predicate: P) -> FilterOperator<P> {
+    FilterOperator {predicate: Arc::new(predicate)}
+}
+
+impl<S, F> Pipeable<S> for MapOperator<F> {
+    type Output = MapObservable<F, S>;
+
+    #[inline]
+    fn apply(&self, source: S) -> Self::Output {
+        LiftObservable {operator: MapOperator {f: self.f.clone()}, source: source}
+    }
+}
+
+impl<S> Pipeable<S> for TakeOperator {
+    type Output = TakeObservable<S>;
+
+    #[inline]
+    fn apply(&self, source: S) -> Self::Output {
+        LiftObservable {operator: TakeOperator {count: self.count}, source: source}
+    }
+}
+
+impl<S, P> Pipeable<S> for FilterOperator<P> {
+    type Output = FilterObservable<P, S>;
+
+    #[inline]
+    fn apply(&self, source: S) -> Self::Output {
+        LiftObservable {operator: FilterOperator {predicate: self.predicate.clone()}, source: source}
+    }
+}
@@ -0,0 +1,71 @@
+// Everything needed to build and subscribe to pipelines, for glob importing
+pub use crate::observer::{Observer, IterationResult, Error, BoxObserver, FnObserver, WeakObserver};
+#[cfg(feature = "std")]
+pub use crate::observer::DynObserver;
+pub use crate::observer::IterationResult::{Stop, Continue};
+pub use crate::observable::{Observable, DynObservable, BoxObservable, Subscription};
+pub use crate::local::LocalObservable;
+pub use crate::borrowed::{RefObserver, RefObservable};
+pub use crate::pull::Pull;
+pub use crate::describe::Node;
+#[cfg(feature = "std")]
+pub use crate::flow::{Flowable, Subscriber, FlowSubscription};
+pub use crate::sources::{range, value, from_iter, start, throw, never, using};
+#[cfg(feature = "std")]
+pub use crate::sources::{create, Emitter, interval, interval_on, timer, timer_on, timer_periodic, timer_periodic_on, start_on};
+#[cfg(feature = "std")]
+pub use crate::blocking::{from_receiver, from_receiver_on, from_lines, from_lines_on, stdin_lines};
+#[cfg(feature = "std")]
+pub use crate::net::{Connection, tcp_listen, tcp_listen_on};
+#[cfg(feature = "std")]
+pub use crate::operators::concat::concat_iter;
+#[cfg(all(feature = "std", unix))]
+pub use crate::signal::{Signal, signals, ctrl_c};
+#[cfg(feature = "watch")]
+pub use crate::watch::{FsEvent, FsEventKind, watch_path, watch_path_on};
+#[cfg(feature = "crossbeam")]
+pub use crate::crossbeam::{from_crossbeam, from_crossbeam_on, select_sources, select_sources_on};
+#[cfg(feature = "serde")]
+pub use crate::json::from_json_lines;
+#[cfg(feature = "serde")]
+pub use crate::record::{replay_from, replay_from_on};
+#[cfg(feature = "std")]
+pub use crate::subjects::{Subject, BehaviorSubject, ReplaySubject, AsyncSubject};
+#[cfg(feature = "std")]
+pub use crate::schedulers::{Scheduler, TimerScheduler};
+#[cfg(feature = "std")]
+pub use crate::schedulers::thread_pool::ThreadPoolScheduler;
+#[cfg(feature = "std")]
+pub use crate::schedulers::new_thread::NewThreadScheduler;
+#[cfg(feature = "std")]
+pub use crate::schedulers::current_thread::CurrentThreadScheduler;
+#[cfg(feature = "tokio")]
+pub use crate::schedulers::tokio::TokioScheduler;
+pub use crate::operators::lift::Operator;
+pub use crate::pipe::Pipeable;
+pub use crate::try_observable::TryObservable;
+#[cfg(feature = "std")]
+pub use crate::operators::backpressure::Overflow;
+pub use crate::operators::materialize::Notification;
+#[cfg(feature = "std")]
+pub use crate::operators::catch_panics::PanicPolicy;
+#[cfg(feature = "std")]
+pub use crate::operators::throttle::ThrottleMode;
+#[cfg(feature = "std")]
+pub use crate::operators::rate_limit::{RateLimit, RateLimitMode};
+#[cfg(feature = "std")]
+pub use crate::operators::reorder::LatePolicy;
+#[cfg(feature = "std")]
+pub use crate::operators::scan_shared::StateHandle;
+#[cfg(feature = "std")]
+pub use crate::operators::take_until::DropGuard;
+#[cfg(feature = "rayon")]
+pub use crate::operators::par_map::ParOrder;
+#[cfg(feature = "std")]
+pub use crate::operators::retry::{BackoffPolicy, FixedBackoff, ExponentialBackoff};
+#[cfg(feature = "futures")]
+pub use crate::stream::from_stream;
+#[cfg(feature = "futures")]
+pub use crate::future::from_future;
+#[cfg(feature = "futures")]
+pub use crate::async_observer::{AsyncObserver, AsyncFnObserver};
@@ -0,0 +1,295 @@
+use alloc::sync::Arc;
+use core::iter::{self, Once, Take};
+use core::mem;
+
+use crate::observer::Observer;
+use crate::observer::IterationResult::Stop;
+use crate::operators::filter::FilterOperator;
+use crate::operators::filter_map::FilterMapOperator;
+use crate::operators::lift::LiftObservable;
+use crate::operators::map::MapOperator;
+use crate::operators::take::TakeOperator;
+use crate::sources::{IterObservable, RangeObservable, Step, ValueObservable};
+
+// A chain that's synchronous from end to end (range, from_iter or value,
+// then map, filter, filter_map and take) lowered to the iterator it amounts
+// to. Pulling from that is a plain loop the optimizer can see all the way
+// through, with none of the stopping and completion bookkeeping of pushing
+// through observers, which is as close as this gets to std::iter.
+pub trait Pull {
+    type Item;
+    type Iter: Iterator<Item=Self::Item>;
+
+    fn pull(&self) -> Self::Iter;
+
+    #[inline]
+    fn fold<B, F>(&self, init: B, f: F) -> B
+        where F: FnMut(B, Self::Item) -> B {
+        self.pull().fold(init, f)
+    }
+
+    // Drives an observer with a pull loop, for when it's the observer that's
+    // wanted rather than the values
+    #[inline]
+    fn run<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> {
+        for val in self.pull() {
+            if let Stop = observer.next(val) {
+                break;
+            }
+        }
+        observer.completed();
+    }
+}
+
+//////////////Sources
+pub struct StepIter<A> {
+    pub(crate) next: A,
+    pub(crate) end: A
+}
+
+impl<A> Iterator for StepIter<A>
+    where A: Step {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self) -> Option<A> {
+        if self.next >= self.end {
+            return None;
+        }
+        let next = self.next.successor();
+        Some(mem::replace(&mut self.next, next))
+    }
+}
+
+impl<A> Pull for RangeObservable<A>
+    where A: Step {
+    type Item = A;
+    type Iter = StepIter<A>;
+
+    #[inline]
+    fn pull(&self) -> StepIter<A> {
+        StepIter {next: self.start.clone(), end: self.end.clone()}
+    }
+}
+
+impl<I> Pull for IterObservable<I>
+    where I: IntoIterator + Clone {
+    type Item = I::Item;
+    type Iter = I::IntoIter;
+
+    #[inline]
+    fn pull(&self) -> I::IntoIter {
+        self.iter.clone().into_iter()
+    }
+}
+
+impl<A> Pull for ValueObservable<A>
+    where A: Clone {
+    type Item = A;
+    type Iter = Once<A>;
+
+    #[inline]
+    fn pull(&self) -> Once<A> {
+        iter::once(self.value.clone())
+    }
+}
+//Sources//////////////
+
+//////////////Operators
+pub struct PullMap<I, F> {
+    pub(crate) iter: I,
+    pub(crate) f: Arc<F>
+}
+
+impl<B, I, F> Iterator for PullMap<I, F>
+    where I: Iterator,
+          F: Fn(I::Item) -> B {
+    type Item = B;
+
+    #[inline]
+    fn next(&mut self) -> Option<B> {
+        self.iter.next().map(&*self.f)
+    }
+
+    #[inline]
+    fn fold<C, G>(self, init: C, mut g: G) -> C
+        where G: FnMut(C, B) -> C {
+        let f = self.f;
+        self.iter.fold(init, move |acc, val| g(acc, f(val)))
+    }
+}
+
+impl<B, S, F> Pull for LiftObservable<S, MapOperator<F>>
+    where S: Pull,
+          F: Fn(S::Item) -> B {
+    type Item = B;
+    type Iter = PullMap<S::Iter, F>;
+
+    #[inline]
+    fn pull(&self) -> Self::Iter {
+        PullMap {iter: self.source.pull(), f: self.operator.f.clone()}
+    }
+}
+
+pub struct PullFilter<I, P> {
+    pub(crate) iter: I,
+    pub(crate) predicate: Arc<P>
+}
+
+impl<I, P> Iterator for PullFilter<I, P>
+    where I: Iterator,
+          P: Fn(&I::Item) -> bool {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        let predicate = &*self.predicate;
+        self.iter.find(|val| predicate(val))
+    }
+
+    #[inline]
+    fn fold<C, G>(self, init: C, mut g: G) -> C
+        where G: FnMut(C, I::Item) -> C {
+        let predicate = self.predicate;
+        self.iter.fold(init, move |acc, val| if predicate(&val) { g(acc, val) } else { acc })
+    }
+}
+
+impl<S, P> Pull for LiftObservable<S, FilterOperator<P>>
+    where S: Pull,
+          P: Fn(&S::Item) -> bool {
+    type Item = S::Item;
+    type Iter = PullFilter<S::Iter, P>;
+
+    #[inline]
+    fn pull(&self) -> Self::Iter {
+        PullFilter {iter: self.source.pull(), predicate: self.operator.predicate.clone()}
+    }
+}
+
+pub struct PullFilterMap<I, F> {
+    pub(crate) iter: I,
+    pub(crate) f: Arc<F>
+}
+
+impl<B, I, F> Iterator for PullFilterMap<I, F>
+    where I: Iterator,
+          F: Fn(I::Item) -> Option<B> {
+    type Item = B;
+
+    #[inline]
+    fn next(&mut self) -> Option<B> {
+        let f = &*self.f;
+        self.iter.find_map(f)
+    }
+
+    #[inline]
+    fn fold<C, G>(self, init: C, mut g: G) -> C
+        where G: FnMut(C, B) -> C {
+        let f = self.f;
+        self.iter.fold(init, move |acc, val| match f(val) {
+            Some(val) => g(acc, val),
+            None => acc
+        })
+    }
+}
+
+impl<B, S, F> Pull for LiftObservable<S, FilterMapOperator<F>>
+    where S: Pull,
+          F: Fn(S::Item) -> Option<B> {
+    type Item = B;
+    type Iter = PullFilterMap<S::Iter, F>;
+
+    #[inline]
+    fn pull(&self) -> Self::Iter {
+        PullFilterMap {iter: self.source.pull(), f: self.operator.f.clone()}
+    }
+}
+
+impl<S> Pull for LiftObservable<S, TakeOperator>
+    where S: Pull {
+    type Item = S::Item;
+    type Iter = Take<S::Iter>;
+
+    #[inline]
+    fn pull(&self) -> Self::Iter {
+        self.source.pull().take(self.operator.count)
+    }
+}
+//Operators//////////////
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::fmt::Debug;
+    use std::marker::PhantomData;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::observable::Observable;
+    use crate::operators::take::TakeObserver;
+    use crate::pull::Pull;
+    use crate::sources::{from_iter, range, value};
+    use crate::testing::observer::TestObserver;
+
+    // Pulling a chain should see just what subscribing to it does
+    fn assert_pulls_as_pushed<S>(source: S)
+        where S: Observable + Pull<Item=<S as Observable>::Item>,
+              <S as Observable>::Item: Clone + PartialEq + Debug + Send + 'static {
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        observer.assert_completed();
+        assert_eq!(source.pull().collect::<Vec<_>>(), observer.values());
+    }
+
+    #[test]
+    fn pulled_sources_match_subscribed_ones() {
+        assert_pulls_as_pushed(range(0, 5));
+        assert_pulls_as_pushed(from_iter(vec![3, 1, 2]));
+        assert_pulls_as_pushed(value(9));
+    }
+
+    #[test]
+    fn pulled_chains_match_subscribed_ones() {
+        assert_pulls_as_pushed(range(0, 50).map(|val| val * 3).filter(|val| val % 2 == 0).take(5));
+        assert_pulls_as_pushed(Observable::filter(Observable::map(range(0, 50), |val| val + 1), |val| val % 7 == 0));
+        assert_pulls_as_pushed(from_iter(vec!["1", "x", "3"]).filter_map(|val| val.parse::<i32>().ok()).map(|val| val * 2));
+        assert_pulls_as_pushed(range(0, 10).take(0));
+    }
+
+    #[test]
+    fn fold_matches_the_iterator() {
+        let source = range(0, 100).filter(|val| val % 3 == 0).map(|val| val * 2);
+        assert_eq!(source.fold(0, |acc, val| acc + val), source.pull().sum::<i32>());
+        assert_eq!(source.fold(0, |acc, val| acc + val), (0..100).filter(|val| val % 3 == 0).map(|val| val * 2).sum::<i32>());
+    }
+
+    #[test]
+    fn take_stops_pulling_from_upstream() {
+        let mapped = Arc::new(AtomicUsize::new(0));
+        let counter = mapped.clone();
+        let source = range(0, i32::MAX).map(move |val| { counter.fetch_add(1, Ordering::SeqCst); val }).take(3);
+        assert_eq!(source.pull().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(mapped.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn each_pull_starts_over() {
+        let source = from_iter(vec![1, 2, 3]).map(|val| val * 10);
+        assert_eq!(source.pull().collect::<Vec<_>>(), vec![10, 20, 30]);
+        assert_eq!(source.pull().collect::<Vec<_>>(), vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn run_drives_an_observer_until_it_stops() {
+        let observer = TestObserver::new();
+        range(0, 10).map(|val| val + 1).run(observer.clone());
+        assert_eq!(observer.value_count(), 10);
+        observer.assert_completed();
+
+        let observer = TestObserver::new();
+        range(0, i32::MAX).run(TakeObserver {remaining: 3, done: false, observer: observer.clone(), _marker: PhantomData});
+        observer.assert_values(&[0, 1, 2]);
+        assert_eq!(observer.completion_count(), 1);
+    }
+}
@@ -0,0 +1,349 @@
+use std::io::{BufRead, Write};
+use std::iter::Peekable;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::vec;
+
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+
+use crate::describe::{Node, short_name};
+use crate::json::RecordedError;
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+use crate::operators::materialize::Notification;
+use crate::schedulers::{Scheduler, TimerScheduler};
+use crate::sync::MutexExt;
+
+// One line of a recording: a notification and how long after subscription
+// it arrived. Errors are kept as their message.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Recorded<A> {
+    pub offset: Duration,
+    pub notification: Notification<A, String>
+}
+
+//////////////Record
+// Passes the stream through unchanged while writing it to the sink as JSON
+// lines. A sink holds one recording, so only the first subscription is
+// recorded and later ones just pass through. If a write fails the recording
+// stops there but the stream carries on.
+pub struct RecordObservable<S, W, D> {
+    pub(crate) sink: Mutex<Option<W>>,
+    pub(crate) scheduler: D,
+    pub(crate) source: S
+}
+
+impl<S, W, D> Observable for RecordObservable<S, W, D>
+    where S: Observable,
+          S::Item: Serialize + Send + Sync + 'static,
+          W: Write + Send + Sync + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = S::Item;
+
+    fn node(&self) -> Node {
+        Node::new(short_name::<Self>(), [self.source.node()])
+    }
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.source.subscribe(RecordObserver {
+            sink: self.sink.acquire().take(),
+            start: self.scheduler.now(),
+            scheduler: self.scheduler.clone(),
+            observer: observer,
+            _marker: PhantomData
+        });
+    }
+}
+
+pub(crate) struct RecordObserver<A, W, N, D> {
+    pub(crate) sink: Option<W>,
+    pub(crate) start: Instant,
+    pub(crate) scheduler: D,
+    pub(crate) observer: N,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A, W, N, D> RecordObserver<A, W, N, D>
+    where A: Serialize,
+          W: Write,
+          D: Scheduler {
+    fn write(&mut self, notification: Notification<&A, String>, last: bool) {
+        let offset = self.scheduler.now().saturating_duration_since(self.start);
+        let written = match self.sink {
+            Some(ref mut sink) => {
+                serde_json::to_writer(&mut *sink, &Recorded {offset: offset, notification: notification}).is_ok() &&
+                    sink.write_all(b"\n").is_ok() &&
+                    (!last || sink.flush().is_ok())
+            },
+            None => return
+        };
+
+        if !written || last {
+            self.sink.take();
+        }
+    }
+}
+
+impl<A, W, N, D> Observer for RecordObserver<A, W, N, D>
+    where A: Serialize,
+          W: Write,
+          N: Observer<Item=A>,
+          D: Scheduler {
+    type Item = A;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.write(Notification::Next(&val), false);
+        self.observer.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.write(Notification::Completed, true);
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.write(Notification::Error(err.to_string()), true);
+        self.observer.error(err);
+    }
+}
+//Record//////////////
+
+//////////////ReplayFrom
+// Plays a recording back on the scheduler, each notification at its
+// original offset from subscription times the scale. With a TestScheduler a
+// captured stream replays the same way every time. The recording is read in
+// full when subscribed; a line that won't read or parse fails the stream at
+// that point, and a recording that stops without a terminal line completes.
+// As with from_json_lines, later subscribers just see it complete.
+pub struct ReplayObservable<R, A, D> {
+    pub(crate) reader: Mutex<Option<R>>,
+    pub(crate) scale: f64,
+    pub(crate) scheduler: D,
+    pub(crate) _marker: PhantomData<fn() -> A>
+}
+
+impl<R, A, D> ReplayObservable<R, A, D> {
+    // 0.5 plays back twice as fast, 0.0 as fast as the scheduler will go
+    #[inline]
+    pub fn scaled(self, scale: f64) -> ReplayObservable<R, A, D> {
+        ReplayObservable {scale: scale, ..self}
+    }
+}
+
+impl<R, A, D> Observable for ReplayObservable<R, A, D>
+    where R: BufRead,
+          A: DeserializeOwned + Send + 'static,
+          D: Scheduler + Clone + 'static {
+    type Item = A;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let reader = match self.reader.acquire().take() {
+            Some(reader) => reader,
+            None => return observer.completed()
+        };
+
+        let records = read_recording(reader, self.scale);
+        let start = self.scheduler.now();
+        replay_next(records.into_iter().peekable(), start, self.scheduler.clone(), observer);
+    }
+}
+
+fn read_recording<A, R>(reader: R, scale: f64) -> Vec<(Duration, Notification<A, Error>)>
+    where R: BufRead,
+          A: DeserializeOwned {
+    let mut records = Vec::new();
+    let mut offset = Duration::from_secs(0);
+
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                records.push((offset, Notification::Error(Arc::new(err) as Error)));
+                return records;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let recorded: Recorded<A> = match serde_json::from_str(&line) {
+            Ok(recorded) => recorded,
+            Err(err) => {
+                records.push((offset, Notification::Error(Arc::new(err) as Error)));
+                return records;
+            }
+        };
+        offset = recorded.offset.mul_f64(scale);
+
+        let notification = match recorded.notification {
+            Notification::Next(val) => Notification::Next(val),
+            Notification::Error(message) => Notification::Error(Arc::new(RecordedError(message)) as Error),
+            Notification::Completed => Notification::Completed
+        };
+        let last = !matches!(notification, Notification::Next(_));
+        records.push((offset, notification));
+        if last {
+            return records;
+        }
+    }
+
+    records.push((offset, Notification::Completed));
+    records
+}
+
+// Delivers everything that's come due, then schedules itself for the next
+// record
+fn replay_next<A, N, D>(mut records: Peekable<vec::IntoIter<(Duration, Notification<A, Error>)>>, start: Instant, scheduler: D, mut observer: N)
+    where A: Send + 'static,
+          N: Observer<Item=A> + Send + 'static,
+          D: Scheduler + Clone + 'static {
+    loop {
+        let due = match records.peek() {
+            Some(&(offset, _)) => start + offset,
+            None => return
+        };
+        let now = scheduler.now();
+        if due > now {
+            let next = scheduler.clone();
+            return scheduler.schedule_after(due - now, Box::new(move || replay_next(records, start, next, observer)));
+        }
+
+        match records.next() {
+            Some((_, Notification::Next(val))) => {
+                if let Stop = observer.next(val) {
+                    return;
+                }
+            },
+            Some((_, Notification::Error(err))) => return observer.error(err),
+            Some((_, Notification::Completed)) => return observer.completed(),
+            None => return
+        }
+    }
+}
+
+pub fn replay_from<A, R>(reader: R) -> ReplayObservable<R, A, TimerScheduler>
+    where R: BufRead,
+          A: DeserializeOwned + Send + 'static {
+    replay_from_on(reader, TimerScheduler)
+}
+
+pub fn replay_from_on<A, R, D>(reader: R, scheduler: D) -> ReplayObservable<R, A, D>
+    where R: BufRead,
+          A: DeserializeOwned + Send + 'static,
+          D: Scheduler + Clone + 'static {
+    ReplayObservable {reader: Mutex::new(Some(reader)), scale: 1.0, scheduler: scheduler, _marker: PhantomData}
+}
+//ReplayFrom//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    use crate::json::RecordedError;
+    use crate::json::tests::SharedBuffer;
+    use crate::observable::Observable;
+    use crate::operators::materialize::Notification;
+    use crate::record::{replay_from_on, Recorded};
+    use crate::testing::marbles::{assert_marbles, cold};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    fn recording(buffer: &SharedBuffer) -> Vec<Recorded<char>> {
+        buffer.text().lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn record_passes_the_stream_through_and_writes_its_timing() {
+        let scheduler = TestScheduler::new();
+        let buffer = SharedBuffer::default();
+        let source = cold(&scheduler, "a-b--|").record_on(buffer.clone(), scheduler.clone());
+        assert_marbles(&scheduler, source, "a-b--|");
+
+        assert_eq!(recording(&buffer), vec![
+            Recorded {offset: ms(0), notification: Notification::Next('a')},
+            Recorded {offset: ms(20), notification: Notification::Next('b')},
+            Recorded {offset: ms(50), notification: Notification::Completed}
+        ]);
+    }
+
+    #[test]
+    fn record_keeps_errors_as_their_message() {
+        let scheduler = TestScheduler::new();
+        let buffer = SharedBuffer::default();
+        let source = cold(&scheduler, "a#").record_on(buffer.clone(), scheduler.clone());
+        assert_marbles(&scheduler, source, "a#");
+        assert_eq!(recording(&buffer)[1], Recorded {offset: ms(10), notification: Notification::Error("marble error".to_string())});
+    }
+
+    #[test]
+    fn only_the_first_subscription_is_recorded() {
+        let scheduler = TestScheduler::new();
+        let buffer = SharedBuffer::default();
+        let source = cold(&scheduler, "ab|").record_on(buffer.clone(), scheduler.clone());
+        let first = TestObserver::new();
+        let second = TestObserver::new();
+        source.subscribe(first.clone());
+        source.subscribe(second.clone());
+        scheduler.advance_by(ms(100));
+        first.assert_values(&['a', 'b']);
+        second.assert_values(&['a', 'b']);
+        assert_eq!(recording(&buffer).len(), 3);
+    }
+
+    #[test]
+    fn replay_from_plays_a_recording_back_at_its_original_timing() {
+        let scheduler = TestScheduler::new();
+        let buffer = SharedBuffer::default();
+        assert_marbles(&scheduler, cold(&scheduler, "a-b--|").record_on(buffer.clone(), scheduler.clone()), "a-b--|");
+
+        let replay = replay_from_on::<char, _, _>(Cursor::new(buffer.text()), scheduler.clone());
+        assert_marbles(&scheduler, replay, "a-b--|");
+    }
+
+    #[test]
+    fn replay_from_scales_the_timing() {
+        let scheduler = TestScheduler::new();
+        let buffer = SharedBuffer::default();
+        assert_marbles(&scheduler, cold(&scheduler, "a-b--|").record_on(buffer.clone(), scheduler.clone()), "a-b--|");
+
+        let slower = replay_from_on::<char, _, _>(Cursor::new(buffer.text()), scheduler.clone()).scaled(2.0);
+        assert_marbles(&scheduler, slower, "a---b-----|");
+        let instant = replay_from_on::<char, _, _>(Cursor::new(buffer.text()), scheduler.clone()).scaled(0.0);
+        assert_marbles(&scheduler, instant, "(ab|)");
+    }
+
+    #[test]
+    fn replay_from_brings_back_a_recorded_error() {
+        let scheduler = TestScheduler::new();
+        let buffer = SharedBuffer::default();
+        assert_marbles(&scheduler, cold(&scheduler, "a-#").record_on(buffer.clone(), scheduler.clone()), "a-#");
+
+        let observer = TestObserver::<char>::new();
+        replay_from_on(Cursor::new(buffer.text()), scheduler.clone()).subscribe(observer.clone());
+        scheduler.advance_by(ms(100));
+        observer.assert_values(&['a']);
+        assert!(observer.errors()[0].downcast_ref::<RecordedError>().is_some());
+    }
+
+    #[test]
+    fn replay_from_fails_at_a_bad_line() {
+        let scheduler = TestScheduler::new();
+        let text = "{\"offset\":{\"secs\":0,\"nanos\":0},\"notification\":{\"Next\":\"a\"}}\nnot json\n";
+        let observer = TestObserver::<char>::new();
+        replay_from_on(Cursor::new(text), scheduler.clone()).subscribe(observer.clone());
+        scheduler.advance_by(ms(100));
+        observer.assert_values(&['a']);
+        observer.assert_error();
+    }
+}
@@ -0,0 +1,126 @@
+use std::thread;
+use std::time::{Duration, Instant};
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+use std::mem;
+use std::cell::RefCell;
+
+use crate::observer::IterationResult;
+use crate::observer::IterationResult::Continue;
+use crate::schedulers::{Scheduler, TimerEntry};
+use crate::schedulers::thread_pool::Task;
+
+pub(crate) struct Trampoline {
+    pub(crate) entries: BinaryHeap<Reverse<TimerEntry>>,
+    pub(crate) seq: u64,
+    pub(crate) running: bool
+}
+
+thread_local! {
+    static TRAMPOLINE: RefCell<Trampoline> = const { RefCell::new(Trampoline {
+        entries: BinaryHeap::new(),
+        seq: 0,
+        running: false
+    }) };
+}
+
+// Runs work on the thread that scheduled it. Work scheduled from inside a
+// task is queued rather than run in place, and the outermost call drains the
+// queue, so recursive scheduling runs in order without growing the stack.
+// Delays are slept out on the calling thread.
+#[derive(Clone, Copy)]
+pub struct CurrentThreadScheduler;
+
+// Lets the thread take a fresh run at its queue even if a task panics
+pub(crate) struct TrampolineGuard;
+
+impl Drop for TrampolineGuard {
+    fn drop(&mut self) {
+        TRAMPOLINE.with(|trampoline| trampoline.borrow_mut().running = false);
+    }
+}
+
+pub(crate) fn run_trampolined(deadline: Instant, task: Task) {
+    let drain = TRAMPOLINE.with(|trampoline| {
+        let mut trampoline = trampoline.borrow_mut();
+        let seq = trampoline.seq;
+        trampoline.seq += 1;
+        trampoline.entries.push(Reverse(TimerEntry {deadline: deadline, seq: seq, task: task}));
+        !mem::replace(&mut trampoline.running, true)
+    });
+    if !drain {
+        return;
+    }
+
+    let _guard = TrampolineGuard;
+    drain_trampoline();
+}
+
+// Runs what's queued on the thread's trampoline until there's nothing left.
+// Besides the outermost call, a task can call it to get at work queued
+// behind it that it has to wait on.
+pub(crate) fn drain_trampoline() {
+    loop {
+        let entry = match TRAMPOLINE.with(|trampoline| trampoline.borrow_mut().entries.pop()) {
+            Some(entry) => entry.0,
+            None => return
+        };
+
+        let now = Instant::now();
+        if entry.deadline > now {
+            thread::sleep(entry.deadline - now);
+        }
+        (entry.task)();
+    }
+}
+
+// Runs f as if it were a task on the thread's trampoline, so anything it
+// schedules on CurrentThreadScheduler waits until it's returned rather than
+// running in place. An operator that arms a timer and then subscribes its
+// source can use it to get the source subscribed before the timer runs.
+pub(crate) fn trampolined<F>(f: F)
+    where F: FnOnce() {
+    let outermost = TRAMPOLINE.with(|trampoline| !mem::replace(&mut trampoline.borrow_mut().running, true));
+    if !outermost {
+        return f();
+    }
+
+    let _guard = TrampolineGuard;
+    f();
+    drain_trampoline();
+}
+
+// Whether the thread's trampoline is draining, and how many tasks it's been
+// given so far, so a caller can tell if something it did queued one here
+pub(crate) fn trampoline_mark() -> (bool, u64) {
+    TRAMPOLINE.with(|trampoline| {
+        let trampoline = trampoline.borrow();
+        (trampoline.running, trampoline.seq)
+    })
+}
+
+pub(crate) fn trampoline_tick(deadline: Instant, period: Duration, mut task: Box<dyn FnMut() -> IterationResult + Send>) {
+    run_trampolined(deadline, Box::new(move || {
+        if let Continue = task() {
+            trampoline_tick(deadline + period, period, task);
+        }
+    }));
+}
+
+impl Scheduler for CurrentThreadScheduler {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn schedule(&self, task: Box<dyn FnOnce() + Send>) {
+        run_trampolined(Instant::now(), task);
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Box<dyn FnOnce() + Send>) {
+        run_trampolined(Instant::now() + delay, task);
+    }
+
+    fn schedule_periodic(&self, delay: Duration, period: Duration, task: Box<dyn FnMut() -> IterationResult + Send>) {
+        trampoline_tick(Instant::now() + delay, period, task);
+    }
+}
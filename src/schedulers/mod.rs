@@ -0,0 +1,203 @@
+pub mod current_thread;
+pub mod new_thread;
+pub mod thread_pool;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+
+use std::sync::{Arc, Condvar, Mutex, OnceLock, PoisonError};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::collections::BinaryHeap;
+use std::cmp::{Ordering, Reverse};
+
+use crate::observer::IterationResult;
+use crate::observer::IterationResult::Continue;
+use crate::sync::MutexExt;
+
+//////////////Timer
+// A single background thread that runs tasks once their deadline passes.
+// Tasks share the thread, so they should hand work off rather than block.
+pub(crate) struct TimerEntry {
+    pub(crate) deadline: Instant,
+    pub(crate) seq: u64,
+    pub(crate) task: Box<dyn FnOnce() + Send>
+}
+
+impl PartialEq for TimerEntry {
+    fn eq(&self, other: &TimerEntry) -> bool {
+        self.deadline == other.deadline && self.seq == other.seq
+    }
+}
+
+impl Eq for TimerEntry {}
+
+impl PartialOrd for TimerEntry {
+    fn partial_cmp(&self, other: &TimerEntry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TimerEntry {
+    fn cmp(&self, other: &TimerEntry) -> Ordering {
+        (self.deadline, self.seq).cmp(&(other.deadline, other.seq))
+    }
+}
+
+pub(crate) struct TimerQueue {
+    pub(crate) entries: BinaryHeap<Reverse<TimerEntry>>,
+    pub(crate) seq: u64
+}
+
+pub(crate) struct Timer {
+    pub(crate) queue: Mutex<TimerQueue>,
+    pub(crate) condvar: Condvar
+}
+
+impl Timer {
+    fn shared() -> &'static Arc<Timer> {
+        static TIMER: OnceLock<Arc<Timer>> = OnceLock::new();
+
+        TIMER.get_or_init(|| {
+            let timer = Arc::new(Timer {
+                queue: Mutex::new(TimerQueue {entries: BinaryHeap::new(), seq: 0}),
+                condvar: Condvar::new()
+            });
+            let runner = timer.clone();
+            thread::spawn(move || runner.run());
+            timer
+        })
+    }
+
+    fn schedule<F>(&self, deadline: Instant, task: F)
+        where F: FnOnce() + Send + 'static {
+        let mut queue = self.queue.acquire();
+        let seq = queue.seq;
+        queue.seq += 1;
+        queue.entries.push(Reverse(TimerEntry {deadline: deadline, seq: seq, task: Box::new(task)}));
+        self.condvar.notify_one();
+    }
+
+    fn run(&self) {
+        let mut queue = self.queue.acquire();
+
+        loop {
+            let now = Instant::now();
+            let next = queue.entries.peek().map(|entry| entry.0.deadline);
+
+            queue = match next {
+                None => self.condvar.wait(queue).unwrap_or_else(PoisonError::into_inner),
+                Some(deadline) if deadline > now => {
+                    self.condvar.wait_timeout(queue, deadline - now).unwrap_or_else(PoisonError::into_inner).0
+                },
+                Some(_) => {
+                    let entry = queue.entries.pop().unwrap().0;
+                    drop(queue);
+                    // Every timer in the process shares this thread, so a
+                    // panicking task mustn't take it down with it
+                    let _ = catch_unwind(AssertUnwindSafe(entry.task));
+                    self.queue.acquire()
+                }
+            };
+        }
+    }
+}
+//Timer//////////////
+
+//////////////Scheduler
+// Decides where and when work runs, so time and concurrency can be swapped
+// out (a thread pool, a virtual clock for tests) without touching the
+// operators. Tasks are boxed so a scheduler can be used as a trait object.
+pub trait Scheduler: Send + Sync {
+    fn now(&self) -> Instant;
+    fn schedule(&self, task: Box<dyn FnOnce() + Send>);
+    fn schedule_after(&self, delay: Duration, task: Box<dyn FnOnce() + Send>);
+    // Runs the task after the delay and then every period until it returns Stop
+    fn schedule_periodic(&self, delay: Duration, period: Duration, task: Box<dyn FnMut() -> IterationResult + Send>);
+}
+
+impl<D> Scheduler for Arc<D>
+    where D: Scheduler + ?Sized {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn schedule(&self, task: Box<dyn FnOnce() + Send>) {
+        (**self).schedule(task);
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Box<dyn FnOnce() + Send>) {
+        (**self).schedule_after(delay, task);
+    }
+
+    fn schedule_periodic(&self, delay: Duration, period: Duration, task: Box<dyn FnMut() -> IterationResult + Send>) {
+        (**self).schedule_periodic(delay, period, task);
+    }
+}
+
+// Runs everything on the shared timer thread. This is what the time based
+// operators use unless they're given a scheduler.
+#[derive(Clone, Copy)]
+pub struct TimerScheduler;
+
+impl Scheduler for TimerScheduler {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn schedule(&self, task: Box<dyn FnOnce() + Send>) {
+        Timer::shared().schedule(Instant::now(), task);
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Box<dyn FnOnce() + Send>) {
+        Timer::shared().schedule(Instant::now() + delay, task);
+    }
+
+    fn schedule_periodic(&self, delay: Duration, period: Duration, task: Box<dyn FnMut() -> IterationResult + Send>) {
+        schedule_tick(Instant::now() + delay, period, task);
+    }
+}
+
+// Deadlines advance from the previous deadline rather than from now, so slow
+// tasks don't make the period drift
+pub(crate) fn schedule_tick(deadline: Instant, period: Duration, mut task: Box<dyn FnMut() -> IterationResult + Send>) {
+    Timer::shared().schedule(deadline, move || {
+        if let Continue = task() {
+            schedule_tick(deadline + period, period, task);
+        }
+    });
+}
+//Scheduler//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::observer::FnObserver;
+    use crate::schedulers::{Scheduler, TimerScheduler};
+    use crate::sources::interval;
+    use crate::testing::observer::TestObserver;
+
+    #[test]
+    fn timer_outlives_a_panicking_task() {
+        let (sender, receiver) = channel();
+        TimerScheduler.schedule(Box::new(|| panic!("task panicked")));
+        TimerScheduler.schedule_after(Duration::from_millis(5), Box::new(move || {
+            let _ = sender.send(());
+        }));
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    fn intervals_tick_after_a_panicking_observer() {
+        interval(Duration::from_millis(5)).take(1).subscribe(FnObserver::new(|_| panic!("observer panicked")));
+
+        let observer = TestObserver::new();
+        interval(Duration::from_millis(5)).take(3).subscribe(observer.clone());
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::thread;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::time::{Duration, Instant};
+
+use crate::observer::IterationResult;
+use crate::schedulers::{Scheduler, Timer};
+use crate::schedulers::thread_pool::{Task, schedule_handoff_tick};
+use crate::sync::MutexExt;
+
+pub(crate) struct ThreadCache {
+    pub(crate) idle: Vec<(u64, Sender<Task>)>,
+    pub(crate) next_id: u64
+}
+
+// Gives each unit of work a thread of its own, for work that blocks. Threads
+// that finish are kept around for keep_alive in case more work turns up.
+#[derive(Clone)]
+pub struct NewThreadScheduler {
+    pub(crate) name: Option<String>,
+    pub(crate) keep_alive: Duration,
+    pub(crate) cache: Arc<Mutex<ThreadCache>>
+}
+
+impl NewThreadScheduler {
+    pub fn new() -> NewThreadScheduler {
+        NewThreadScheduler {
+            name: None,
+            keep_alive: Duration::from_secs(60),
+            cache: Arc::new(Mutex::new(ThreadCache {idle: Vec::new(), next_id: 0}))
+        }
+    }
+
+    // Shared scheduler for blocking I/O
+    pub fn io() -> NewThreadScheduler {
+        static IO: OnceLock<NewThreadScheduler> = OnceLock::new();
+
+        IO.get_or_init(|| NewThreadScheduler::new().name("rx-io")).clone()
+    }
+
+    // Threads are named prefix-N
+    pub fn name(mut self, prefix: &str) -> NewThreadScheduler {
+        self.name = Some(prefix.to_string());
+        self
+    }
+
+    pub fn keep_alive(mut self, keep_alive: Duration) -> NewThreadScheduler {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    pub(crate) fn execute(&self, task: Task) {
+        let mut task = task;
+        loop {
+            let idle = self.cache.acquire().idle.pop();
+            match idle {
+                // A thread can exit between being picked and being sent to, in
+                // which case the task comes back and the next one is tried
+                Some((_, sender)) => match sender.send(task) {
+                    Ok(()) => return,
+                    Err(err) => task = err.0
+                },
+                None => return self.spawn(task)
+            }
+        }
+    }
+
+    pub(crate) fn spawn(&self, task: Task) {
+        let id = {
+            let mut cache = self.cache.acquire();
+            cache.next_id += 1;
+            cache.next_id
+        };
+
+        let mut builder = thread::Builder::new();
+        if let Some(ref prefix) = self.name {
+            builder = builder.name(format!("{}-{}", prefix, id));
+        }
+
+        let cache = self.cache.clone();
+        let keep_alive = self.keep_alive;
+        builder.spawn(move || run_cached_thread(id, task, keep_alive, cache)).unwrap();
+    }
+}
+
+pub(crate) fn run_cached_thread(id: u64, task: Task, keep_alive: Duration, cache: Arc<Mutex<ThreadCache>>) {
+    let (sender, receiver) = channel();
+    let mut task = task;
+
+    loop {
+        let _ = catch_unwind(AssertUnwindSafe(task));
+        cache.acquire().idle.push((id, sender.clone()));
+
+        task = loop {
+            match receiver.recv_timeout(keep_alive) {
+                Ok(task) => break task,
+                Err(RecvTimeoutError::Timeout) => {
+                    // Only leave if nobody has claimed this thread in the
+                    // meantime, otherwise a task is on its way
+                    let mut cache = cache.acquire();
+                    if let Some(index) = cache.idle.iter().position(|&(idle, _)| idle == id) {
+                        cache.idle.remove(index);
+                        return;
+                    }
+                },
+                Err(RecvTimeoutError::Disconnected) => return
+            }
+        };
+    }
+}
+
+impl Scheduler for NewThreadScheduler {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn schedule(&self, task: Box<dyn FnOnce() + Send>) {
+        self.execute(task);
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Box<dyn FnOnce() + Send>) {
+        let scheduler = self.clone();
+        Timer::shared().schedule(Instant::now() + delay, move || scheduler.execute(task));
+    }
+
+    fn schedule_periodic(&self, delay: Duration, period: Duration, task: Box<dyn FnMut() -> IterationResult + Send>) {
+        schedule_handoff_tick(self.clone(), Instant::now() + delay, period, task);
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::{Arc, Condvar, Mutex, OnceLock, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::time::{Duration, Instant};
+use std::collections::VecDeque;
+
+use crate::observer::IterationResult;
+use crate::observer::IterationResult::Continue;
+use crate::schedulers::{Scheduler, Timer};
+use crate::sync::MutexExt;
+
+pub(crate) type Task = Box<dyn FnOnce() + Send>;
+
+pub(crate) struct PoolQueue {
+    pub(crate) tasks: VecDeque<Task>,
+    pub(crate) shutdown: bool
+}
+
+pub(crate) struct Pool {
+    pub(crate) queue: Mutex<PoolQueue>,
+    pub(crate) condvar: Condvar,
+    pub(crate) workers: Mutex<Vec<JoinHandle<()>>>
+}
+
+// A fixed set of worker threads pulling from one queue. Delays are waited out
+// on the timer thread and only then handed to a worker. A task that panics
+// takes down neither its worker nor the other tasks.
+#[derive(Clone)]
+pub struct ThreadPoolScheduler {
+    pub(crate) pool: Arc<Pool>
+}
+
+impl ThreadPoolScheduler {
+    pub fn new(workers: usize) -> ThreadPoolScheduler {
+        let pool = Arc::new(Pool {
+            queue: Mutex::new(PoolQueue {tasks: VecDeque::new(), shutdown: false}),
+            condvar: Condvar::new(),
+            workers: Mutex::new(Vec::new())
+        });
+
+        let handles = (0..workers.max(1)).map(|i| {
+            let pool = pool.clone();
+            thread::Builder::new()
+                .name(format!("rx-pool-{}", i))
+                .spawn(move || run_worker(&pool))
+                .unwrap()
+        }).collect();
+        *pool.workers.acquire() = handles;
+
+        ThreadPoolScheduler {pool: pool}
+    }
+
+    // Shared pool with a worker per core, for CPU bound work
+    pub fn computation() -> ThreadPoolScheduler {
+        static COMPUTATION: OnceLock<ThreadPoolScheduler> = OnceLock::new();
+
+        COMPUTATION.get_or_init(|| {
+            let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+            ThreadPoolScheduler::new(workers)
+        }).clone()
+    }
+
+    pub(crate) fn execute(&self, task: Task) {
+        let mut queue = self.pool.queue.acquire();
+        if queue.shutdown {
+            return;
+        }
+        queue.tasks.push_back(task);
+        self.pool.condvar.notify_one();
+    }
+
+    // Stops taking new work, lets the workers finish what's already queued and
+    // waits for them. Anything scheduled from then on is dropped.
+    pub fn shutdown(&self) {
+        self.pool.queue.acquire().shutdown = true;
+        self.pool.condvar.notify_all();
+
+        let workers = std::mem::take(&mut *self.pool.workers.acquire());
+        let current = thread::current().id();
+        for worker in workers {
+            // A task can shut its own pool down, but can't wait for itself
+            if worker.thread().id() != current {
+                let _ = worker.join();
+            }
+        }
+    }
+}
+
+pub(crate) fn run_worker(pool: &Pool) {
+    let mut queue = pool.queue.acquire();
+
+    loop {
+        match queue.tasks.pop_front() {
+            Some(task) => {
+                drop(queue);
+                let _ = catch_unwind(AssertUnwindSafe(task));
+                queue = pool.queue.acquire();
+            },
+            None if queue.shutdown => return,
+            None => queue = pool.condvar.wait(queue).unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+}
+
+// Keeps time on the timer thread but runs each tick on the given scheduler,
+// for schedulers that only know how to run work right away
+pub(crate) fn schedule_handoff_tick<D>(scheduler: D, deadline: Instant, period: Duration, mut task: Box<dyn FnMut() -> IterationResult + Send>)
+    where D: Scheduler + Clone + 'static {
+    Timer::shared().schedule(deadline, move || {
+        let next = scheduler.clone();
+        scheduler.schedule(Box::new(move || {
+            if let Continue = task() {
+                schedule_handoff_tick(next, deadline + period, period, task);
+            }
+        }));
+    });
+}
+
+impl Scheduler for ThreadPoolScheduler {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn schedule(&self, task: Box<dyn FnOnce() + Send>) {
+        self.execute(task);
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Box<dyn FnOnce() + Send>) {
+        let pool = self.clone();
+        Timer::shared().schedule(Instant::now() + delay, move || pool.execute(task));
+    }
+
+    fn schedule_periodic(&self, delay: Duration, period: Duration, task: Box<dyn FnMut() -> IterationResult + Send>) {
+        schedule_handoff_tick(self.clone(), Instant::now() + delay, period, task);
+    }
+}
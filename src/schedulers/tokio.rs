@@ -0,0 +1,145 @@
+use std::time::{Duration, Instant};
+
+use ::tokio::runtime::Handle;
+use ::tokio::time::{self, MissedTickBehavior};
+
+use crate::observer::IterationResult;
+use crate::observer::IterationResult::Stop;
+use crate::schedulers::Scheduler;
+
+// Runs work as tasks on a tokio runtime, with delays on the runtime's timer
+// instead of the shared timer thread. Time comes from tokio's clock, so a
+// runtime with paused time drives the time based operators too. As on the
+// timer thread, tasks share the runtime's workers and shouldn't block.
+#[derive(Clone)]
+pub struct TokioScheduler {
+    pub(crate) handle: Handle
+}
+
+impl TokioScheduler {
+    pub fn new(handle: Handle) -> TokioScheduler {
+        TokioScheduler {handle: handle}
+    }
+
+    // The runtime this is called from; panics outside of one
+    pub fn current() -> TokioScheduler {
+        TokioScheduler::new(Handle::current())
+    }
+}
+
+impl Scheduler for TokioScheduler {
+    fn now(&self) -> Instant {
+        // Entered so the clock is this runtime's even off its threads
+        let _guard = self.handle.enter();
+        time::Instant::now().into_std()
+    }
+
+    fn schedule(&self, task: Box<dyn FnOnce() + Send>) {
+        self.handle.spawn(async move { task() });
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Box<dyn FnOnce() + Send>) {
+        self.handle.spawn(async move {
+            time::sleep(delay).await;
+            task()
+        });
+    }
+
+    fn schedule_periodic(&self, delay: Duration, period: Duration, mut task: Box<dyn FnMut() -> IterationResult + Send>) {
+        self.handle.spawn(async move {
+            // Ticks are kept on their original deadlines, as on the timer
+            // thread. tokio won't take a zero period, so it's rounded up.
+            let start = time::Instant::now() + delay;
+            let mut ticks = time::interval_at(start, period.max(Duration::from_nanos(1)));
+            ticks.set_missed_tick_behavior(MissedTickBehavior::Burst);
+            loop {
+                ticks.tick().await;
+                if let Stop = task() {
+                    break;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use ::tokio::runtime::{Builder, Runtime};
+    use ::tokio::time;
+
+    use crate::observable::Observable;
+    use crate::observer::IterationResult::{Continue, Stop};
+    use crate::schedulers::Scheduler;
+    use crate::schedulers::tokio::TokioScheduler;
+    use crate::sources::{interval_on, timer_on};
+    use crate::testing::observer::TestObserver;
+
+    fn runtime() -> Runtime {
+        Builder::new_current_thread().enable_time().build().unwrap()
+    }
+
+    // A current thread runtime only gets on with its tasks while blocked on
+    fn run_for(runtime: &Runtime, duration: Duration) {
+        runtime.block_on(async { time::sleep(duration).await });
+    }
+
+    #[test]
+    fn tasks_run_as_the_runtime_is_driven() {
+        let runtime = runtime();
+        let scheduler = TokioScheduler::new(runtime.handle().clone());
+        let ran = Arc::new(AtomicUsize::new(0));
+        let counter = ran.clone();
+        scheduler.schedule(Box::new(move || { counter.fetch_add(1, Ordering::SeqCst); }));
+        assert_eq!(ran.load(Ordering::SeqCst), 0);
+
+        run_for(&runtime, Duration::from_millis(1));
+        assert_eq!(ran.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn timers_wait_on_the_runtimes_clock() {
+        let runtime = runtime();
+        let scheduler = TokioScheduler::new(runtime.handle().clone());
+        let observer = TestObserver::new();
+        let start = scheduler.now();
+        timer_on(Duration::from_millis(20), scheduler.clone()).subscribe(observer.clone());
+
+        run_for(&runtime, Duration::from_millis(5));
+        observer.assert_values(&[]);
+        run_for(&runtime, Duration::from_millis(40));
+        observer.assert_values(&[0]);
+        observer.assert_completed();
+        assert!(scheduler.now() - start >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn periodic_tasks_stop_when_asked() {
+        let runtime = runtime();
+        let scheduler = TokioScheduler::new(runtime.handle().clone());
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let counter = ticks.clone();
+        scheduler.schedule_periodic(Duration::ZERO, Duration::from_millis(2), Box::new(move || {
+            if counter.fetch_add(1, Ordering::SeqCst) < 2 { Continue } else { Stop }
+        }));
+
+        run_for(&runtime, Duration::from_millis(40));
+        assert_eq!(ticks.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn current_picks_up_the_running_runtime() {
+        let runtime = runtime();
+        let observer = TestObserver::new();
+        let subscribed = observer.clone();
+        runtime.block_on(async move {
+            interval_on(Duration::from_millis(2), TokioScheduler::current()).take(3).subscribe(subscribed);
+            time::sleep(Duration::from_millis(40)).await;
+        });
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+}
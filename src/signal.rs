@@ -0,0 +1,213 @@
+use std::io::{self, Read};
+use std::os::raw::{c_int, c_void};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::thread;
+
+use crate::observable::Observable;
+use crate::observer::Observer;
+use crate::subjects::Subject;
+use crate::sync::MutexExt;
+
+extern "C" {
+    fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+    fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+}
+
+// signal() returns this when it fails
+const SIG_ERR: usize = !0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Signal {
+    Hangup,
+    Interrupt,
+    Quit,
+    Terminate
+}
+
+impl Signal {
+    fn number(self) -> c_int {
+        match self {
+            Signal::Hangup => 1,
+            Signal::Interrupt => 2,
+            Signal::Quit => 3,
+            Signal::Terminate => 15
+        }
+    }
+
+    fn from_number(number: c_int) -> Option<Signal> {
+        match number {
+            1 => Some(Signal::Hangup),
+            2 => Some(Signal::Interrupt),
+            3 => Some(Signal::Quit),
+            15 => Some(Signal::Terminate),
+            _ => None
+        }
+    }
+}
+
+//////////////Registry
+// Where the handler writes, -1 until the registry is up
+static WAKE_FD: AtomicI32 = AtomicI32::new(-1);
+
+// Nothing but a write is safe in a signal handler, so the handler writes the
+// signal's number to a socket and a thread of the registry's own reads it
+// back and dispatches it
+extern "C" fn handle(signum: c_int) {
+    let fd = WAKE_FD.load(Ordering::SeqCst);
+    if fd >= 0 {
+        let byte = signum as u8;
+        unsafe {
+            write(fd, &byte as *const u8 as *const c_void, 1);
+        }
+    }
+}
+
+pub(crate) struct Registry {
+    pub(crate) subject: Subject<Signal>,
+    pub(crate) installed: Mutex<Vec<Signal>>,
+    // Kept open for the handler
+    pub(crate) _wake: UnixStream
+}
+
+impl Registry {
+    fn start() -> io::Result<Registry> {
+        let (mut reader, wake) = UnixStream::pair()?;
+        // A burst of signals shouldn't block the handler, and the ones that
+        // don't fit are as good as delivered
+        wake.set_nonblocking(true)?;
+
+        let subject = Subject::new();
+        let dispatch = subject.clone();
+        thread::Builder::new().name("rx-signals".to_string()).spawn(move || {
+            let mut byte = [0u8];
+            while let Ok(1) = reader.read(&mut byte) {
+                if let Some(signal) = Signal::from_number(byte[0] as c_int) {
+                    dispatch.dispatch(signal);
+                }
+            }
+        })?;
+
+        WAKE_FD.store(wake.as_raw_fd(), Ordering::SeqCst);
+        Ok(Registry {subject: subject, installed: Mutex::new(Vec::new()), _wake: wake})
+    }
+
+    // Handlers are only installed once something listens for the signal, and
+    // then stay for the life of the process, so the signal no longer has its
+    // default effect even after every subscriber has gone
+    fn install(&self, signals: &[Signal]) -> io::Result<()> {
+        let mut installed = self.installed.acquire();
+        for &sig in signals {
+            if installed.contains(&sig) {
+                continue;
+            }
+            if unsafe { signal(sig.number(), handle) } == SIG_ERR {
+                return Err(io::Error::last_os_error());
+            }
+            installed.push(sig);
+        }
+        Ok(())
+    }
+}
+
+fn registry() -> io::Result<&'static Registry> {
+    static REGISTRY: OnceLock<Result<Registry, io::ErrorKind>> = OnceLock::new();
+
+    match *REGISTRY.get_or_init(|| Registry::start().map_err(|err| err.kind())) {
+        Ok(ref registry) => Ok(registry),
+        Err(kind) => Err(io::Error::new(kind, "couldn't set up signal handling"))
+    }
+}
+//Registry//////////////
+
+//////////////Signals
+// Emits each of the given signals the process receives, from then on. It's
+// hot and never completes, so it's meant for take_until or the like:
+// work.take_until(ctrl_c()) stops the work on the first ctrl-c.
+pub struct SignalsObservable {
+    pub(crate) signals: Vec<Signal>
+}
+
+impl Observable for SignalsObservable {
+    type Item = Signal;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let registry = match registry().and_then(|registry| registry.install(&self.signals).map(|_| registry)) {
+            Ok(registry) => registry,
+            Err(err) => return observer.error(Arc::new(err))
+        };
+
+        let signals = self.signals.clone();
+        registry.subject.clone()
+            .filter(move |sig| signals.contains(sig))
+            .subscribe(observer);
+    }
+}
+
+pub fn signals(signals: &[Signal]) -> SignalsObservable {
+    SignalsObservable {signals: signals.to_vec()}
+}
+
+pub fn ctrl_c() -> SignalsObservable {
+    signals(&[Signal::Interrupt])
+}
+//Signals//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::os::raw::c_int;
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::signal::{signals, Signal};
+    use crate::sources::interval;
+    use crate::testing::observer::TestObserver;
+
+    extern "C" {
+        fn raise(signum: c_int) -> c_int;
+    }
+
+    // Only signals whose handlers are already in place, or the test process
+    // gets the default effect. Tests run side by side, so each test raises a
+    // signal of its own.
+    fn send(sig: Signal) {
+        assert_eq!(unsafe { raise(sig.number()) }, 0);
+    }
+
+    #[test]
+    fn signal_numbers_round_trip() {
+        for sig in [Signal::Hangup, Signal::Interrupt, Signal::Quit, Signal::Terminate] {
+            assert_eq!(Signal::from_number(sig.number()), Some(sig));
+        }
+        assert_eq!(Signal::from_number(9), None);
+    }
+
+    #[test]
+    fn signals_emits_only_the_signals_asked_for() {
+        let hangups = TestObserver::new();
+        let quits = TestObserver::new();
+        signals(&[Signal::Hangup]).take(1).subscribe(hangups.clone());
+        signals(&[Signal::Quit]).subscribe(quits.clone());
+
+        send(Signal::Hangup);
+        assert!(hangups.await_terminal_event(Duration::from_secs(5)));
+        hangups.assert_values(&[Signal::Hangup]);
+        quits.assert_values(&[]);
+        quits.assert_not_completed();
+    }
+
+    #[test]
+    fn take_until_a_signal_stops_the_work() {
+        let observer = TestObserver::new();
+        interval(Duration::from_millis(5)).take_until(signals(&[Signal::Terminate])).subscribe(observer.clone());
+        assert!(!observer.await_terminal_event(Duration::from_millis(30)));
+
+        send(Signal::Terminate);
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_completed();
+        assert!(observer.value_count() > 0);
+    }
+}
@@ -0,0 +1,794 @@
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use core::mem;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+#[cfg(feature = "std")]
+use crate::flow::{Flowable, Subscriber, subscribe_pull};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Stop;
+#[cfg(feature = "std")]
+use crate::observer::IterationResult::Continue;
+#[cfg(feature = "std")]
+use crate::operators::subscribe_on::SubscribeOnObservable;
+#[cfg(feature = "std")]
+use crate::schedulers::{Scheduler, TimerScheduler};
+#[cfg(feature = "std")]
+use crate::sync::MutexExt;
+
+//////////////Range
+// What range needs from its bounds. Kept to the primitive integers, which is
+// all std::iter::Step was used for before it was needed on stable.
+pub trait Step: Clone + PartialOrd {
+    fn successor(&self) -> Self;
+}
+
+macro_rules! impl_step {
+    ($($t:ty)*) => {$(
+        impl Step for $t {
+            #[inline]
+            fn successor(&self) -> $t {
+                *self + 1
+            }
+        }
+    )*}
+}
+
+impl_step!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+pub struct RangeObservable<A> {
+    pub(crate) start: A,
+    pub(crate) end: A
+}
+
+impl<A> Observable for RangeObservable<A>
+    where A: Step {
+    type Item = A;
+
+    #[inline]
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let mut state = self.start.clone();
+
+        // Checked before each item, so an empty range sends nothing
+        while state < self.end {
+            if let Stop = observer.next(state.clone()) {
+                break;
+            }
+            state = state.successor();
+        }
+
+        observer.completed();
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A> Flowable for RangeObservable<A>
+    where A: Step + Send + 'static {
+    type Item = A;
+
+    fn subscribe_flow<N>(&self, subscriber: N)
+        where N: Subscriber<Item=Self::Item> + Send + Sync + 'static {
+        let mut state = self.start.clone();
+        let end = self.end.clone();
+        subscribe_pull(subscriber, move || {
+            if state >= end {
+                return None;
+            }
+            let next = state.successor();
+            Some(mem::replace(&mut state, next))
+        });
+    }
+}
+
+pub fn range<A: Step>(start: A, end: A) -> RangeObservable<A> {
+    RangeObservable {start: start, end: end}
+}
+//Range//////////////
+
+pub struct ValueObservable<A> {
+    pub(crate) value: A
+}
+
+impl<A> Observable for ValueObservable<A>
+    where A: Clone {
+    type Item = A;
+
+    #[inline]
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+
+        observer.next(self.value.clone());
+        observer.completed();
+    }
+}
+
+pub fn value<A: Clone>(value: A) -> ValueObservable<A> {
+    ValueObservable {value: value}
+}
+
+// Iterates a fresh clone of the iterator for every subscription
+pub struct IterObservable<I> {
+    pub(crate) iter: I
+}
+
+impl<I> Observable for IterObservable<I>
+    where I: IntoIterator + Clone {
+    type Item = I::Item;
+
+    #[inline]
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        for val in self.iter.clone() {
+            if let Stop = observer.next(val) {
+                break;
+            }
+        }
+
+        observer.completed();
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I> Flowable for IterObservable<I>
+    where I: IntoIterator + Clone,
+          I::IntoIter: Send + 'static {
+    type Item = I::Item;
+
+    fn subscribe_flow<N>(&self, subscriber: N)
+        where N: Subscriber<Item=Self::Item> + Send + Sync + 'static {
+        let mut iter = self.iter.clone().into_iter();
+        subscribe_pull(subscriber, move || iter.next());
+    }
+}
+
+pub fn from_iter<I>(iter: I) -> IterObservable<I>
+    where I: IntoIterator + Clone {
+    IterObservable {iter: iter}
+}
+
+//////////////Create
+// A handle on a create subscription that can be kept and cloned, so a source
+// can be fed from callbacks and other threads after the closure's returned.
+// The cancellation runs once the subscription's over: when the downstream
+// stops, when the source completes or fails, or when the last handle's gone,
+// as nothing can be sent after that. Clones share the observer, and don't
+// send to it at the same time. Sending from inside the downstream's next
+// deadlocks.
+#[cfg(feature = "std")]
+pub struct Emitter<A> {
+    pub(crate) shared: Arc<EmitterShared<A>>
+}
+
+#[cfg(feature = "std")]
+pub(crate) struct EmitterShared<A> {
+    // None once the subscription's over
+    pub(crate) observer: Mutex<Option<Box<dyn Observer<Item=A> + Send>>>,
+    pub(crate) teardown: Mutex<EmitterTeardown>
+}
+
+#[cfg(feature = "std")]
+pub(crate) struct EmitterTeardown {
+    pub(crate) cancellation: Option<Box<dyn FnOnce() + Send>>,
+    pub(crate) stopped: bool
+}
+
+#[cfg(feature = "std")]
+impl<A> EmitterShared<A> {
+    // The cancellation's run outside the lock, so it can use the emitter
+    fn stop(&self) {
+        let cancellation = {
+            let mut teardown = self.teardown.acquire();
+            teardown.stopped = true;
+            teardown.cancellation.take()
+        };
+        if let Some(cancellation) = cancellation {
+            cancellation();
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A> Drop for EmitterShared<A> {
+    fn drop(&mut self) {
+        if let Some(cancellation) = self.teardown.acquire_mut().cancellation.take() {
+            cancellation();
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A> Clone for Emitter<A> {
+    #[inline]
+    fn clone(&self) -> Emitter<A> {
+        Emitter {shared: self.shared.clone()}
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A> Emitter<A> {
+    pub fn next(&self, val: A) -> IterationResult {
+        let mut observer = self.shared.observer.acquire();
+        let result = match *observer {
+            Some(ref mut observer) => observer.next(val),
+            None => return Stop
+        };
+
+        if let Stop = result {
+            observer.take();
+            drop(observer);
+            self.shared.stop();
+        }
+        result
+    }
+
+    // Still forwarded after a downstream Stop, the same as the built-in sources
+    pub fn completed(&self) {
+        let observer = self.shared.observer.acquire().take();
+        if let Some(mut observer) = observer {
+            observer.completed();
+            self.shared.stop();
+        }
+    }
+
+    pub fn error(&self, err: Error) {
+        let observer = self.shared.observer.acquire().take();
+        if let Some(mut observer) = observer {
+            observer.error(err);
+            self.shared.stop();
+        }
+    }
+
+    // For teardown such as deregistering a callback. One set before is run
+    // as it's replaced, and one set once the subscription's over runs
+    // straight away.
+    pub fn set_cancellation<C>(&self, cancellation: C)
+        where C: FnOnce() + Send + 'static {
+        let run: Box<dyn FnOnce() + Send> = Box::new(cancellation);
+        let run = {
+            let mut teardown = self.shared.teardown.acquire();
+            if teardown.stopped {
+                Some(run)
+            } else {
+                teardown.cancellation.replace(run)
+            }
+        };
+        if let Some(run) = run {
+            run();
+        }
+    }
+
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        self.shared.teardown.acquire().stopped
+    }
+}
+
+#[cfg(feature = "std")]
+pub struct CreateObservable<F, A> {
+    pub(crate) f: F,
+    pub(crate) _marker: PhantomData<A>
+}
+
+#[cfg(feature = "std")]
+impl<A, F> Observable for CreateObservable<F, A>
+    where F: Fn(Emitter<A>) {
+    type Item = A;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let observer: Box<dyn Observer<Item=A> + Send> = Box::new(observer);
+        (self.f)(Emitter {
+            shared: Arc::new(EmitterShared {
+                observer: Mutex::new(Some(observer)),
+                teardown: Mutex::new(EmitterTeardown {cancellation: None, stopped: false})
+            })
+        });
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn create<A, F>(f: F) -> CreateObservable<F, A>
+    where F: Fn(Emitter<A>) {
+    CreateObservable {f: f, _marker: PhantomData}
+}
+//Create//////////////
+
+pub struct ThrowObservable<A> {
+    pub(crate) error: Error,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A> Observable for ThrowObservable<A> {
+    type Item = A;
+
+    #[inline]
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        observer.error(self.error.clone());
+    }
+}
+
+pub fn throw<A>(err: Error) -> ThrowObservable<A> {
+    ThrowObservable {error: err, _marker: PhantomData}
+}
+
+// Runs the closure for every subscription and emits what it returns, for
+// work that shouldn't happen until something's listening. value() needs its
+// value up front instead.
+pub struct StartObservable<F> {
+    pub(crate) f: F
+}
+
+impl<A, F> Observable for StartObservable<F>
+    where F: Fn() -> A {
+    type Item = A;
+
+    #[inline]
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        observer.next((self.f)());
+        observer.completed();
+    }
+}
+
+pub fn start<A, F>(f: F) -> StartObservable<F>
+    where F: Fn() -> A {
+    StartObservable {f: f}
+}
+
+// Runs the closure on the scheduler rather than in subscribe
+#[cfg(feature = "std")]
+pub fn start_on<A, F, D>(f: F, scheduler: D) -> SubscribeOnObservable<StartObservable<F>, D>
+    where F: Fn() -> A,
+          D: Scheduler {
+    start(f).subscribe_on(scheduler)
+}
+
+// Never emits or terminates. The observer is dropped straight away, as
+// nothing will ever be sent to it.
+pub struct NeverObservable<A> {
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A> Observable for NeverObservable<A> {
+    type Item = A;
+
+    #[inline]
+    fn subscribe<N>(&self, _: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {}
+}
+
+pub fn never<A>() -> NeverObservable<A> {
+    NeverObservable {_marker: PhantomData}
+}
+
+//////////////Interval
+#[cfg(feature = "std")]
+pub struct IntervalObservable<D> {
+    pub(crate) period: Duration,
+    pub(crate) scheduler: D
+}
+
+#[cfg(feature = "std")]
+impl<D> Observable for IntervalObservable<D>
+    where D: Scheduler {
+    type Item = usize;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        schedule_count(&self.scheduler, self.period, self.period, observer);
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn schedule_count<D, N>(scheduler: &D, delay: Duration, period: Duration, mut observer: N)
+    where D: Scheduler,
+          N: Observer<Item=usize> + Send + 'static {
+    let mut count = 0;
+    scheduler.schedule_periodic(delay, period, Box::new(move || {
+        match observer.next(count) {
+            Continue => {
+                count += 1;
+                Continue
+            },
+            Stop => {
+                observer.completed();
+                Stop
+            }
+        }
+    }));
+}
+
+#[cfg(feature = "std")]
+pub fn interval(period: Duration) -> IntervalObservable<TimerScheduler> {
+    interval_on(period, TimerScheduler)
+}
+
+#[cfg(feature = "std")]
+pub fn interval_on<D>(period: Duration, scheduler: D) -> IntervalObservable<D>
+    where D: Scheduler {
+    IntervalObservable {period: period, scheduler: scheduler}
+}
+//Interval//////////////
+
+//////////////TimerSource
+#[cfg(feature = "std")]
+pub struct TimerObservable<D> {
+    pub(crate) delay: Duration,
+    pub(crate) period: Option<Duration>,
+    pub(crate) scheduler: D
+}
+
+#[cfg(feature = "std")]
+impl<D> Observable for TimerObservable<D>
+    where D: Scheduler {
+    type Item = usize;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        match self.period {
+            Some(period) => schedule_count(&self.scheduler, self.delay, period, observer),
+            None => self.scheduler.schedule_after(self.delay, Box::new(move || {
+                observer.next(0);
+                observer.completed();
+            }))
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn timer(delay: Duration) -> TimerObservable<TimerScheduler> {
+    timer_on(delay, TimerScheduler)
+}
+
+#[cfg(feature = "std")]
+pub fn timer_on<D>(delay: Duration, scheduler: D) -> TimerObservable<D>
+    where D: Scheduler {
+    TimerObservable {delay: delay, period: None, scheduler: scheduler}
+}
+
+#[cfg(feature = "std")]
+pub fn timer_periodic(delay: Duration, period: Duration) -> TimerObservable<TimerScheduler> {
+    timer_periodic_on(delay, period, TimerScheduler)
+}
+
+#[cfg(feature = "std")]
+pub fn timer_periodic_on<D>(delay: Duration, period: Duration, scheduler: D) -> TimerObservable<D>
+    where D: Scheduler {
+    TimerObservable {delay: delay, period: Some(period), scheduler: scheduler}
+}
+//TimerSource//////////////
+
+//////////////Using
+pub struct UsingObservable<R, U, A, F> {
+    pub(crate) acquire: A,
+    pub(crate) factory: F,
+    pub(crate) _marker: PhantomData<(R, U)>
+}
+
+// The resource is moved into the observable built from it. The subscription
+// keeps that observable alive and lets go of it once the stream terminates or
+// the downstream stops, so the resource is dropped as soon as nothing can
+// still use it.
+impl<R, U, A, F> Observable for UsingObservable<R, U, A, F>
+    where A: Fn() -> R,
+          F: Fn(R) -> U,
+          U: Observable + Send + Sync + 'static,
+          U::Item: Send + Sync + 'static {
+    type Item = U::Item;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let source = Arc::new((self.factory)((self.acquire)()));
+        source.subscribe(UsingObserver {source: Some(source.clone()), observer: observer});
+    }
+}
+
+pub fn using<R, U, A, F>(acquire: A, factory: F) -> UsingObservable<R, U, A, F>
+    where A: Fn() -> R,
+          F: Fn(R) -> U,
+          U: Observable {
+    UsingObservable {acquire: acquire, factory: factory, _marker: PhantomData}
+}
+
+pub(crate) struct UsingObserver<U, N> {
+    pub(crate) source: Option<Arc<U>>,
+    pub(crate) observer: N
+}
+
+impl<U, N> Observer for UsingObserver<U, N>
+    where N: Observer {
+    type Item = N::Item;
+
+    #[inline]
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        let result = self.observer.next(val);
+        if let Stop = result {
+            self.source.take();
+        }
+        result
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+        self.source.take();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.error(err);
+        self.source.take();
+    }
+}
+//Using//////////////
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::observer::IterationResult::Stop;
+    use crate::sources::{create, range, start, start_on, using, Emitter};
+    use crate::subjects::Subject;
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    // A source that keeps its emitter for the test to drive, counting how
+    // often its cancellation runs
+    fn kept_source() -> (impl Observable<Item=i32>, Arc<Mutex<Option<Emitter<i32>>>>, Arc<AtomicUsize>) {
+        let slot = Arc::new(Mutex::new(None));
+        let cancelled = Arc::new(AtomicUsize::new(0));
+        let kept = slot.clone();
+        let counted = cancelled.clone();
+        let source = create(move |emitter: Emitter<i32>| {
+            let counted = counted.clone();
+            emitter.set_cancellation(move || { counted.fetch_add(1, Ordering::SeqCst); });
+            *kept.lock().unwrap() = Some(emitter);
+        });
+        (source, slot, cancelled)
+    }
+
+    // Counts how often it's been dropped
+    struct Resource {
+        dropped: Arc<AtomicUsize>
+    }
+
+    impl Drop for Resource {
+        fn drop(&mut self) {
+            self.dropped.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    // Hands out a Resource on every acquire, counting acquires and drops
+    fn resources() -> (impl Fn() -> Resource + Send + Sync + 'static, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let acquired = Arc::new(AtomicUsize::new(0));
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let (counted, released) = (acquired.clone(), dropped.clone());
+        (move || {
+            counted.fetch_add(1, Ordering::SeqCst);
+            Resource {dropped: released.clone()}
+        }, acquired, dropped)
+    }
+
+    #[test]
+    fn range_is_half_open() {
+        let observer = TestObserver::new();
+        range(2, 5).subscribe(observer.clone());
+        observer.assert_values(&[2, 3, 4]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn empty_range_sends_nothing() {
+        let observer = TestObserver::new();
+        range(5, 5).subscribe(observer.clone());
+        observer.assert_values(&[]);
+        observer.assert_completed();
+
+        let observer = TestObserver::new();
+        range(7, 5).subscribe(observer.clone());
+        observer.assert_values(&[]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn range_stops_when_asked() {
+        let observer = TestObserver::new();
+        range(0, 100).take(2).subscribe(observer.clone());
+        observer.assert_values(&[0, 1]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn emitter_outlives_the_create_closure() {
+        let (source, slot, cancelled) = kept_source();
+        let observer = TestObserver::new();
+        source.take(2).subscribe(observer.clone());
+
+        let emitter = slot.lock().unwrap().take().unwrap();
+        assert_eq!(cancelled.load(Ordering::SeqCst), 0);
+        emitter.next(1);
+        assert_eq!(cancelled.load(Ordering::SeqCst), 0);
+        emitter.next(2);
+        assert_eq!(cancelled.load(Ordering::SeqCst), 1);
+        assert!(matches!(emitter.next(3), Stop));
+        assert!(emitter.is_stopped());
+
+        observer.assert_values(&[1, 2]);
+        observer.assert_completed();
+        drop(emitter);
+        assert_eq!(cancelled.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn emitter_cancels_once_terminated() {
+        let (source, slot, cancelled) = kept_source();
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        let emitter = slot.lock().unwrap().take().unwrap();
+        emitter.next(1);
+        emitter.completed();
+        emitter.completed();
+        assert_eq!(cancelled.load(Ordering::SeqCst), 1);
+        observer.assert_values(&[1]);
+        observer.assert_completed();
+
+        let (source, slot, cancelled) = kept_source();
+        let observer = TestObserver::new();
+        source.subscribe(observer.clone());
+        let emitter = slot.lock().unwrap().take().unwrap();
+        emitter.error(Arc::new(MarbleError));
+        assert_eq!(cancelled.load(Ordering::SeqCst), 1);
+        observer.assert_error();
+    }
+
+    #[test]
+    fn emitter_cancels_when_the_last_handle_goes() {
+        let (source, slot, cancelled) = kept_source();
+        source.subscribe(TestObserver::new());
+        let emitter = slot.lock().unwrap().take().unwrap();
+        let other = emitter.clone();
+        drop(emitter);
+        assert_eq!(cancelled.load(Ordering::SeqCst), 0);
+        drop(other);
+        assert_eq!(cancelled.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn cancellation_set_after_the_end_runs_straight_away() {
+        let cancelled = Arc::new(AtomicUsize::new(0));
+        let counted = cancelled.clone();
+        let observer = TestObserver::new();
+        create(move |emitter: Emitter<i32>| {
+            emitter.completed();
+            let ran = counted.clone();
+            emitter.set_cancellation(move || { ran.fetch_add(1, Ordering::SeqCst); });
+            assert_eq!(counted.load(Ordering::SeqCst), 1);
+        })
+        .subscribe(observer.clone());
+
+        assert_eq!(cancelled.load(Ordering::SeqCst), 1);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn emitter_can_be_fed_from_another_thread() {
+        let observer = TestObserver::new();
+        create(|emitter: Emitter<i32>| {
+            thread::spawn(move || {
+                for i in 0.. {
+                    if let Stop = emitter.next(i) {
+                        break;
+                    }
+                }
+            });
+        })
+        .take(3)
+        .subscribe(observer.clone());
+
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn using_drops_the_resource_once_the_stream_completes() {
+        let (acquire, acquired, dropped) = resources();
+        let observer = TestObserver::new();
+        using(acquire, |resource| range(0, 3).tap(move |_| { let _ = &resource; })).subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2]);
+        observer.assert_completed();
+        assert_eq!(acquired.load(Ordering::SeqCst), 1);
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn using_drops_the_resource_once_the_stream_fails() {
+        let (acquire, _, dropped) = resources();
+        let observer = TestObserver::<i32>::new();
+        using(acquire, |resource| create(move |emitter: Emitter<i32>| {
+            let _ = &resource;
+            emitter.error(Arc::new(MarbleError));
+        }))
+        .subscribe(observer.clone());
+        observer.assert_error();
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn using_drops_the_resource_once_the_downstream_stops() {
+        let (acquire, _, dropped) = resources();
+        let observer = TestObserver::new();
+        using(acquire, |resource| range(0, 100).tap(move |_| { let _ = &resource; })).take(2).subscribe(observer.clone());
+        observer.assert_values(&[0, 1]);
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn using_holds_the_resource_until_the_end() {
+        let (acquire, acquired, dropped) = resources();
+        let subject = Subject::new();
+        let source = subject.clone();
+        let observer = TestObserver::new();
+        using(acquire, move |resource| source.clone().tap(move |_: &i32| { let _ = &resource; })).subscribe(observer.clone());
+
+        subject.clone().next(1);
+        assert_eq!(dropped.load(Ordering::SeqCst), 0);
+        subject.clone().completed();
+        assert_eq!(acquired.load(Ordering::SeqCst), 1);
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+        observer.assert_values(&[1]);
+    }
+
+    #[test]
+    fn using_acquires_for_every_subscription() {
+        let (acquire, acquired, dropped) = resources();
+        let source = using(acquire, |resource| range(0, 2).tap(move |_| { let _ = &resource; }));
+        source.subscribe(TestObserver::new());
+        source.subscribe(TestObserver::new());
+        assert_eq!(acquired.load(Ordering::SeqCst), 2);
+        assert_eq!(dropped.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn start_runs_the_closure_for_each_subscription() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let source = start(move || counter.fetch_add(1, Ordering::SeqCst) * 10);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        for expected in [0, 10] {
+            let observer = TestObserver::new();
+            source.subscribe(observer.clone());
+            observer.assert_values(&[expected]);
+            observer.assert_completed();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn start_on_runs_the_closure_on_the_scheduler() {
+        let scheduler = TestScheduler::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let observer = TestObserver::new();
+        start_on(move || counter.fetch_add(1, Ordering::SeqCst), scheduler.clone()).subscribe(observer.clone());
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        observer.assert_values(&[]);
+
+        scheduler.trigger_actions();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        observer.assert_values(&[0]);
+        observer.assert_completed();
+    }
+}
@@ -0,0 +1,351 @@
+use std::sync::{Arc, Mutex};
+use std::thread::{self, Thread};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll, Wake, Waker};
+
+use futures_core::Stream;
+
+use crate::flow::{Flowable, FlowSubscription, Subscriber, subscribe_pull};
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::sync::MutexExt;
+
+//////////////IntoStream
+// What a stream adapter has been handed and not yet yielded. A failure is
+// queued as the last item.
+pub(crate) struct StreamState<A, E> {
+    pub(crate) queue: VecDeque<Result<A, E>>,
+    pub(crate) finished: bool,
+    pub(crate) dropped: bool,
+    pub(crate) waker: Option<Waker>,
+    pub(crate) subscription: Option<FlowSubscription>,
+    pub(crate) requested: bool
+}
+
+// Reads an observable as a Stream of Results. The source is subscribed on the
+// first poll, from the polling thread. A plain observable pushes as fast as it
+// likes, so whatever arrives between polls is buffered; a flowable is asked
+// for one item at a time instead.
+pub struct ObservableStream<A, E = Error> {
+    pub(crate) subscribe: Option<Box<dyn FnOnce(StreamObserver<A, E>) + Send>>,
+    pub(crate) state: Arc<Mutex<StreamState<A, E>>>
+}
+
+impl<A, E> ObservableStream<A, E> {
+    pub(crate) fn new(subscribe: Box<dyn FnOnce(StreamObserver<A, E>) + Send>) -> ObservableStream<A, E> {
+        ObservableStream {
+            subscribe: Some(subscribe),
+            state: Arc::new(Mutex::new(StreamState {
+                queue: VecDeque::new(),
+                finished: false,
+                dropped: false,
+                waker: None,
+                subscription: None,
+                requested: false
+            }))
+        }
+    }
+}
+
+impl<A, E> Stream for ObservableStream<A, E> {
+    type Item = Result<A, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(subscribe) = this.subscribe.take() {
+            subscribe(StreamObserver {state: this.state.clone()});
+        }
+
+        loop {
+            // Requesting can deliver on this thread, so it happens outside the
+            // lock and the queue is checked again after
+            let subscription = {
+                let mut state = this.state.acquire();
+                if let Some(item) = state.queue.pop_front() {
+                    return Poll::Ready(Some(item));
+                }
+                if state.finished {
+                    return Poll::Ready(None);
+                }
+                state.waker = Some(cx.waker().clone());
+                let subscription = match state.subscription {
+                    Some(ref subscription) if !state.requested => subscription.clone(),
+                    _ => return Poll::Pending
+                };
+                state.requested = true;
+                subscription
+            };
+            subscription.request(1);
+        }
+    }
+}
+
+impl<A, E> Drop for ObservableStream<A, E> {
+    fn drop(&mut self) {
+        let subscription = {
+            let mut state = self.state.acquire();
+            state.dropped = true;
+            state.subscription.take()
+        };
+        if let Some(subscription) = subscription {
+            subscription.cancel();
+        }
+    }
+}
+
+pub struct StreamObserver<A, E> {
+    pub(crate) state: Arc<Mutex<StreamState<A, E>>>
+}
+
+impl<A, E> StreamObserver<A, E> {
+    fn push(&self, item: Option<Result<A, E>>) -> IterationResult {
+        let waker = {
+            let mut state = self.state.acquire();
+            if state.dropped || state.finished {
+                return Stop;
+            }
+            match item {
+                Some(item) => {
+                    state.finished = item.is_err();
+                    state.queue.push_back(item);
+                    state.requested = false;
+                },
+                None => state.finished = true
+            }
+            state.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Continue
+    }
+}
+
+impl<A, E> Observer<E> for StreamObserver<A, E> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.push(Some(Ok(val)))
+    }
+
+    fn completed(&mut self) {
+        self.push(None);
+    }
+
+    fn error(&mut self, err: E) {
+        self.push(Some(Err(err)));
+    }
+}
+
+impl<A> Subscriber for StreamObserver<A, Error> {
+    fn on_subscribe(&mut self, subscription: FlowSubscription) {
+        let mut state = self.state.acquire();
+        if state.dropped {
+            drop(state);
+            return subscription.cancel();
+        }
+        state.subscription = Some(subscription);
+    }
+}
+//IntoStream//////////////
+
+//////////////FromStream
+// The other direction: an observable, and a flowable, of a stream's items.
+// Nothing here is an executor, so subscribing polls the stream on the
+// subscribing thread, parking it while the stream is pending. A stream can
+// only be read once, so later subscribers just see it complete.
+pub struct StreamObservable<St> {
+    pub(crate) stream: Mutex<Option<Pin<Box<St>>>>
+}
+
+pub fn from_stream<St>(stream: St) -> StreamObservable<St>
+    where St: Stream {
+    StreamObservable {stream: Mutex::new(Some(Box::pin(stream)))}
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// Polls until ready, parking the current thread whenever the poll is pending
+pub(crate) fn block_on_poll<T, P>(mut poll: P) -> T
+    where P: FnMut(&mut Context) -> Poll<T> {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match poll(&mut cx) {
+            Poll::Ready(val) => return val,
+            Poll::Pending => thread::park()
+        }
+    }
+}
+
+fn poll_blocking<St>(stream: &mut Pin<Box<St>>) -> Option<St::Item>
+    where St: Stream + ?Sized {
+    block_on_poll(|cx| stream.as_mut().poll_next(cx))
+}
+
+impl<St> Observable for StreamObservable<St>
+    where St: Stream {
+    type Item = St::Item;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let stream = self.stream.acquire().take();
+        if let Some(mut stream) = stream {
+            while let Some(val) = poll_blocking(&mut stream) {
+                if let Stop = observer.next(val) {
+                    break;
+                }
+            }
+        }
+        observer.completed();
+    }
+}
+
+impl<St> Flowable for StreamObservable<St>
+    where St: Stream + Send + 'static {
+    type Item = St::Item;
+
+    fn subscribe_flow<N>(&self, subscriber: N)
+        where N: Subscriber<Item=Self::Item> + Send + Sync + 'static {
+        let mut stream = self.stream.acquire().take();
+        subscribe_pull(subscriber, move || stream.as_mut().and_then(poll_blocking));
+    }
+}
+//FromStream//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::{Context, Waker};
+
+    use futures_core::Stream;
+
+    use crate::flow::Flowable;
+    use crate::observable::Observable;
+    use crate::observer::{Error, Observer};
+    use crate::sources::{from_iter, range, throw};
+    use crate::stream::{block_on_poll, from_stream};
+    use crate::subjects::Subject;
+    use crate::testing::marbles::MarbleError;
+    use crate::testing::observer::{TestObserver, TestSubscriber};
+
+    fn next<St>(stream: &mut St) -> Option<St::Item>
+        where St: Stream + Unpin {
+        block_on_poll(|cx| Pin::new(&mut *stream).poll_next(cx))
+    }
+
+    #[test]
+    fn into_stream_yields_the_values_then_ends() {
+        let mut stream = range(0, 3).into_stream();
+        assert_eq!(next(&mut stream).map(Result::ok), Some(Some(0)));
+        assert_eq!(next(&mut stream).map(Result::ok), Some(Some(1)));
+        assert_eq!(next(&mut stream).map(Result::ok), Some(Some(2)));
+        assert!(next(&mut stream).is_none());
+    }
+
+    #[test]
+    fn into_stream_yields_a_failure_as_the_last_item() {
+        let mut stream = throw::<i32>(Arc::new(MarbleError)).into_stream();
+        match next(&mut stream) {
+            Some(Err(err)) => assert!(err.downcast_ref::<MarbleError>().is_some()),
+            _ => panic!("expected the error")
+        }
+        assert!(next(&mut stream).is_none());
+    }
+
+    #[test]
+    fn into_stream_and_from_stream_round_trip() {
+        let observer = TestObserver::new();
+        from_stream(range(0, 5).into_stream())
+            .map(|val: Result<i32, Error>| val.unwrap())
+            .subscribe(observer.clone());
+        observer.assert_values(&[0, 1, 2, 3, 4]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn into_stream_doesnt_subscribe_until_polled() {
+        let mut subject = Subject::<i32>::new();
+        let mut stream = subject.clone().into_stream();
+        subject.next(1);
+
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(Pin::new(&mut stream).poll_next(&mut cx).is_pending());
+        subject.next(2);
+        subject.completed();
+        assert_eq!(next(&mut stream).map(Result::ok), Some(Some(2)));
+        assert!(next(&mut stream).is_none());
+    }
+
+    #[test]
+    fn dropping_the_stream_stops_the_source() {
+        let mut subject = Subject::<i32>::new();
+        let observer = TestObserver::new();
+        let mut stream = subject.clone().into_stream();
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(Pin::new(&mut stream).poll_next(&mut cx).is_pending());
+        drop(stream);
+
+        subject.clone().subscribe(observer.clone());
+        subject.next(1);
+        subject.next(2);
+        observer.assert_values(&[1, 2]);
+        assert_eq!(subject.observer_count(), 1);
+    }
+
+    #[test]
+    fn into_flow_stream_pulls_one_item_per_poll() {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let counter = pulled.clone();
+        let mut stream = from_iter((0..5).inspect(move |_| { counter.fetch_add(1, Ordering::SeqCst); }))
+            .into_flow_stream();
+        assert_eq!(pulled.load(Ordering::SeqCst), 0);
+        assert_eq!(next(&mut stream).map(Result::ok), Some(Some(0)));
+        assert_eq!(pulled.load(Ordering::SeqCst), 1);
+        assert_eq!(next(&mut stream).map(Result::ok), Some(Some(1)));
+        assert_eq!(pulled.load(Ordering::SeqCst), 2);
+        drop(stream);
+        assert_eq!(pulled.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn from_stream_is_read_once() {
+        let source = from_stream(range(0, 2).into_stream());
+        let first = TestObserver::new();
+        let second = TestObserver::new();
+        source.subscribe(first.clone());
+        source.subscribe(second.clone());
+        assert_eq!(first.value_count(), 2);
+        first.assert_completed();
+        assert_eq!(second.value_count(), 0);
+        second.assert_completed();
+    }
+
+    #[test]
+    fn from_stream_as_a_flowable_polls_only_what_is_requested() {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let counter = pulled.clone();
+        let stream = from_iter((0..5).inspect(move |_| { counter.fetch_add(1, Ordering::SeqCst); }))
+            .into_flow_stream();
+        let subscriber = TestSubscriber::new(2);
+        from_stream(stream).subscribe_flow(subscriber.clone());
+        assert_eq!(subscriber.observer().value_count(), 2);
+        assert_eq!(pulled.load(Ordering::SeqCst), 2);
+        subscriber.observer().assert_not_completed();
+
+        subscriber.request(10);
+        assert_eq!(subscriber.observer().value_count(), 5);
+        subscriber.observer().assert_completed();
+    }
+}
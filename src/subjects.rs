@@ -0,0 +1,645 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::collections::VecDeque;
+use std::mem;
+
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::{Scheduler, TimerScheduler};
+use crate::sync::MutexExt;
+
+//////////////Subject
+// Subscribers are boxed since a subject hands out to any number of differently
+// typed observers
+pub(crate) type BoxedObserver<A> = Box<dyn Observer<Item=A> + Send + Sync>;
+
+#[derive(Clone)]
+pub(crate) enum Terminal {
+    Completed,
+    Failed(Error)
+}
+
+impl Terminal {
+    pub(crate) fn deliver<A>(&self, observer: &mut dyn Observer<Item=A>) {
+        match *self {
+            Terminal::Completed => observer.completed(),
+            Terminal::Failed(ref err) => observer.error(err.clone())
+        }
+    }
+}
+
+// History a subject keeps for late subscribers, bounded by count and/or age.
+// Entries are numbered so a subscriber catching up can tell which ones it has
+// already seen. Ages go by the scheduler's clock.
+pub(crate) struct ReplayBuffer<A> {
+    pub(crate) entries: VecDeque<(u64, Instant, A)>,
+    pub(crate) next_seq: u64,
+    pub(crate) max_count: Option<usize>,
+    pub(crate) max_age: Option<Duration>,
+    pub(crate) scheduler: Arc<dyn Scheduler>
+}
+
+impl<A> ReplayBuffer<A> {
+    fn new(max_count: Option<usize>, max_age: Option<Duration>) -> ReplayBuffer<A> {
+        ReplayBuffer::new_on(max_count, max_age, Arc::new(TimerScheduler))
+    }
+
+    fn new_on(max_count: Option<usize>, max_age: Option<Duration>, scheduler: Arc<dyn Scheduler>) -> ReplayBuffer<A> {
+        ReplayBuffer {entries: VecDeque::new(), next_seq: 0, max_count: max_count, max_age: max_age, scheduler: scheduler}
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.max_count != Some(0)
+    }
+
+    fn push(&mut self, val: A) {
+        let now = self.scheduler.now();
+        self.entries.push_back((self.next_seq, now, val));
+        self.next_seq += 1;
+        if let Some(max_count) = self.max_count {
+            while self.entries.len() > max_count {
+                self.entries.pop_front();
+            }
+        }
+        self.evict(now);
+    }
+
+    // Entries are in arrival order, so expired ones are always at the front
+    fn evict(&mut self, now: Instant) {
+        if let Some(max_age) = self.max_age {
+            while let Some(&(_, time, _)) = self.entries.front() {
+                if now.duration_since(time) <= max_age {
+                    break;
+                }
+                self.entries.pop_front();
+            }
+        }
+    }
+
+    fn latest(&self) -> Option<&A> {
+        self.entries.back().map(|(_, _, val)| val)
+    }
+
+    fn since(&mut self, seq: u64) -> Vec<(u64, A)>
+        where A: Clone {
+        let now = self.scheduler.now();
+        self.evict(now);
+        self.entries.iter()
+            .filter(|&&(s, _, _)| s >= seq)
+            .map(|&(s, _, ref val)| (s, val.clone()))
+            .collect()
+    }
+}
+
+pub(crate) enum Pending<A> {
+    Next(A),
+    Terminate(Terminal)
+}
+
+pub(crate) struct SubjectState<A> {
+    pub(crate) observers: Vec<BoxedObserver<A>>,
+    // Set once the termination has been delivered
+    pub(crate) terminal: Option<Terminal>,
+    // Set as soon as termination is pushed, so nothing's accepted after it
+    pub(crate) closed: bool,
+    pub(crate) pending: VecDeque<Pending<A>>,
+    pub(crate) draining: bool,
+    pub(crate) buffer: ReplayBuffer<A>,
+    // Whether late subscribers still get the history after termination
+    pub(crate) replay_terminated: bool
+}
+
+// A hot source that relays whatever is pushed into it to every current
+// subscriber. Clones share the same subscribers.
+pub struct Subject<A> {
+    pub(crate) state: Arc<Mutex<SubjectState<A>>>
+}
+
+impl<A> Clone for Subject<A> {
+    fn clone(&self) -> Subject<A> {
+        Subject {state: self.state.clone()}
+    }
+}
+
+impl<A> Subject<A> {
+    pub fn new() -> Subject<A> {
+        Subject::with_replay(ReplayBuffer::new(Some(0), None), false)
+    }
+
+    pub(crate) fn with_replay(buffer: ReplayBuffer<A>, replay_terminated: bool) -> Subject<A> {
+        Subject {state: Arc::new(Mutex::new(SubjectState {
+            observers: Vec::new(),
+            terminal: None,
+            closed: false,
+            pending: VecDeque::new(),
+            draining: false,
+            buffer: buffer,
+            replay_terminated: replay_terminated
+        }))}
+    }
+
+    pub fn observer_count(&self) -> usize {
+        self.state.acquire().observers.len()
+    }
+
+    pub(crate) fn dispatch(&self, val: A)
+        where A: Clone {
+        self.push(Pending::Next(val));
+    }
+
+    pub(crate) fn terminate(&self, terminal: Terminal)
+        where A: Clone {
+        self.push(Pending::Terminate(terminal));
+    }
+
+    // Whoever finds the subject idle delivers, and everyone else, whether on
+    // another thread or calling back in from a subscriber, queues what they
+    // have for that drain to deliver in turn. Nothing is dropped and a
+    // subscriber is never called from two places at once.
+    fn push(&self, pending: Pending<A>)
+        where A: Clone {
+        {
+            let mut state = self.state.acquire();
+            if state.closed {
+                return;
+            }
+            if let Pending::Terminate(_) = pending {
+                state.closed = true;
+            }
+            state.pending.push_back(pending);
+            if state.draining {
+                return;
+            }
+            state.draining = true;
+        }
+        self.drain();
+    }
+
+    // Subscribers are called outside the lock, so they're free to subscribe
+    // to this subject themselves. Anyone who subscribes mid-dispatch misses
+    // the value being dispatched, unless it's being kept for replay.
+    fn drain(&self)
+        where A: Clone {
+        loop {
+            let (pending, mut observers) = {
+                let mut state = self.state.acquire();
+                let pending = match state.pending.pop_front() {
+                    Some(pending) => pending,
+                    None => {
+                        state.draining = false;
+                        return;
+                    }
+                };
+
+                // Recorded as it goes out rather than as it's queued, so a
+                // subscriber catching up can't get it twice
+                match pending {
+                    Pending::Next(ref val) if state.buffer.is_enabled() => state.buffer.push(val.clone()),
+                    Pending::Terminate(ref terminal) => state.terminal = Some(terminal.clone()),
+                    _ => {}
+                }
+                (pending, mem::take(&mut state.observers))
+            };
+
+            match pending {
+                Pending::Next(val) => {
+                    observers.retain_mut(|observer| {
+                        match observer.next(val.clone()) {
+                            Continue => true,
+                            Stop => {
+                                observer.completed();
+                                false
+                            }
+                        }
+                    });
+
+                    let mut state = self.state.acquire();
+                    let added = mem::replace(&mut state.observers, observers);
+                    state.observers.extend(added);
+                },
+                Pending::Terminate(terminal) => {
+                    for mut observer in observers {
+                        terminal.deliver(&mut *observer);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<A> Observer for Subject<A>
+    where A: Clone {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.dispatch(val);
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.terminate(Terminal::Completed);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.terminate(Terminal::Failed(err));
+    }
+}
+
+impl<A> Observable for Subject<A>
+    where A: Clone {
+    type Item = A;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        // History is handed over outside the lock, so keep catching up until
+        // nothing new has arrived and the observer can go live
+        let mut seen = 0;
+        loop {
+            let (pending, terminal) = {
+                let mut state = self.state.acquire();
+                let pending = if state.terminal.is_none() || state.replay_terminated {
+                    state.buffer.since(seen)
+                } else {
+                    Vec::new()
+                };
+
+                if !pending.is_empty() {
+                    (pending, None)
+                } else {
+                    match state.terminal {
+                        Some(ref terminal) => (pending, Some(terminal.clone())),
+                        None => return state.observers.push(Box::new(observer))
+                    }
+                }
+            };
+
+            for (seq, val) in pending {
+                seen = seq + 1;
+                if let Stop = observer.next(val) {
+                    return observer.completed();
+                }
+            }
+
+            if let Some(terminal) = terminal {
+                return terminal.deliver(&mut observer);
+            }
+        }
+    }
+}
+//Subject//////////////
+
+//////////////BehaviorSubject
+// A subject that always holds a current value, which new subscribers get
+// before any live emissions. Once terminated, late subscribers only get
+// the termination.
+pub struct BehaviorSubject<A> {
+    pub(crate) subject: Subject<A>
+}
+
+impl<A> Clone for BehaviorSubject<A> {
+    fn clone(&self) -> BehaviorSubject<A> {
+        BehaviorSubject {subject: self.subject.clone()}
+    }
+}
+
+impl<A> BehaviorSubject<A> {
+    pub fn new(initial: A) -> BehaviorSubject<A> {
+        let mut buffer = ReplayBuffer::new(Some(1), None);
+        buffer.push(initial);
+        BehaviorSubject {subject: Subject::with_replay(buffer, false)}
+    }
+
+    pub fn value(&self) -> A
+        where A: Clone {
+        let state = self.subject.state.acquire();
+        state.buffer.latest().cloned().unwrap()
+    }
+
+    pub fn observer_count(&self) -> usize {
+        self.subject.observer_count()
+    }
+}
+
+impl<A> Observer for BehaviorSubject<A>
+    where A: Clone {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.subject.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.subject.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.subject.error(err);
+    }
+}
+
+impl<A> Observable for BehaviorSubject<A>
+    where A: Clone {
+    type Item = A;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.subject.subscribe(observer);
+    }
+}
+//BehaviorSubject//////////////
+
+//////////////ReplaySubject
+// A subject that replays its recorded history to late subscribers, including
+// after it has terminated. History can be bounded by count and by age.
+pub struct ReplaySubject<A> {
+    pub(crate) subject: Subject<A>
+}
+
+impl<A> Clone for ReplaySubject<A> {
+    fn clone(&self) -> ReplaySubject<A> {
+        ReplaySubject {subject: self.subject.clone()}
+    }
+}
+
+impl<A> ReplaySubject<A> {
+    pub fn new(max_count: Option<usize>, max_age: Option<Duration>) -> ReplaySubject<A> {
+        ReplaySubject::new_on(max_count, max_age, TimerScheduler)
+    }
+
+    // Ages are measured on the scheduler's clock
+    pub fn new_on<D>(max_count: Option<usize>, max_age: Option<Duration>, scheduler: D) -> ReplaySubject<A>
+        where D: Scheduler + 'static {
+        ReplaySubject {subject: Subject::with_replay(ReplayBuffer::new_on(max_count, max_age, Arc::new(scheduler)), true)}
+    }
+
+    pub fn unbounded() -> ReplaySubject<A> {
+        ReplaySubject::new(None, None)
+    }
+
+    pub fn with_count(max_count: usize) -> ReplaySubject<A> {
+        ReplaySubject::new(Some(max_count), None)
+    }
+
+    pub fn with_window(max_age: Duration) -> ReplaySubject<A> {
+        ReplaySubject::new(None, Some(max_age))
+    }
+
+    pub fn with_window_on<D>(max_age: Duration, scheduler: D) -> ReplaySubject<A>
+        where D: Scheduler + 'static {
+        ReplaySubject::new_on(None, Some(max_age), scheduler)
+    }
+
+    pub fn observer_count(&self) -> usize {
+        self.subject.observer_count()
+    }
+}
+
+impl<A> Observer for ReplaySubject<A>
+    where A: Clone {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.subject.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.subject.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.subject.error(err);
+    }
+}
+
+impl<A> Observable for ReplaySubject<A>
+    where A: Clone {
+    type Item = A;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.subject.subscribe(observer);
+    }
+}
+//ReplaySubject//////////////
+
+//////////////AsyncSubject
+// A subject that holds on to the last value it receives and only hands it
+// out, followed by completion, once it completes. An error is relayed
+// without any value.
+pub struct AsyncSubject<A> {
+    pub(crate) subject: Subject<A>,
+    pub(crate) last: Arc<Mutex<Option<A>>>
+}
+
+impl<A> Clone for AsyncSubject<A> {
+    fn clone(&self) -> AsyncSubject<A> {
+        AsyncSubject {subject: self.subject.clone(), last: self.last.clone()}
+    }
+}
+
+impl<A> AsyncSubject<A> {
+    pub fn new() -> AsyncSubject<A> {
+        AsyncSubject {
+            // Nothing is recorded until completion, at which point the final
+            // value is kept around for late subscribers
+            subject: Subject::with_replay(ReplayBuffer::new(Some(1), None), true),
+            last: Arc::new(Mutex::new(None))
+        }
+    }
+
+    pub fn observer_count(&self) -> usize {
+        self.subject.observer_count()
+    }
+}
+
+impl<A> Observer for AsyncSubject<A>
+    where A: Clone {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        *self.last.acquire() = Some(val);
+        Continue
+    }
+
+    fn completed(&mut self) {
+        let last = self.last.acquire().take();
+        if let Some(val) = last {
+            self.subject.next(val);
+        }
+        self.subject.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.last.acquire().take();
+        self.subject.error(err);
+    }
+}
+
+impl<A> Observable for AsyncSubject<A>
+    where A: Clone {
+    type Item = A;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        self.subject.subscribe(observer);
+    }
+}
+//AsyncSubject//////////////
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::observable::Observable;
+    use crate::observer::Observer;
+    use crate::subjects::{AsyncSubject, BehaviorSubject, ReplaySubject, Subject};
+    use crate::testing::observer::TestObserver;
+    use crate::testing::scheduler::TestScheduler;
+
+    #[test]
+    fn subject_relays_to_current_subscribers() {
+        let mut subject = Subject::new();
+        let early = TestObserver::new();
+        subject.subscribe(early.clone());
+        subject.next(1);
+
+        let late = TestObserver::new();
+        subject.subscribe(late.clone());
+        subject.next(2);
+        subject.completed();
+
+        early.assert_values(&[1, 2]);
+        early.assert_completed();
+        late.assert_values(&[2]);
+        late.assert_completed();
+        assert_eq!(subject.observer_count(), 0);
+    }
+
+    #[test]
+    fn concurrent_pushes_all_arrive() {
+        let subject = Subject::new();
+        let observer = TestObserver::new();
+        subject.subscribe(observer.clone());
+
+        let threads: Vec<_> = (0..4).map(|_| {
+            let mut subject = subject.clone();
+            thread::spawn(move || {
+                for i in 0..1000 {
+                    subject.next(i);
+                }
+            })
+        }).collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(observer.value_count(), 4000);
+        assert_eq!(subject.observer_count(), 1);
+    }
+
+    #[test]
+    fn completing_from_inside_next_reaches_subscribers() {
+        let subject = Subject::new();
+        let observer = TestObserver::new();
+        let inner = subject.clone();
+        subject.clone().tap(move |_| inner.clone().completed()).subscribe(observer.clone());
+
+        subject.clone().next(1);
+        subject.clone().next(2);
+
+        observer.assert_values(&[1]);
+        observer.assert_completed();
+        assert_eq!(subject.observer_count(), 0);
+    }
+
+    #[test]
+    fn pushing_from_inside_next_keeps_order() {
+        let subject = Subject::new();
+        let observer = TestObserver::new();
+        let inner = subject.clone();
+        subject.clone().tap(move |&val: &i32| {
+            if val < 3 {
+                inner.clone().next(val + 1);
+            }
+        }).subscribe(observer.clone());
+
+        let other = TestObserver::new();
+        subject.subscribe(other.clone());
+        subject.clone().next(0);
+
+        observer.assert_values(&[0, 1, 2, 3]);
+        other.assert_values(&[0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn behavior_subject_starts_with_its_value() {
+        let mut subject = BehaviorSubject::new(0);
+        subject.next(1);
+        let observer = TestObserver::new();
+        subject.subscribe(observer.clone());
+        subject.next(2);
+
+        observer.assert_values(&[1, 2]);
+        assert_eq!(subject.value(), 2);
+    }
+
+    #[test]
+    fn replay_subject_replays_after_termination() {
+        let mut subject = ReplaySubject::with_count(2);
+        subject.next(1);
+        subject.next(2);
+        subject.next(3);
+        subject.completed();
+
+        let observer = TestObserver::new();
+        subject.subscribe(observer.clone());
+        observer.assert_values(&[2, 3]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn replay_window_follows_the_scheduler_clock() {
+        let scheduler = TestScheduler::new();
+        let mut subject = ReplaySubject::with_window_on(Duration::from_secs(10), scheduler.clone());
+        subject.next(1);
+        scheduler.advance_by(Duration::from_secs(6));
+        subject.next(2);
+        scheduler.advance_by(Duration::from_secs(6));
+
+        let observer = TestObserver::new();
+        subject.subscribe(observer.clone());
+        observer.assert_values(&[2]);
+    }
+
+    #[test]
+    fn cache_window_follows_the_scheduler_clock() {
+        let scheduler = TestScheduler::new();
+        let source = Subject::new();
+        let cached = source.clone().cache_with_on(None, Some(Duration::from_secs(10)), scheduler.clone());
+        cached.subscribe(TestObserver::new());
+        source.clone().next(1);
+        scheduler.advance_by(Duration::from_secs(6));
+        source.clone().next(2);
+        scheduler.advance_by(Duration::from_secs(6));
+
+        let observer = TestObserver::new();
+        cached.subscribe(observer.clone());
+        observer.assert_values(&[2]);
+    }
+
+    #[test]
+    fn async_subject_only_sends_the_last_value() {
+        let mut subject = AsyncSubject::new();
+        let observer = TestObserver::new();
+        subject.subscribe(observer.clone());
+        subject.next(1);
+        subject.next(2);
+        observer.assert_values(&[]);
+
+        subject.completed();
+        observer.assert_values(&[2]);
+        observer.assert_completed();
+    }
+}
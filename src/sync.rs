@@ -0,0 +1,47 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+// A panic while a lock is held poisons it, and unwrapping after that turns
+// one failure into a panic in everything else sharing the lock. Locks here
+// aren't held while calling out, so the state behind a poisoned one is as
+// consistent as it would have been; carry on with it instead.
+pub(crate) trait MutexExt<T> {
+    fn acquire(&self) -> MutexGuard<'_, T>;
+    fn acquire_mut(&mut self) -> &mut T;
+}
+
+impl<T> MutexExt<T> for Mutex<T> {
+    #[inline]
+    fn acquire(&self) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[inline]
+    fn acquire_mut(&mut self) -> &mut T {
+        self.get_mut().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+    use std::sync::{Arc, Mutex};
+
+    use crate::sync::MutexExt;
+
+    #[test]
+    fn a_poisoned_lock_is_still_acquired() {
+        let lock = Arc::new(Mutex::new(1));
+        let poisoner = lock.clone();
+        let _ = panic::catch_unwind(move || {
+            let mut guard = poisoner.lock().unwrap();
+            *guard = 2;
+            panic!("while holding the lock");
+        });
+        assert!(lock.is_poisoned());
+        assert_eq!(*lock.acquire(), 2);
+
+        let mut lock = Arc::try_unwrap(lock).unwrap();
+        *lock.acquire_mut() = 3;
+        assert_eq!(*lock.acquire(), 3);
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::fmt;
+use std::error::Error as StdError;
+
+use crate::observable::Observable;
+use crate::observer::Observer;
+use crate::observer::IterationResult::Stop;
+use crate::schedulers::Scheduler;
+use crate::testing::marbles::MARBLE_FRAME;
+use crate::testing::scheduler::TestScheduler;
+use crate::sync::MutexExt;
+
+// Random finite observables for property testing operator laws. Everything is
+// driven by a seed, so a failing case can be replayed with Gen::from_seed.
+pub struct Gen {
+    pub(crate) seed: u64
+}
+
+impl Gen {
+    pub fn from_seed(seed: u64) -> Gen {
+        Gen {seed: seed | 1}
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+
+    // Uniform in [low, high)
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        if high <= low {
+            return low;
+        }
+        low + self.next_u64() % (high - low)
+    }
+
+    pub fn ratio(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.range(0, denominator) < numerator
+    }
+
+    // Up to max_len values, each a random number of frames after the last,
+    // ending in completion or (one time in four) an error
+    pub fn observable<A, F>(&mut self, max_len: usize, mut value: F) -> GeneratedObservable<A>
+        where F: FnMut(&mut Gen) -> A {
+        let len = self.range(0, max_len as u64 + 1);
+        let events = (0..len).map(|_| {
+            let gap = MARBLE_FRAME * self.range(0, 4) as u32;
+            (gap, value(self))
+        }).collect();
+
+        GeneratedObservable {
+            events: Arc::new(events),
+            fails: self.ratio(1, 4),
+            scheduler: None
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GeneratedError;
+
+impl fmt::Display for GeneratedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "generated error")
+    }
+}
+
+impl StdError for GeneratedError {}
+
+// Emits everything synchronously, unless put on a TestScheduler with on(), in
+// which case the generated gaps between items are played out in virtual time
+pub struct GeneratedObservable<A> {
+    pub(crate) events: Arc<Vec<(Duration, A)>>,
+    pub(crate) fails: bool,
+    pub(crate) scheduler: Option<TestScheduler>
+}
+
+impl<A> Clone for GeneratedObservable<A> {
+    fn clone(&self) -> GeneratedObservable<A> {
+        GeneratedObservable {events: self.events.clone(), fails: self.fails, scheduler: self.scheduler.clone()}
+    }
+}
+
+impl<A> GeneratedObservable<A> {
+    pub fn values(&self) -> Vec<A>
+        where A: Clone {
+        self.events.iter().map(|(_, val)| val.clone()).collect()
+    }
+
+    pub fn fails(&self) -> bool {
+        self.fails
+    }
+
+    pub fn on(mut self, scheduler: &TestScheduler) -> GeneratedObservable<A> {
+        self.scheduler = Some(scheduler.clone());
+        self
+    }
+
+    pub(crate) fn terminate(&self, observer: &mut dyn Observer<Item=A>) {
+        if self.fails {
+            observer.error(Arc::new(GeneratedError));
+        } else {
+            observer.completed();
+        }
+    }
+}
+
+impl<A> Observable for GeneratedObservable<A>
+    where A: Clone + Send + Sync + 'static {
+    type Item = A;
+
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let scheduler = match self.scheduler {
+            Some(ref scheduler) => scheduler,
+            None => {
+                for (_, val) in self.events.iter() {
+                    if let Stop = observer.next(val.clone()) {
+                        return observer.completed();
+                    }
+                }
+                return self.terminate(&mut observer);
+            }
+        };
+
+        let observer = Arc::new(Mutex::new((observer, false)));
+        let mut at = Duration::from_secs(0);
+        for &(gap, ref val) in self.events.iter() {
+            at += gap;
+            let observer = observer.clone();
+            let val = val.clone();
+            scheduler.schedule_after(at, Box::new(move || {
+                let mut guard = observer.acquire();
+                let (ref mut observer, ref mut done) = *guard;
+                if !*done {
+                    if let Stop = observer.next(val) {
+                        *done = true;
+                        observer.completed();
+                    }
+                }
+            }));
+        }
+
+        let this = self.clone();
+        scheduler.schedule_after(at, Box::new(move || {
+            let mut guard = observer.acquire();
+            let (ref mut observer, ref mut done) = *guard;
+            if !*done {
+                *done = true;
+                this.terminate(observer);
+            }
+        }));
+    }
+}
+
+// Runs the property against freshly seeded generators, panicking with the
+// seed of the first case that fails
+pub fn check_property<F>(cases: usize, mut property: F)
+    where F: FnMut(&mut Gen) -> bool {
+    let base = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0);
+
+    for case in 0..cases as u64 {
+        let seed = base.wrapping_add(case.wrapping_mul(0x9e3779b97f4a7c15));
+        if !property(&mut Gen::from_seed(seed)) {
+            panic!("property failed for seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::marker::PhantomData;
+use std::fmt;
+use std::error::Error as StdError;
+
+use crate::observable::Observable;
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::{Stop, Continue};
+use crate::schedulers::Scheduler;
+use crate::subjects::Subject;
+use crate::operators::materialize::Notification;
+use crate::testing::scheduler::TestScheduler;
+use crate::sync::MutexExt;
+
+// Marble diagrams describe a stream one frame per character:
+//   -      nothing happens this frame
+//   a      a value (any other character)
+//   |      completion
+//   #      an error
+//   (ab)   several events in the same frame
+//   ^      the frame the subscription starts on, for hot sources
+// Spaces are ignored so diagrams can be lined up.
+pub const MARBLE_FRAME: Duration = Duration::from_millis(10);
+
+pub type MarbleEvents<A> = Vec<(usize, Notification<A, ()>)>;
+
+#[derive(Debug)]
+pub struct MarbleError;
+
+impl fmt::Display for MarbleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "marble error")
+    }
+}
+
+impl StdError for MarbleError {}
+
+// Returns the events by frame along with the frame the subscription starts on
+pub fn parse_marbles(marbles: &str) -> (usize, MarbleEvents<char>) {
+    let mut events = Vec::new();
+    let mut frame = 0;
+    let mut start = 0;
+    let mut group = false;
+
+    for c in marbles.chars().filter(|c| !c.is_whitespace()) {
+        match c {
+            '(' => group = true,
+            ')' => {
+                group = false;
+                frame += 1;
+            },
+            '^' => start = frame,
+            '-' => {},
+            '|' => events.push((frame, Notification::Completed)),
+            '#' => events.push((frame, Notification::Error(()))),
+            c => events.push((frame, Notification::Next(c)))
+        }
+
+        if !group && c != ')' {
+            frame += 1;
+        }
+    }
+
+    (start, events)
+}
+
+pub fn render_marbles(events: &MarbleEvents<String>) -> String {
+    let frames = events.last().map_or(0, |&(frame, _)| frame + 1);
+    let mut marbles = String::new();
+
+    for frame in 0..frames {
+        let symbols: Vec<String> = events.iter()
+            .filter(|&&(f, _)| f == frame)
+            .map(|(_, event)| match *event {
+                Notification::Next(ref val) => val.clone(),
+                Notification::Error(()) => "#".to_string(),
+                Notification::Completed => "|".to_string()
+            })
+            .collect();
+
+        match symbols.len() {
+            0 => marbles.push('-'),
+            1 => marbles.push_str(&symbols[0]),
+            _ => marbles.push_str(&format!("({})", symbols.concat()))
+        }
+    }
+    marbles
+}
+
+// Plays the diagram from the start for every subscriber
+#[derive(Clone)]
+pub struct ColdObservable {
+    pub(crate) scheduler: TestScheduler,
+    pub(crate) events: Arc<MarbleEvents<char>>
+}
+
+impl Observable for ColdObservable {
+    type Item = char;
+
+    fn subscribe<N>(&self, observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let observer = Arc::new(Mutex::new((observer, false)));
+
+        for &(frame, ref event) in self.events.iter() {
+            let observer = observer.clone();
+            let event = event.clone();
+            self.scheduler.schedule_after(MARBLE_FRAME * frame as u32, Box::new(move || {
+                let mut guard = observer.acquire();
+                let (ref mut observer, ref mut done) = *guard;
+                if *done {
+                    return;
+                }
+
+                match event {
+                    Notification::Next(val) => {
+                        if let Stop = observer.next(val) {
+                            *done = true;
+                            observer.completed();
+                        }
+                    },
+                    Notification::Error(()) => {
+                        *done = true;
+                        observer.error(Arc::new(MarbleError));
+                    },
+                    Notification::Completed => {
+                        *done = true;
+                        observer.completed();
+                    }
+                }
+            }));
+        }
+    }
+}
+
+pub fn cold(scheduler: &TestScheduler, marbles: &str) -> ColdObservable {
+    ColdObservable {scheduler: scheduler.clone(), events: Arc::new(parse_marbles(marbles).1)}
+}
+
+// Plays the diagram once, with the ^ frame at the scheduler's current time.
+// Anything before it has already happened and is never seen.
+pub fn hot(scheduler: &TestScheduler, marbles: &str) -> Subject<char> {
+    let subject = Subject::new();
+    let (start, events) = parse_marbles(marbles);
+
+    for (frame, event) in events.into_iter().filter(|&(frame, _)| frame >= start) {
+        let mut subject = subject.clone();
+        scheduler.schedule_after(MARBLE_FRAME * (frame - start) as u32, Box::new(move || {
+            match event {
+                Notification::Next(val) => {
+                    subject.next(val);
+                },
+                Notification::Error(()) => subject.error(Arc::new(MarbleError)),
+                Notification::Completed => subject.completed()
+            }
+        }));
+    }
+    subject
+}
+
+pub(crate) struct MarbleRecorder<A> {
+    pub(crate) scheduler: TestScheduler,
+    pub(crate) start: Duration,
+    pub(crate) events: Arc<Mutex<MarbleEvents<String>>>,
+    pub(crate) _marker: PhantomData<A>
+}
+
+impl<A> MarbleRecorder<A> {
+    fn record(&mut self, event: Notification<String, ()>) {
+        let frame = (self.scheduler.clock() - self.start).as_nanos() / MARBLE_FRAME.as_nanos();
+        self.events.acquire().push((frame as usize, event));
+    }
+}
+
+impl<A> Observer for MarbleRecorder<A>
+    where A: fmt::Display {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.record(Notification::Next(val.to_string()));
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.record(Notification::Completed);
+    }
+
+    fn error(&mut self, _: Error) {
+        self.record(Notification::Error(()));
+    }
+}
+
+// Subscribes now, runs the scheduler over the frames the expected diagram
+// covers and panics if what came out doesn't match it
+pub fn assert_marbles<S>(scheduler: &TestScheduler, source: S, expected: &str)
+    where S: Observable,
+          S::Item: fmt::Display + Send + Sync + 'static {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let start = scheduler.clock();
+    source.subscribe(MarbleRecorder {
+        scheduler: scheduler.clone(),
+        start: start,
+        events: events.clone(),
+        _marker: PhantomData
+    });
+
+    let frames = expected.chars().filter(|c| !c.is_whitespace()).count();
+    scheduler.advance_to(start + MARBLE_FRAME * frames as u32);
+
+    let expected: MarbleEvents<String> = parse_marbles(expected).1.into_iter()
+        .map(|(frame, event)| (frame, match event {
+            Notification::Next(val) => Notification::Next(val.to_string()),
+            Notification::Error(()) => Notification::Error(()),
+            Notification::Completed => Notification::Completed
+        }))
+        .collect();
+    let actual = events.acquire().clone();
+
+    if actual != expected {
+        panic!("marbles differ\n expected: {}\n   actual: {}", render_marbles(&expected), render_marbles(&actual));
+    }
+}
@@ -0,0 +1,8 @@
+// Helpers for testing pipelines: a virtual clock, marble diagrams and a
+// recording observer. The property test generators sit behind the testing
+// feature.
+#[cfg(feature = "testing")]
+pub mod generators;
+pub mod marbles;
+pub mod observer;
+pub mod scheduler;
@@ -0,0 +1,211 @@
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use std::fmt;
+
+use crate::flow::{FlowSubscription, Subscriber};
+use crate::observer::{Error, IterationResult, Observer};
+use crate::observer::IterationResult::Continue;
+use crate::operators::materialize::Notification;
+use crate::schedulers::{Scheduler, TimerScheduler};
+use crate::sync::MutexExt;
+
+pub(crate) struct TestRecord<A> {
+    pub(crate) events: Vec<(Instant, Notification<A, Error>)>,
+    pub(crate) terminated: bool
+}
+
+// Records everything it's sent, with the time it arrived, for asserting on
+// afterwards. Subscribe a clone and keep the original to inspect.
+pub struct TestObserver<A> {
+    pub(crate) record: Arc<(Mutex<TestRecord<A>>, Condvar)>,
+    pub(crate) scheduler: Arc<dyn Scheduler>
+}
+
+impl<A> Clone for TestObserver<A> {
+    fn clone(&self) -> TestObserver<A> {
+        TestObserver {record: self.record.clone(), scheduler: self.scheduler.clone()}
+    }
+}
+
+impl<A> TestObserver<A> {
+    pub fn new() -> TestObserver<A> {
+        TestObserver {
+            record: Arc::new((Mutex::new(TestRecord {events: Vec::new(), terminated: false}), Condvar::new())),
+            scheduler: Arc::new(TimerScheduler)
+        }
+    }
+
+    // Times events by the scheduler's clock, such as a TestScheduler's
+    // virtual one
+    pub fn on<D>(mut self, scheduler: D) -> TestObserver<A>
+        where D: Scheduler + 'static {
+        self.scheduler = Arc::new(scheduler);
+        self
+    }
+
+    fn push(&self, event: Notification<A, Error>) {
+        let (ref record, ref condvar) = *self.record;
+        let mut record = record.acquire();
+        match event {
+            Notification::Next(_) => {},
+            _ => {
+                record.terminated = true;
+                condvar.notify_all();
+            }
+        }
+        record.events.push((self.scheduler.now(), event));
+    }
+
+    pub fn events(&self) -> Vec<(Instant, Notification<A, Error>)>
+        where A: Clone {
+        self.record.0.acquire().events.clone()
+    }
+
+    pub fn values(&self) -> Vec<A>
+        where A: Clone {
+        self.record.0.acquire().events.iter().filter_map(|(_, event)| match *event {
+            Notification::Next(ref val) => Some(val.clone()),
+            _ => None
+        }).collect()
+    }
+
+    pub fn value_count(&self) -> usize {
+        self.record.0.acquire().events.iter().filter(|&(_, event)| matches!(*event, Notification::Next(_))).count()
+    }
+
+    pub fn completion_count(&self) -> usize {
+        self.record.0.acquire().events.iter().filter(|&(_, event)| matches!(*event, Notification::Completed)).count()
+    }
+
+    pub fn errors(&self) -> Vec<Error> {
+        self.record.0.acquire().events.iter().filter_map(|(_, event)| match *event {
+            Notification::Error(ref err) => Some(err.clone()),
+            _ => None
+        }).collect()
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.record.0.acquire().terminated
+    }
+
+    // Blocks until the observer completes or errors, giving up after the
+    // timeout. Returns whether it terminated.
+    pub fn await_terminal_event(&self, timeout: Duration) -> bool {
+        let (ref record, ref condvar) = *self.record;
+        let record = record.acquire();
+        let (record, _) = condvar.wait_timeout_while(record, timeout, |record| !record.terminated).unwrap_or_else(PoisonError::into_inner);
+        record.terminated
+    }
+
+    pub fn assert_values(&self, expected: &[A])
+        where A: Clone + PartialEq + fmt::Debug {
+        let values = self.values();
+        assert!(values == expected, "expected values {:?}, got {:?}", expected, values);
+    }
+
+    pub fn assert_completed(&self) {
+        let count = self.completion_count();
+        assert!(count == 1, "expected one completion, got {}", count);
+    }
+
+    pub fn assert_not_completed(&self) {
+        let count = self.completion_count();
+        assert!(count == 0, "expected no completion, got {}", count);
+    }
+
+    pub fn assert_error(&self) {
+        let count = self.errors().len();
+        assert!(count == 1, "expected one error, got {}", count);
+    }
+
+    pub fn assert_no_errors(&self) {
+        let errors = self.errors();
+        assert!(errors.is_empty(), "expected no errors, got {}", errors.iter().map(|err| err.to_string()).collect::<Vec<String>>().join(", "));
+    }
+
+    pub fn assert_terminated(&self) {
+        let count = self.completion_count() + self.errors().len();
+        assert!(count == 1, "expected one terminal event, got {}", count);
+    }
+}
+
+impl<A> Observer for TestObserver<A> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.push(Notification::Next(val));
+        Continue
+    }
+
+    fn completed(&mut self) {
+        self.push(Notification::Completed);
+    }
+
+    fn error(&mut self, err: Error) {
+        self.push(Notification::Error(err));
+    }
+}
+
+// A TestObserver for flows, which asks for the initial demand when it's
+// subscribed and for more whenever the test says
+pub struct TestSubscriber<A> {
+    pub(crate) observer: TestObserver<A>,
+    pub(crate) initial: u64,
+    pub(crate) subscription: Arc<Mutex<Option<FlowSubscription>>>
+}
+
+impl<A> Clone for TestSubscriber<A> {
+    fn clone(&self) -> TestSubscriber<A> {
+        TestSubscriber {observer: self.observer.clone(), initial: self.initial, subscription: self.subscription.clone()}
+    }
+}
+
+impl<A> TestSubscriber<A> {
+    pub fn new(initial: u64) -> TestSubscriber<A> {
+        TestSubscriber {observer: TestObserver::new(), initial: initial, subscription: Arc::new(Mutex::new(None))}
+    }
+
+    // What's been received, to assert on
+    pub fn observer(&self) -> &TestObserver<A> {
+        &self.observer
+    }
+
+    pub fn request(&self, n: u64) {
+        let subscription = self.subscription.acquire().clone();
+        if let Some(subscription) = subscription {
+            subscription.request(n);
+        }
+    }
+
+    pub fn cancel(&self) {
+        let subscription = self.subscription.acquire().take();
+        if let Some(subscription) = subscription {
+            subscription.cancel();
+        }
+    }
+}
+
+impl<A> Observer for TestSubscriber<A> {
+    type Item = A;
+
+    fn next(&mut self, val: Self::Item) -> IterationResult {
+        self.observer.next(val)
+    }
+
+    fn completed(&mut self) {
+        self.observer.completed();
+    }
+
+    fn error(&mut self, err: Error) {
+        self.observer.error(err);
+    }
+}
+
+impl<A> Subscriber for TestSubscriber<A> {
+    fn on_subscribe(&mut self, subscription: FlowSubscription) {
+        *self.subscription.acquire() = Some(subscription.clone());
+        if self.initial > 0 {
+            subscription.request(self.initial);
+        }
+    }
+}
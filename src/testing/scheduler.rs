@@ -0,0 +1,113 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+
+use crate::observer::IterationResult;
+use crate::observer::IterationResult::Continue;
+use crate::schedulers::{Scheduler, TimerEntry};
+use crate::schedulers::thread_pool::Task;
+use crate::sync::MutexExt;
+
+pub(crate) struct TestState {
+    pub(crate) clock: Duration,
+    pub(crate) entries: BinaryHeap<Reverse<TimerEntry>>,
+    pub(crate) seq: u64
+}
+
+// A virtual clock that only moves when told to. Nothing runs until the clock
+// is advanced (or trigger_actions is called), and then everything that has
+// come due runs in order on the calling thread, so time based operators can
+// be tested instantly and deterministically.
+#[derive(Clone)]
+pub struct TestScheduler {
+    pub(crate) origin: Instant,
+    pub(crate) state: Arc<Mutex<TestState>>
+}
+
+impl TestScheduler {
+    pub fn new() -> TestScheduler {
+        TestScheduler {
+            origin: Instant::now(),
+            state: Arc::new(Mutex::new(TestState {clock: Duration::from_secs(0), entries: BinaryHeap::new(), seq: 0}))
+        }
+    }
+
+    // Virtual time since the scheduler was made
+    pub fn clock(&self) -> Duration {
+        self.state.acquire().clock
+    }
+
+    pub fn advance_by(&self, delay: Duration) {
+        let target = self.clock() + delay;
+        self.advance_to(target);
+    }
+
+    // Runs everything due up to the given virtual time, with the clock set to
+    // each task's due time while it runs. Time never goes backwards.
+    pub fn advance_to(&self, target: Duration) {
+        loop {
+            let entry = {
+                let mut state = self.state.acquire();
+                let target = target.max(state.clock);
+                let due = match state.entries.peek() {
+                    Some(entry) => entry.0.deadline <= self.origin + target,
+                    None => false
+                };
+                if !due {
+                    state.clock = target;
+                    return;
+                }
+
+                let entry = state.entries.pop().unwrap().0;
+                state.clock = state.clock.max(entry.deadline - self.origin);
+                entry
+            };
+
+            (entry.task)();
+        }
+    }
+
+    // Runs whatever is due without moving the clock
+    pub fn trigger_actions(&self) {
+        let now = self.clock();
+        self.advance_to(now);
+    }
+
+    pub(crate) fn enqueue(&self, deadline: Instant, task: Task) {
+        let mut state = self.state.acquire();
+        let seq = state.seq;
+        state.seq += 1;
+        state.entries.push(Reverse(TimerEntry {deadline: deadline, seq: seq, task: task}));
+    }
+}
+
+pub(crate) fn schedule_test_tick(scheduler: TestScheduler, deadline: Instant, period: Duration, mut task: Box<dyn FnMut() -> IterationResult + Send>) {
+    let next = scheduler.clone();
+    scheduler.enqueue(deadline, Box::new(move || {
+        if let Continue = task() {
+            schedule_test_tick(next, deadline + period, period, task);
+        }
+    }));
+}
+
+impl Scheduler for TestScheduler {
+    fn now(&self) -> Instant {
+        self.origin + self.clock()
+    }
+
+    fn schedule(&self, task: Box<dyn FnOnce() + Send>) {
+        let now = self.now();
+        self.enqueue(now, task);
+    }
+
+    fn schedule_after(&self, delay: Duration, task: Box<dyn FnOnce() + Send>) {
+        let deadline = self.now() + delay;
+        self.enqueue(deadline, task);
+    }
+
+    fn schedule_periodic(&self, delay: Duration, period: Duration, task: Box<dyn FnMut() -> IterationResult + Send>) {
+        let deadline = self.now() + delay;
+        schedule_test_tick(self.clone(), deadline, period, task);
+    }
+}
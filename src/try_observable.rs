@@ -0,0 +1,133 @@
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use core::marker::PhantomData;
+
+use crate::observable::Observable;
+use crate::observer::Error;
+use crate::operators::lift::LiftObservable;
+#[cfg(feature = "std")]
+use crate::operators::merge::MergeAllObservable;
+use crate::operators::result::{AndThen, FilterOk, MapErr, MapOk, TryObservableOf, TryOperator};
+#[cfg(feature = "std")]
+use crate::operators::result::{TryFlatMap, TryFlatMapObservable};
+
+// Combinators for streams of Results, which work on the Ok or Err side so
+// their closures needn't match on every item. These are about the items;
+// failures of the stream itself still go through error().
+pub trait TryObservable<E = Error>: Observable<E, Item=Result<Self::Ok, Self::Err>> {
+    type Ok;
+    type Err;
+
+    #[inline]
+    fn map_ok<B, F>(self, f: F) -> TryObservableOf<MapOk<F>, Self>
+        where F: Fn(Self::Ok) -> B,
+              Self: Sized {
+        self.try_lift(MapOk {f: f})
+    }
+
+    #[inline]
+    fn map_err<Y, F>(self, f: F) -> TryObservableOf<MapErr<F>, Self>
+        where F: Fn(Self::Err) -> Y,
+              Self: Sized {
+        self.try_lift(MapErr {f: f})
+    }
+
+    #[inline]
+    fn and_then<B, F>(self, f: F) -> TryObservableOf<AndThen<F>, Self>
+        where F: Fn(Self::Ok) -> Result<B, Self::Err>,
+              Self: Sized {
+        self.try_lift(AndThen {f: f})
+    }
+
+    #[inline]
+    fn filter_ok<P>(self, predicate: P) -> TryObservableOf<FilterOk<P>, Self>
+        where P: Fn(&Self::Ok) -> bool,
+              Self: Sized {
+        self.try_lift(FilterOk {predicate: predicate})
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn try_flat_map<U, F>(self, f: F) -> TryFlatMapObservable<F, Self, U, Self::Err>
+        where F: Fn(Self::Ok) -> U,
+              Self: Sized {
+        MergeAllObservable {source: self.try_lift(TryFlatMap {f: f}), _marker: PhantomData}
+    }
+
+    #[inline]
+    fn try_lift<G>(self, step: G) -> TryObservableOf<G, Self>
+        where Self: Sized {
+        LiftObservable {operator: TryOperator {step: Arc::new(step)}, source: self}
+    }
+}
+
+impl<S, T, X, E> TryObservable<E> for S
+    where S: Observable<E, Item=Result<T, X>> {
+    type Ok = T;
+    type Err = X;
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::observable::Observable;
+    use crate::sources::{from_iter, range};
+    use crate::testing::observer::TestObserver;
+    use crate::try_observable::TryObservable;
+
+    fn results() -> Vec<Result<i32, &'static str>> {
+        vec![Ok(1), Err("bad"), Ok(2), Ok(3)]
+    }
+
+    #[test]
+    fn map_ok_leaves_err_items_alone() {
+        let observer = TestObserver::new();
+        from_iter(results()).map_ok(|val| val * 10).subscribe(observer.clone());
+        observer.assert_values(&[Ok(10), Err("bad"), Ok(20), Ok(30)]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn map_err_leaves_ok_items_alone() {
+        let observer = TestObserver::new();
+        from_iter(results()).map_err(|err| err.len()).subscribe(observer.clone());
+        observer.assert_values(&[Ok(1), Err(3), Ok(2), Ok(3)]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn and_then_can_turn_ok_items_into_errs() {
+        let observer = TestObserver::new();
+        from_iter(results())
+            .and_then(|val| if val == 2 { Err("two") } else { Ok(val) })
+            .subscribe(observer.clone());
+        observer.assert_values(&[Ok(1), Err("bad"), Err("two"), Ok(3)]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn filter_ok_always_passes_err_items() {
+        let observer = TestObserver::new();
+        from_iter(results()).filter_ok(|val| *val != 2).subscribe(observer.clone());
+        observer.assert_values(&[Ok(1), Err("bad"), Ok(3)]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn try_flat_map_flattens_ok_items_and_passes_errs_on() {
+        let observer = TestObserver::new();
+        from_iter(results())
+            .try_flat_map(|val| range(0, val).map(Ok))
+            .subscribe(observer.clone());
+        observer.assert_values(&[Ok(0), Err("bad"), Ok(0), Ok(1), Ok(0), Ok(1), Ok(2)]);
+        observer.assert_completed();
+    }
+
+    #[test]
+    fn err_items_dont_end_the_stream() {
+        let observer = TestObserver::new();
+        from_iter(results()).map_ok(|val| val + 1).take(3).subscribe(observer.clone());
+        observer.assert_values(&[Ok(2), Err("bad"), Ok(3)]);
+        observer.assert_completed();
+        observer.assert_no_errors();
+    }
+}
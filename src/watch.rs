@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::channel;
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use notify::event::{ModifyKind, RenameMode};
+
+use crate::observable::Observable;
+use crate::observer::Observer;
+use crate::observer::IterationResult::Stop;
+use crate::schedulers::Scheduler;
+use crate::schedulers::new_thread::NewThreadScheduler;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FsEventKind {
+    Created,
+    Modified,
+    Removed
+}
+
+// One change to one path. A rename is seen as the old path removed and the
+// new one created.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FsEvent {
+    pub kind: FsEventKind,
+    pub path: PathBuf
+}
+
+// Editors and build tools tend to touch a file several times per save, so
+// this is usually followed by debounce, or group_by on the path with a
+// debounce per group.
+pub struct WatchObservable<D> {
+    pub(crate) path: PathBuf,
+    pub(crate) recursive: bool,
+    pub(crate) scheduler: D
+}
+
+impl<D> WatchObservable<D> {
+    // Whether directories under the path are watched too, which they are by
+    // default
+    pub fn recursive(mut self, recursive: bool) -> WatchObservable<D> {
+        self.recursive = recursive;
+        self
+    }
+}
+
+impl<D> Observable for WatchObservable<D>
+    where D: Scheduler {
+    type Item = FsEvent;
+
+    // Each subscriber gets a watcher of its own, on the scheduler, dropped
+    // with the first event after the downstream stops
+    fn subscribe<N>(&self, mut observer: N)
+        where N: Observer<Item=Self::Item> + Send + Sync + 'static {
+        let path = self.path.clone();
+        let mode = if self.recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+
+        self.scheduler.schedule(Box::new(move || {
+            let (sender, receiver) = channel();
+            let mut watcher = match notify::recommended_watcher(sender) {
+                Ok(watcher) => watcher,
+                Err(err) => return observer.error(Arc::new(err))
+            };
+            if let Err(err) = watcher.watch(&path, mode) {
+                return observer.error(Arc::new(err));
+            }
+
+            for event in receiver.iter() {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(err) => return observer.error(Arc::new(err))
+                };
+
+                for (kind, path) in changes(event.kind, event.paths) {
+                    if let Stop = observer.next(FsEvent {kind: kind, path: path}) {
+                        return;
+                    }
+                }
+            }
+            observer.completed();
+        }));
+    }
+}
+
+// What notify reports, narrowed down. Reads aren't changes, so they're left
+// out along with whatever the platform couldn't classify.
+fn changes(kind: EventKind, paths: Vec<PathBuf>) -> Vec<(FsEventKind, PathBuf)> {
+    let kind = match kind {
+        EventKind::Create(_) => FsEventKind::Created,
+        EventKind::Remove(_) => FsEventKind::Removed,
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => FsEventKind::Removed,
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => FsEventKind::Created,
+        // Follows the From and To halves, which have been reported already
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => return Vec::new(),
+        EventKind::Modify(_) | EventKind::Any => FsEventKind::Modified,
+        EventKind::Access(_) | EventKind::Other => return Vec::new()
+    };
+    paths.into_iter().map(|path| (kind, path)).collect()
+}
+
+pub fn watch_path<P>(path: P) -> WatchObservable<NewThreadScheduler>
+    where P: AsRef<Path> {
+    watch_path_on(path, NewThreadScheduler::io())
+}
+
+pub fn watch_path_on<P, D>(path: P, scheduler: D) -> WatchObservable<D>
+    where P: AsRef<Path>,
+          D: Scheduler {
+    WatchObservable {path: path.as_ref().to_path_buf(), recursive: true, scheduler: scheduler}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use notify::EventKind;
+    use notify::event::{AccessKind, CreateKind, DataChange, ModifyKind, RemoveKind, RenameMode};
+
+    use crate::observable::Observable;
+    use crate::testing::observer::TestObserver;
+    use crate::watch::{changes, watch_path, FsEvent, FsEventKind};
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rx-watch-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // The watcher's set up on another thread, so keep poking until it notices
+    fn poke_until<F, P>(observer: &TestObserver<FsEvent>, mut poke: F, seen: P) -> bool
+        where F: FnMut(),
+              P: Fn(&[FsEvent]) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            poke();
+            thread::sleep(Duration::from_millis(20));
+            if seen(&observer.values()) {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn changes_narrows_notifys_events_down() {
+        let path = || vec![PathBuf::from("a")];
+        assert_eq!(changes(EventKind::Create(CreateKind::File), path()), vec![(FsEventKind::Created, PathBuf::from("a"))]);
+        assert_eq!(changes(EventKind::Remove(RemoveKind::Any), path()), vec![(FsEventKind::Removed, PathBuf::from("a"))]);
+        assert_eq!(changes(EventKind::Modify(ModifyKind::Data(DataChange::Content)), path()), vec![(FsEventKind::Modified, PathBuf::from("a"))]);
+        assert_eq!(changes(EventKind::Modify(ModifyKind::Name(RenameMode::From)), path()), vec![(FsEventKind::Removed, PathBuf::from("a"))]);
+        assert_eq!(changes(EventKind::Modify(ModifyKind::Name(RenameMode::To)), path()), vec![(FsEventKind::Created, PathBuf::from("a"))]);
+        assert!(changes(EventKind::Modify(ModifyKind::Name(RenameMode::Both)), path()).is_empty());
+        assert!(changes(EventKind::Access(AccessKind::Any), path()).is_empty());
+        assert!(changes(EventKind::Other, path()).is_empty());
+    }
+
+    #[test]
+    fn watch_path_sees_files_created_and_removed() {
+        let dir = scratch_dir("changes");
+        let file = dir.join("probe");
+        let observer = TestObserver::new();
+        watch_path(&dir).subscribe(observer.clone());
+
+        assert!(poke_until(&observer, || fs::write(&file, "x").unwrap(), |events| {
+            events.iter().any(|event| event.path == file && event.kind != FsEventKind::Removed)
+        }));
+        fs::remove_file(&file).unwrap();
+        assert!(poke_until(&observer, || {}, |events| {
+            events.contains(&FsEvent {kind: FsEventKind::Removed, path: file.clone()})
+        }));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn watch_path_stops_with_the_downstream() {
+        let dir = scratch_dir("stop");
+        let observer = TestObserver::new();
+        watch_path(&dir).take(1).subscribe(observer.clone());
+
+        let mut count = 0;
+        assert!(poke_until(&observer, || {
+            count += 1;
+            fs::write(dir.join(format!("file{}", count)), "x").unwrap();
+        }, |events| !events.is_empty()));
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        assert_eq!(observer.value_count(), 1);
+        observer.assert_completed();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn watching_a_missing_path_fails() {
+        let observer = TestObserver::new();
+        watch_path(std::env::temp_dir().join("rx-watch-not-there")).subscribe(observer.clone());
+        assert!(observer.await_terminal_event(Duration::from_secs(5)));
+        observer.assert_error();
+    }
+}